@@ -0,0 +1,84 @@
+//! `cosmwasm-std` integration of the Composable bindings.
+//!
+//! A contract using the bindings is generic over [`ComposableQuery`] and [`ComposableMsg`]:
+//! ```ignore
+//! #[entry_point]
+//! pub fn execute(
+//!     deps: DepsMut<ComposableQuery>,
+//!     env: Env,
+//!     info: MessageInfo,
+//!     msg: ExecuteMsg,
+//! ) -> StdResult<Response<ComposableMsg>> {
+//!     let price = ComposableQuerier::new(&deps.querier).price("1", 1_000_000_000_000)?;
+//!     ...
+//! }
+//! ```
+use crate::{
+	AssetMetadataResponse, AssetsQuery, Coin, ComposableMsg, ComposableQuery, OracleQuery,
+	PabloQuery, PriceResponse, SwapResponse, TwapResponse,
+};
+use cosmwasm_std::{CosmosMsg, CustomMsg, CustomQuery, QuerierWrapper, StdResult};
+
+impl CustomQuery for ComposableQuery {}
+
+impl CustomMsg for ComposableMsg {}
+
+impl From<ComposableMsg> for CosmosMsg<ComposableMsg> {
+	fn from(msg: ComposableMsg) -> Self {
+		CosmosMsg::Custom(msg)
+	}
+}
+
+/// Typed wrapper around a querier able to issue [`ComposableQuery`].
+pub struct ComposableQuerier<'a> {
+	querier: &'a QuerierWrapper<'a, ComposableQuery>,
+}
+
+impl<'a> ComposableQuerier<'a> {
+	pub fn new(querier: &'a QuerierWrapper<'a, ComposableQuery>) -> Self {
+		ComposableQuerier { querier }
+	}
+
+	pub fn price(&self, denom: impl Into<String>, amount: u128) -> StdResult<PriceResponse> {
+		self.querier.query(
+			&ComposableQuery::Oracle(OracleQuery::Price {
+				denom: denom.into(),
+				amount: amount.into(),
+			})
+			.into(),
+		)
+	}
+
+	pub fn twap(&self, denom: impl Into<String>, amount: u128) -> StdResult<TwapResponse> {
+		self.querier.query(
+			&ComposableQuery::Oracle(OracleQuery::Twap {
+				denom: denom.into(),
+				amount: amount.into(),
+			})
+			.into(),
+		)
+	}
+
+	pub fn spot_price(
+		&self,
+		pool_id: u128,
+		base_asset: Coin,
+		quote_denom: impl Into<String>,
+		calculate_with_fees: bool,
+	) -> StdResult<SwapResponse> {
+		self.querier.query(
+			&ComposableQuery::Pablo(PabloQuery::SpotPrice {
+				pool_id: pool_id.into(),
+				base_asset,
+				quote_denom: quote_denom.into(),
+				calculate_with_fees,
+			})
+			.into(),
+		)
+	}
+
+	pub fn asset_metadata(&self, denom: impl Into<String>) -> StdResult<AssetMetadataResponse> {
+		self.querier
+			.query(&ComposableQuery::Assets(AssetsQuery::Metadata { denom: denom.into() }).into())
+	}
+}
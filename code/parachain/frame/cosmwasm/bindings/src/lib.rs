@@ -0,0 +1,102 @@
+//! Custom CosmWasm bindings to the Composable pallets.
+//!
+//! Contracts running on `pallet-cosmwasm` can use [`ComposableQuery`] as their `CustomQuery` and
+//! [`ComposableMsg`] as their `CustomMsg` to read oracle prices and asset metadata, quote and swap
+//! on Pablo pools, stake in the staking rewards pools and dispatch XCM to other chains.
+//!
+//! Assets are referred to by their cosmwasm denom, i.e. the same denom used in `BankMsg` and
+//! `Coin`. Amounts are serialized as strings, the same way `Uint128` is.
+//!
+//! Enable the `cosmwasm` feature from a contract to get the `cosmwasm-std` integration, including
+//! the typed [`cosmwasm::ComposableQuerier`].
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+#[cfg(feature = "cosmwasm")]
+pub mod cosmwasm;
+mod msg;
+mod query;
+
+pub use crate::{msg::*, query::*};
+use alloc::string::{String, ToString};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// A value serialized as a string, mostly used for 128 bits integers that JSON can't represent.
+#[cfg_attr(feature = "std", derive(schemars::JsonSchema))]
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Debug, Serialize, Deserialize)]
+#[repr(transparent)]
+pub struct Displayed<T>(
+	#[serde(bound(serialize = "T: core::fmt::Display"))]
+	#[serde(serialize_with = "serialize_as_string")]
+	#[serde(bound(deserialize = "T: core::str::FromStr"))]
+	#[serde(deserialize_with = "deserialize_from_string")]
+	pub T,
+);
+
+fn serialize_as_string<S: Serializer, T: core::fmt::Display>(
+	t: &T,
+	serializer: S,
+) -> Result<S::Ok, S::Error> {
+	serializer.serialize_str(&t.to_string())
+}
+
+fn deserialize_from_string<'de, D: Deserializer<'de>, T: core::str::FromStr>(
+	deserializer: D,
+) -> Result<T, D::Error> {
+	let s = String::deserialize(deserializer)?;
+	s.parse::<T>().map_err(|_| serde::de::Error::custom("Parse from string failed"))
+}
+
+impl<T> From<T> for Displayed<T> {
+	fn from(x: T) -> Self {
+		Displayed(x)
+	}
+}
+
+/// An amount of an asset, serialized like a cosmwasm `Coin`.
+#[cfg_attr(feature = "std", derive(schemars::JsonSchema))]
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub struct Coin {
+	pub denom: String,
+	pub amount: Displayed<u128>,
+}
+
+impl Coin {
+	pub fn new(amount: u128, denom: impl Into<String>) -> Self {
+		Coin { denom: denom.into(), amount: amount.into() }
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn query_schema() {
+		let query = ComposableQuery::Oracle(OracleQuery::Price {
+			denom: "1".into(),
+			amount: 1_000_000_000_000_u128.into(),
+		});
+		let json = serde_json::to_string(&query).unwrap();
+		assert_eq!(json, r#"{"oracle":{"price":{"denom":"1","amount":"1000000000000"}}}"#);
+		assert_eq!(serde_json::from_str::<ComposableQuery>(&json).unwrap(), query);
+	}
+
+	#[test]
+	fn msg_schema() {
+		let msg = ComposableMsg::Pablo(PabloMsg::Swap {
+			pool_id: 1_u128.into(),
+			in_asset: Coin::new(100, "1"),
+			min_receive: Coin::new(90, "4"),
+			keep_alive: true,
+		});
+		let json = serde_json::to_string(&msg).unwrap();
+		assert_eq!(
+			json,
+			r#"{"pablo":{"swap":{"pool_id":"1","in_asset":{"denom":"1","amount":"100"},"min_receive":{"denom":"4","amount":"90"},"keep_alive":true}}}"#
+		);
+		assert_eq!(serde_json::from_str::<ComposableMsg>(&json).unwrap(), msg);
+	}
+}
@@ -0,0 +1,60 @@
+use crate::{Coin, Displayed};
+use alloc::{string::String, vec::Vec};
+use serde::{Deserialize, Serialize};
+
+/// Custom messages a contract can dispatch to the Composable pallets.
+///
+/// Messages are executed on behalf of the contract, i.e. with the funds it owns.
+#[cfg_attr(feature = "std", derive(schemars::JsonSchema))]
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ComposableMsg {
+	Pablo(PabloMsg),
+	StakingRewards(StakingRewardsMsg),
+	Xcm(XcmMsg),
+}
+
+#[cfg_attr(feature = "std", derive(schemars::JsonSchema))]
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PabloMsg {
+	/// Swap `in_asset` on the pool `pool_id`, receiving at least `min_receive`.
+	/// The data of the message is a [`crate::SwapResponse`].
+	Swap { pool_id: Displayed<u128>, in_asset: Coin, min_receive: Coin, keep_alive: bool },
+}
+
+#[cfg_attr(feature = "std", derive(schemars::JsonSchema))]
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StakingRewardsMsg {
+	/// Stake `amount` in the rewards pool of the `pool_id` denom, locked for `duration_preset`
+	/// seconds. The data of the message is a [`StakeResponse`].
+	Stake { pool_id: String, amount: Displayed<u128>, duration_preset: u64, keep_alive: bool },
+	/// Claim the rewards accumulated by a staked position.
+	Claim { fnft_collection_id: String, fnft_instance_id: u64 },
+}
+
+/// Only available to the contracts allowed by the chain, a native fee being charged to the
+/// contract for every message.
+#[cfg_attr(feature = "std", derive(schemars::JsonSchema))]
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum XcmMsg {
+	/// Send an XCM program to `destination`, originating from the location of the contract.
+	/// `destination` is a SCALE encoded `VersionedMultiLocation` and `message` a SCALE encoded
+	/// `VersionedXcm<()>`.
+	Send { destination: Vec<u8>, message: Vec<u8> },
+	/// Transfer `asset` to `beneficiary` on the sibling parachain `para_id`, or on the relay
+	/// chain if [`None`]. The execution on the destination, bounded by `dest_weight`, is paid
+	/// with `asset`.
+	Transfer { asset: Coin, para_id: Option<u32>, beneficiary: [u8; 32], dest_weight: u64 },
+}
+
+/// Position minted when staking.
+#[cfg_attr(feature = "std", derive(schemars::JsonSchema))]
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub struct StakeResponse {
+	pub fnft_collection_id: String,
+	pub fnft_instance_id: u64,
+}
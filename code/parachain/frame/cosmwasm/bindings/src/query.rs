@@ -0,0 +1,82 @@
+use crate::{Coin, Displayed};
+use alloc::string::String;
+use serde::{Deserialize, Serialize};
+
+/// Custom queries a contract can issue to the Composable pallets.
+#[cfg_attr(feature = "std", derive(schemars::JsonSchema))]
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ComposableQuery {
+	Oracle(OracleQuery),
+	Pablo(PabloQuery),
+	Assets(AssetsQuery),
+}
+
+#[cfg_attr(feature = "std", derive(schemars::JsonSchema))]
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OracleQuery {
+	/// Price of `amount` of `denom` in the pegging asset of the oracle.
+	/// Returns a [`PriceResponse`].
+	Price { denom: String, amount: Displayed<u128> },
+	/// Time weighted price of `amount` of `denom`.
+	/// Returns a [`TwapResponse`].
+	Twap { denom: String, amount: Displayed<u128> },
+}
+
+#[cfg_attr(feature = "std", derive(schemars::JsonSchema))]
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PabloQuery {
+	/// Quote of `base_asset` in `quote_denom` on the pool `pool_id`.
+	/// Returns a [`SwapResponse`].
+	SpotPrice {
+		pool_id: Displayed<u128>,
+		base_asset: Coin,
+		quote_denom: String,
+		calculate_with_fees: bool,
+	},
+}
+
+#[cfg_attr(feature = "std", derive(schemars::JsonSchema))]
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AssetsQuery {
+	/// Metadata of the asset `denom`.
+	/// Returns an [`AssetMetadataResponse`].
+	Metadata { denom: String },
+}
+
+#[cfg_attr(feature = "std", derive(schemars::JsonSchema))]
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub struct PriceResponse {
+	pub price: Displayed<u128>,
+	/// Block at which the price was submitted.
+	pub block: u64,
+}
+
+#[cfg_attr(feature = "std", derive(schemars::JsonSchema))]
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub struct TwapResponse {
+	pub price: Displayed<u128>,
+}
+
+/// Outcome of a swap, either quoted or executed.
+#[cfg_attr(feature = "std", derive(schemars::JsonSchema))]
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub struct SwapResponse {
+	pub value: Coin,
+	pub fee: Coin,
+}
+
+#[cfg_attr(feature = "std", derive(schemars::JsonSchema))]
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub struct AssetMetadataResponse {
+	pub denom: String,
+	/// Number of decimals of the unit of the asset.
+	pub decimals: u8,
+}
@@ -1,6 +1,8 @@
 use codec::Codec;
 use core::{fmt::Display, str::FromStr};
-use cosmwasm_runtime_api::CosmwasmRuntimeApi;
+use cosmwasm_runtime_api::{
+	ContractInfoResult, ContractStatePage, CosmwasmRuntimeApi, DryRunResult,
+};
 use jsonrpsee::{
 	core::{Error as RpcError, RpcResult},
 	proc_macros::rpc,
@@ -43,6 +45,54 @@ mod cosmwasm_api {
 			message: Vec<u8>,
 			at: Option<BlockHash>,
 		) -> RpcResult<AccountId>;
+
+		#[method(name = "cosmwasm_dryRunExecute")]
+		fn dry_run_execute(
+			&self,
+			executor: AccountId,
+			contract: AccountId,
+			funds: BTreeMap<AssetId, (Balance, bool)>,
+			gas: u64,
+			message: Vec<u8>,
+			at: Option<BlockHash>,
+		) -> RpcResult<DryRunResult>;
+
+		#[method(name = "cosmwasm_dryRunMigrate")]
+		fn dry_run_migrate(
+			&self,
+			migrator: AccountId,
+			contract: AccountId,
+			new_code_id: u64,
+			gas: u64,
+			message: Vec<u8>,
+			at: Option<BlockHash>,
+		) -> RpcResult<DryRunResult>;
+
+		#[method(name = "cosmwasm_contractInfo")]
+		fn contract_info(
+			&self,
+			contract: AccountId,
+			at: Option<BlockHash>,
+		) -> RpcResult<ContractInfoResult<AccountId>>;
+
+		#[method(name = "cosmwasm_contractState")]
+		fn contract_state(
+			&self,
+			contract: AccountId,
+			prefix: Option<Vec<u8>>,
+			start_after: Option<Vec<u8>>,
+			limit: u32,
+			at: Option<BlockHash>,
+		) -> RpcResult<ContractStatePage>;
+
+		#[method(name = "cosmwasm_contractsByCode")]
+		fn contracts_by_code(
+			&self,
+			code_id: u64,
+			start_after: Option<AccountId>,
+			limit: u32,
+			at: Option<BlockHash>,
+		) -> RpcResult<Vec<AccountId>>;
 	}
 }
 
@@ -117,4 +167,83 @@ where
 		runtime_api_result
 			.map_err(|e| runtime_error_into_rpc_error(String::from_utf8_lossy(e.as_ref())))
 	}
+
+	fn dry_run_execute(
+		&self,
+		executor: AccountId,
+		contract: AccountId,
+		funds: BTreeMap<AssetId, (Balance, bool)>,
+		gas: u64,
+		message: Vec<u8>,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> RpcResult<DryRunResult> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+		let runtime_api_result = api
+			.dry_run_execute(&at, executor, contract, funds, gas, message)
+			.map_err(runtime_error_into_rpc_error)?;
+		runtime_api_result
+			.map_err(|e| runtime_error_into_rpc_error(String::from_utf8_lossy(e.as_ref())))
+	}
+
+	fn dry_run_migrate(
+		&self,
+		migrator: AccountId,
+		contract: AccountId,
+		new_code_id: u64,
+		gas: u64,
+		message: Vec<u8>,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> RpcResult<DryRunResult> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+		let runtime_api_result = api
+			.dry_run_migrate(&at, migrator, contract, new_code_id, gas, message)
+			.map_err(runtime_error_into_rpc_error)?;
+		runtime_api_result
+			.map_err(|e| runtime_error_into_rpc_error(String::from_utf8_lossy(e.as_ref())))
+	}
+
+	fn contract_info(
+		&self,
+		contract: AccountId,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> RpcResult<ContractInfoResult<AccountId>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+		let runtime_api_result =
+			api.contract_info(&at, contract).map_err(runtime_error_into_rpc_error)?;
+		runtime_api_result
+			.map_err(|e| runtime_error_into_rpc_error(String::from_utf8_lossy(e.as_ref())))
+	}
+
+	fn contract_state(
+		&self,
+		contract: AccountId,
+		prefix: Option<Vec<u8>>,
+		start_after: Option<Vec<u8>>,
+		limit: u32,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> RpcResult<ContractStatePage> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+		let runtime_api_result = api
+			.contract_state(&at, contract, prefix, start_after, limit)
+			.map_err(runtime_error_into_rpc_error)?;
+		runtime_api_result
+			.map_err(|e| runtime_error_into_rpc_error(String::from_utf8_lossy(e.as_ref())))
+	}
+
+	fn contracts_by_code(
+		&self,
+		code_id: u64,
+		start_after: Option<AccountId>,
+		limit: u32,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> RpcResult<Vec<AccountId>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+		api.contracts_by_code(&at, code_id, start_after, limit)
+			.map_err(runtime_error_into_rpc_error)
+	}
 }
@@ -2,11 +2,57 @@
 #![allow(clippy::too_many_arguments)]
 #![allow(clippy::unnecessary_mut_passed)]
 
-use codec::Codec;
+use codec::{Codec, Decode, Encode};
+#[cfg(feature = "std")]
+use serde::{Deserialize, Serialize};
 use sp_std::collections::btree_map::BTreeMap;
 #[cfg(not(feature = "std"))]
 use sp_std::vec::Vec;
 
+/// Event emitted by a contract.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub struct ContractEvent {
+	pub ty: Vec<u8>,
+	pub attributes: Vec<(Vec<u8>, Vec<u8>)>,
+}
+
+/// Outcome of a dry run of a contract entrypoint.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub struct DryRunResult {
+	/// Gas consumed by the contract, i.e. the minimum `gas` the extrinsic must be given.
+	pub gas_used: u64,
+	/// Data returned by the contract.
+	pub data: Option<Vec<u8>>,
+	/// Events emitted by the contract and its sub-messages.
+	pub events: Vec<ContractEvent>,
+}
+
+/// Metadata of an instantiated contract.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub struct ContractInfoResult<AccountId> {
+	/// The code this contract is baked by.
+	pub code_id: u64,
+	/// Account that created this instance.
+	pub instantiator: AccountId,
+	/// Current admin of the contract instance, if any.
+	pub admin: Option<AccountId>,
+	/// Contract label defined by the instantiator.
+	pub label: Vec<u8>,
+}
+
+/// Page of the raw entries of a contract storage.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub struct ContractStatePage {
+	/// The (key, value) entries, ordered by hashed key.
+	pub entries: Vec<(Vec<u8>, Vec<u8>)>,
+	/// Key to resume from (as `start_after`) to get the next page, [`None`] if exhausted.
+	pub next: Option<Vec<u8>>,
+}
+
 // Cosmwasm Runtime API declaration.
 sp_api::decl_runtime_apis! {
 	pub trait CosmwasmRuntimeApi<AccountId, AssetId, Balance, Error>
@@ -32,5 +78,42 @@ sp_api::decl_runtime_apis! {
 			gas: u64,
 			message: Vec<u8>,
 		) -> Result<AccountId, Error>;
+
+		/// Simulate `Cosmwasm::execute` against the current state, without committing it.
+		fn dry_run_execute(
+			executor: AccountId,
+			contract: AccountId,
+			funds: BTreeMap<AssetId, (Balance, bool)>,
+			gas: u64,
+			message: Vec<u8>,
+		) -> Result<DryRunResult, Error>;
+
+		/// Simulate `Cosmwasm::migrate` against the current state, without committing it.
+		fn dry_run_migrate(
+			migrator: AccountId,
+			contract: AccountId,
+			new_code_id: u64,
+			gas: u64,
+			message: Vec<u8>,
+		) -> Result<DryRunResult, Error>;
+
+		/// Metadata of an instantiated contract.
+		fn contract_info(contract: AccountId) -> Result<ContractInfoResult<AccountId>, Error>;
+
+		/// Page through the raw entries of a contract storage, optionally restricted to the keys
+		/// starting with `prefix`.
+		fn contract_state(
+			contract: AccountId,
+			prefix: Option<Vec<u8>>,
+			start_after: Option<Vec<u8>>,
+			limit: u32,
+		) -> Result<ContractStatePage, Error>;
+
+		/// Page through the contracts currently baked by the code `code_id`.
+		fn contracts_by_code(
+			code_id: u64,
+			start_after: Option<AccountId>,
+			limit: u32,
+		) -> Vec<AccountId>;
 	}
 }
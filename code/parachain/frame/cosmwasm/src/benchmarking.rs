@@ -5,12 +5,18 @@ use crate::{
 		abstraction::{CanonicalCosmwasmAccount, CosmwasmAccount, Gas},
 		wasmi::{CosmwasmVMCache, CosmwasmVMShared},
 	},
+	types::UploadPolicy,
 	ContractInfoOf, Pallet as Cosmwasm,
 };
 use alloc::{
 	borrow::ToOwned, boxed::Box, collections::BTreeMap, format, string::String, vec, vec::Vec,
 };
+use codec::Encode;
 use core::{cell::SyncUnsafeCell, marker::PhantomData};
+use cosmwasm_bindings::{
+	AssetsQuery, Coin as ComposableCoin, ComposableMsg, ComposableQuery, OracleQuery, PabloMsg,
+	PabloQuery, StakeResponse, StakingRewardsMsg, XcmMsg,
+};
 use cosmwasm_vm::{
 	cosmwasm_std::{Coin, Reply, SubMsgResult},
 	executor::InstantiateCall,
@@ -21,16 +27,21 @@ use cosmwasm_vm_wasmi::code_gen::{
 };
 use entrypoint::*;
 use frame_benchmarking::{account, benchmarks, impl_benchmark_test_suite};
-use frame_support::traits::{fungible, fungibles, fungibles::Mutate, Get};
+use frame_support::traits::{fungible, fungibles, fungibles::Mutate, EnsureOrigin, Get};
 use frame_system::RawOrigin;
 use lazy_static::lazy_static;
 use primitives::currency::CurrencyId;
 use rand::{rngs::SmallRng, RngCore, SeedableRng};
 use sha2::{Digest, Sha256};
 use sha3::Keccak256;
+use sp_runtime::traits::Convert;
 use wasm_instrument::parity_wasm::elements::{
 	BlockType, BrTableData, Instruction, Instructions, ValueType,
 };
+use xcm::{
+	latest::{Instruction as XcmInstruction, MultiLocation, OriginKind, Xcm},
+	VersionedMultiLocation, VersionedXcm,
+};
 
 const SECP256K1_MESSAGE_HEX: &str = "5c868fedb8026979ebd26f1ba07c27eedf4ff6d10443505a96ecaf21ba8c4f0937b3cd23ffdc3dd429d4cd1905fb8dbcceeff1350020e18b58d2ba70887baa3a9b783ad30d3fbf210331cdd7df8d77defa398cdacdfc2e359c7ba4cae46bb74401deb417f8b912a1aa966aeeba9c39c7dd22479ae2b30719dca2f2206c5eb4b7";
 const SECP256K1_SIGNATURE_HEX: &str = "207082eb2c3dfa0b454e0906051270ba4074ac93760ba9e7110cd9471475111151eb0dbbc9920e72146fb564f99d039802bf6ef2561446eb126ef364d21ee9c4";
@@ -415,6 +426,43 @@ benchmarks! {
 		assert_eq!(ContractToInfo::<T>::get(&contract).unwrap().admin, Some(new_admin));
 	}
 
+	remove_code {
+		let n in 1..T::MaxCodeSize::get() - 10000;
+		let origin = create_funded_account::<T>("origin");
+		let wasm_module: WasmModule = code_gen::ModuleDefinition::new(Default::default(), n as usize, None).unwrap().into();
+		Cosmwasm::<T>::do_upload(&origin, wasm_module.code.try_into().unwrap()).unwrap();
+		let pristine_code_hash = CodeIdToInfo::<T>::get(1).unwrap().pristine_code_hash;
+	}: _(RawOrigin::Signed(origin), CodeIdentifier::CodeId(1))
+	verify {
+		assert_eq!(CodeIdToInfo::<T>::contains_key(1), false);
+		assert_eq!(PristineCode::<T>::contains_key(1), false);
+		assert_eq!(InstrumentedCode::<T>::contains_key(1), false);
+		assert_eq!(CodeHashToId::<T>::contains_key(pristine_code_hash), false);
+	}
+
+	set_upload_policy {
+		let origin = T::UploadOrigin::successful_origin();
+	}: _<T::Origin>(origin, UploadPolicy::Allowlist)
+	verify {
+		assert_eq!(CodeUploadPolicy::<T>::get(), UploadPolicy::Allowlist);
+	}
+
+	update_upload_allowlist {
+		let origin = T::UploadOrigin::successful_origin();
+		let uploader = account::<<T as Config>::AccountIdExtended>("uploader", 0, 0xCAFEBABE);
+	}: _<T::Origin>(origin, uploader.clone(), true)
+	verify {
+		assert!(UploadAllowlist::<T>::contains_key(&uploader));
+	}
+
+	update_xcm_send_allowlist {
+		let origin = T::SudoOrigin::successful_origin();
+		let contract = account::<<T as Config>::AccountIdExtended>("contract", 0, 0xCAFEBABE);
+	}: _<T::Origin>(origin, contract.clone(), true)
+	verify {
+		assert!(XcmSendAllowlist::<T>::contains_key(&contract));
+	}
+
 	db_read {
 		let sender = create_funded_account::<T>("origin");
 		let (contract, info) = create_instantiated_contract::<T>(sender.clone());
@@ -438,6 +486,9 @@ benchmarks! {
 	}: {
 		Cosmwasm::<T>::do_db_write(&mut vm.0, "hello".as_bytes(), "world".as_bytes()).unwrap();
 	}
+	verify {
+		assert!(vm.0.contract_info.storage_usage > 0);
+	}
 
 	db_scan {
 		let sender = create_funded_account::<T>("origin");
@@ -462,7 +513,10 @@ benchmarks! {
 		let mut vm = Cosmwasm::<T>::cosmwasm_new_vm(get_shared_vm(), sender, contract, info, vec![]).unwrap();
 		Cosmwasm::<T>::do_db_write(&mut vm.0, "hello".as_bytes(), "world".as_bytes()).unwrap();
 	}: {
-		Cosmwasm::<T>::do_db_remove(&mut vm.0, "hello".as_bytes());
+		Cosmwasm::<T>::do_db_remove(&mut vm.0, "hello".as_bytes()).unwrap();
+	}
+	verify {
+		assert_eq!(vm.0.contract_info.storage_usage, 0);
 	}
 
 	balance {
@@ -480,6 +534,169 @@ benchmarks! {
 		Cosmwasm::<T>::do_transfer(&sender, &receiver, &funds, false).unwrap();
 	}
 
+	all_balance {
+		// Up to the max assets listed, the ones past the well known assets being minted under
+		// arbitrary ids.
+		let n in 0..T::MaxAssetsList::get();
+		let sender = create_funded_account::<T>("origin");
+		let known_assets = CurrencyId::list_assets();
+		let _ = create_coins::<T>(vec![&sender], n.min(known_assets.len() as u32));
+		let mut assets: Vec<_> =
+			known_assets.iter().take(n as usize).map(|asset| asset.id.into()).collect();
+		for i in assets.len() as u32..n {
+			let asset_id = u128::from(u32::MAX) + u128::from(i);
+			<pallet_assets::Pallet<T> as Mutate<T::AccountId>>::mint_into(
+				CurrencyId(asset_id),
+				&sender,
+				10_000_000_000_000_000_000u128.into(),
+			)
+			.unwrap();
+			assets.push(asset_id.into());
+		}
+	}: {
+		// Listing the assets is part of the query.
+		let _ = T::AssetsList::get();
+		Cosmwasm::<T>::do_all_balance(&sender, assets);
+	}
+
+	burn {
+		let n in 0..CurrencyId::list_assets().len().try_into().unwrap();
+		let sender = create_funded_account::<T>("origin");
+		let funds: Vec<Coin> = create_coins::<T>(vec![&sender], n);
+	}: {
+		Cosmwasm::<T>::do_burn(&sender, &funds).unwrap();
+	}
+
+	query_oracle_price {
+		let asset: <T as Config>::AssetId = CurrencyId::KSM.0.into();
+		T::BenchmarkHelper::set_price(asset);
+		let query = ComposableQuery::Oracle(OracleQuery::Price {
+			denom: T::AssetToDenom::convert(asset),
+			amount: 1_000_000_000_000_u128.into(),
+		});
+	}: {
+		Cosmwasm::<T>::do_query_custom(query).unwrap();
+	}
+
+	query_oracle_twap {
+		let asset: <T as Config>::AssetId = CurrencyId::KSM.0.into();
+		T::BenchmarkHelper::set_price(asset);
+		let query = ComposableQuery::Oracle(OracleQuery::Twap {
+			denom: T::AssetToDenom::convert(asset),
+			amount: 1_000_000_000_000_u128.into(),
+		});
+	}: {
+		Cosmwasm::<T>::do_query_custom(query).unwrap();
+	}
+
+	query_pablo_spot_price {
+		let base: <T as Config>::AssetId = CurrencyId::KSM.0.into();
+		let quote: <T as Config>::AssetId = CurrencyId::USDT.0.into();
+		let pool_id = T::BenchmarkHelper::create_pool(base, quote);
+		let query = ComposableQuery::Pablo(PabloQuery::SpotPrice {
+			pool_id: pool_id.into(),
+			base_asset: ComposableCoin::new(1_000_000, T::AssetToDenom::convert(base)),
+			quote_denom: T::AssetToDenom::convert(quote),
+			calculate_with_fees: true,
+		});
+	}: {
+		Cosmwasm::<T>::do_query_custom(query).unwrap();
+	}
+
+	query_assets_metadata {
+		let asset: <T as Config>::AssetId = CurrencyId::KSM.0.into();
+		let query = ComposableQuery::Assets(AssetsQuery::Metadata {
+			denom: T::AssetToDenom::convert(asset),
+		});
+	}: {
+		Cosmwasm::<T>::do_query_custom(query).unwrap();
+	}
+
+	message_pablo_swap {
+		let base: <T as Config>::AssetId = CurrencyId::KSM.0.into();
+		let quote: <T as Config>::AssetId = CurrencyId::USDT.0.into();
+		let pool_id = T::BenchmarkHelper::create_pool(base, quote);
+		let contract = create_funded_account::<T>("contract");
+		<T as Config>::Assets::mint_into(base, &contract, 1_000_000_000_000_u128.into()).unwrap();
+		let message = ComposableMsg::Pablo(PabloMsg::Swap {
+			pool_id: pool_id.into(),
+			in_asset: ComposableCoin::new(1_000_000, T::AssetToDenom::convert(base)),
+			min_receive: ComposableCoin::new(0, T::AssetToDenom::convert(quote)),
+			keep_alive: true,
+		});
+	}: {
+		Cosmwasm::<T>::do_message_custom(&contract, message).unwrap();
+	}
+
+	message_staking_stake {
+		let (asset, duration_preset) = T::BenchmarkHelper::create_reward_pool();
+		let contract = create_funded_account::<T>("contract");
+		<T as Config>::Assets::mint_into(asset, &contract, 1_000_000_000_000_u128.into()).unwrap();
+		let message = ComposableMsg::StakingRewards(StakingRewardsMsg::Stake {
+			pool_id: T::AssetToDenom::convert(asset),
+			amount: 1_000_000_000_u128.into(),
+			duration_preset,
+			keep_alive: true,
+		});
+	}: {
+		Cosmwasm::<T>::do_message_custom(&contract, message).unwrap();
+	}
+
+	message_staking_claim {
+		let (asset, duration_preset) = T::BenchmarkHelper::create_reward_pool();
+		let contract = create_funded_account::<T>("contract");
+		<T as Config>::Assets::mint_into(asset, &contract, 1_000_000_000_000_u128.into()).unwrap();
+		let stake = ComposableMsg::StakingRewards(StakingRewardsMsg::Stake {
+			pool_id: T::AssetToDenom::convert(asset),
+			amount: 1_000_000_000_u128.into(),
+			duration_preset,
+			keep_alive: true,
+		});
+		let data = Cosmwasm::<T>::do_message_custom(&contract, stake).unwrap().unwrap();
+		let StakeResponse { fnft_collection_id, fnft_instance_id } =
+			serde_json::from_slice(&data).unwrap();
+		let message = ComposableMsg::StakingRewards(StakingRewardsMsg::Claim {
+			fnft_collection_id,
+			fnft_instance_id,
+		});
+	}: {
+		Cosmwasm::<T>::do_message_custom(&contract, message).unwrap();
+	}
+
+	xcm_send {
+		let n in 0..65_536;
+		T::BenchmarkHelper::prepare_xcm();
+		let contract = create_funded_account::<T>("contract");
+		XcmSendAllowlist::<T>::insert(&contract, ());
+		let program = Xcm::<()>(vec![XcmInstruction::Transact {
+			origin_type: OriginKind::SovereignAccount,
+			require_weight_at_most: 1_000_000_000,
+			call: vec![0; n as usize].into(),
+		}]);
+		let message = ComposableMsg::Xcm(XcmMsg::Send {
+			destination: VersionedMultiLocation::from(MultiLocation::parent()).encode(),
+			message: VersionedXcm::from(program).encode(),
+		});
+	}: {
+		Cosmwasm::<T>::do_message_custom(&contract, message).unwrap();
+	}
+
+	xcm_transfer {
+		T::BenchmarkHelper::prepare_xcm();
+		let asset: <T as Config>::AssetId = CurrencyId::KSM.0.into();
+		let contract = create_funded_account::<T>("contract");
+		<T as Config>::Assets::mint_into(asset, &contract, 1_000_000_000_000_u128.into()).unwrap();
+		XcmSendAllowlist::<T>::insert(&contract, ());
+		let message = ComposableMsg::Xcm(XcmMsg::Transfer {
+			asset: ComposableCoin::new(1_000_000_000, T::AssetToDenom::convert(asset)),
+			para_id: None,
+			beneficiary: [1; 32],
+			dest_weight: 1_000_000_000,
+		});
+	}: {
+		Cosmwasm::<T>::do_message_custom(&contract, message).unwrap();
+	}
+
 	set_contract_meta {
 		let sender = create_funded_account::<T>("origin");
 		let (contract, info) = create_instantiated_contract::<T>(sender.clone());
@@ -3,8 +3,8 @@ use crate::{
 		abstraction::CosmwasmAccount,
 		wasmi::{CosmwasmVM, CosmwasmVMError, CosmwasmVMShared},
 	},
-	AccountIdOf, CodeIdToInfo, Config, ContractInfoOf, ContractLabelOf, ContractMessageOf,
-	ContractToInfo, CurrentNonce, EntryPoint, Error, Event, FundsOf, Pallet,
+	AccountIdOf, CodeIdToContracts, CodeIdToInfo, Config, ContractInfoOf, ContractLabelOf,
+	ContractMessageOf, ContractToInfo, CurrentNonce, EntryPoint, Error, Event, FundsOf, Pallet,
 };
 use alloc::vec::Vec;
 use composable_support::abstractions::utils::increment::Increment;
@@ -12,7 +12,7 @@ use core::marker::PhantomData;
 
 use cosmwasm_vm::{
 	cosmwasm_std::{Binary, Coin, Event as CosmwasmEvent},
-	executor::{ExecuteCall, InstantiateCall, MigrateCall, ReplyCall},
+	executor::{ExecuteCall, InstantiateCall, MigrateCall, ReplyCall, SudoCall},
 	system::{
 		cosmwasm_system_entrypoint, cosmwasm_system_entrypoint_serialize, cosmwasm_system_run,
 		CosmwasmCallVM, CosmwasmCodeId, StargateCosmwasmCallVM,
@@ -48,6 +48,8 @@ impl CallerState for ExecuteCall {}
 
 impl CallerState for ReplyCall {}
 
+impl CallerState for SudoCall {}
+
 impl<I, O, T: Config> CallerState for Dispatchable<I, O, T> {}
 
 /// Setup state for `instantiate` entrypoint.
@@ -78,8 +80,10 @@ impl EntryPointCaller<InstantiateCall> {
 			trie_id,
 			admin,
 			label,
+			storage_usage: 0,
 		};
 		ContractToInfo::<T>::insert(&contract, &contract_info);
+		CodeIdToContracts::<T>::insert(code_id, &contract, ());
 		CodeIdToInfo::<T>::try_mutate(code_id, |entry| -> Result<(), Error<T>> {
 			let code_info = entry.as_mut().ok_or(Error::<T>::CodeNotFound)?;
 			code_info.refcount =
@@ -149,6 +153,29 @@ impl EntryPointCaller<ReplyCall> {
 	}
 }
 
+impl EntryPointCaller<SudoCall> {
+	/// Prepares for `sudo` entrypoint call.
+	///
+	/// * `sender` - Address of the account on behalf of which the entrypoint is called.
+	/// * `contract` - Address of the contract to be called.
+	pub(crate) fn setup<T: Config>(
+		sender: AccountIdOf<T>,
+		contract: AccountIdOf<T>,
+	) -> Result<EntryPointCaller<Dispatchable<SudoCall, (), T>>, Error<T>> {
+		let contract_info = Pallet::<T>::contract_info(&contract)?;
+		Ok(EntryPointCaller {
+			state: Dispatchable {
+				entry_point: EntryPoint::Sudo,
+				sender,
+				contract,
+				contract_info,
+				output: (),
+				marker: PhantomData,
+			},
+		})
+	}
+}
+
 /// Setup state for `migrate` entrypoint.
 impl EntryPointCaller<MigrateCall> {
 	/// Prepares for `migrate` entrypoint call.
@@ -271,6 +298,36 @@ where
 		Ok(self.state.output)
 	}
 
+	/// Run the entrypoint without depositing its outcome, returning the data and the events
+	/// emitted by the contract instead.
+	///
+	/// * `shared` - Shared state of the Cosmwasm VM.
+	/// * `funds` - Funds to be transferred before execution.
+	/// * `message` - Message to be passed to the entrypoint.
+	pub(crate) fn dry_run(
+		self,
+		shared: &mut CosmwasmVMShared,
+		funds: FundsOf<T>,
+		message: ContractMessageOf<T>,
+	) -> Result<(Option<Binary>, Vec<CosmwasmEvent>), CosmwasmVMError<T>>
+	where
+		for<'x> WasmiVM<CosmwasmVM<'x, T>>: CosmwasmCallVM<I> + StargateCosmwasmCallVM,
+		for<'x> VmErrorOf<WasmiVM<CosmwasmVM<'x, T>>>: Into<CosmwasmVMError<T>>,
+	{
+		let funds = funds
+			.into_iter()
+			.map(|(asset, (amount, _))| Pallet::<T>::native_asset_to_cosmwasm_asset(asset, amount))
+			.collect();
+		Pallet::<T>::cosmwasm_call(
+			shared,
+			self.state.sender,
+			self.state.contract,
+			self.state.contract_info,
+			funds,
+			|vm| cosmwasm_system_entrypoint::<I, _>(vm, &message).map_err(Into::into),
+		)
+	}
+
 	/// Continue the execution by running an entrypoint. This is used for running
 	/// submessages.
 	///
@@ -41,6 +41,7 @@ pub use pallet::*;
 
 pub mod ibc;
 pub mod instrument;
+pub mod migrations;
 pub mod runtimes;
 pub mod types;
 pub mod version;
@@ -60,6 +61,10 @@ mod tests;
 #[frame_support::pallet]
 pub mod pallet {
 	const SUBSTRATE_ECDSA_SIGNATURE_LEN: usize = 65;
+	/// Maximum number of keys visited by [`contract_state`].
+	pub const MAX_CONTRACT_STATE_PAGE_SIZE: u32 = 1_000;
+	/// Maximum number of contracts returned by [`contracts_by_code`].
+	pub const MAX_CONTRACTS_BY_CODE_PAGE_SIZE: u32 = 1_000;
 	use crate::{
 		entrypoint::*,
 		instrument::{gas_and_stack_instrumentation, CostRules, INSTRUMENTATION_VERSION},
@@ -70,7 +75,7 @@ pub mod pallet {
 				InitialStorageMutability, ValidationError,
 			},
 		},
-		types::{CodeInfo, ContractInfo},
+		types::{CodeInfo, ContractInfo, UploadPolicy},
 		version::Version,
 		weights::WeightInfo,
 	};
@@ -80,6 +85,7 @@ pub mod pallet {
 		string::String,
 		vec,
 	};
+	use codec::DecodeLimit;
 	use composable_support::abstractions::{
 		nonce::Nonce,
 		utils::{
@@ -87,7 +93,21 @@ pub mod pallet {
 			start_at::ZeroInit,
 		},
 	};
+	use composable_traits::{
+		currency::LocalAssets,
+		dex::{Amm, AssetAmount, SwapResult},
+		oracle::Oracle,
+		staking::Staking,
+	};
 	use core::fmt::Debug;
+	use cosmwasm_bindings::{
+		AssetMetadataResponse, AssetsQuery, Coin as ComposableCoin, ComposableMsg, ComposableQuery,
+		Displayed, OracleQuery, PabloMsg, PabloQuery, PriceResponse, StakeResponse,
+		StakingRewardsMsg, SwapResponse, TwapResponse, XcmMsg,
+	};
+	use cosmwasm_runtime_api::{
+		ContractEvent, ContractInfoResult, ContractStatePage, DryRunResult,
+	};
 	use cosmwasm_vm::{
 		cosmwasm_std::{
 			Addr, Attribute as CosmwasmEventAttribute, Binary as CosmwasmBinary, BlockInfo, Coin,
@@ -96,7 +116,7 @@ pub mod pallet {
 		},
 		executor::{
 			cosmwasm_call, ExecuteCall, InstantiateCall, MigrateCall, QueryCall, QueryResponse,
-			ReplyCall,
+			ReplyCall, SudoCall,
 		},
 		system::{cosmwasm_system_query, CosmwasmCodeId, CosmwasmContractMeta},
 	};
@@ -104,18 +124,31 @@ pub mod pallet {
 	use frame_support::{
 		dispatch::{DispatchErrorWithPostInfo, DispatchResultWithPostInfo, PostDispatchInfo},
 		pallet_prelude::*,
-		storage::{child::ChildInfo, ChildTriePrefixIterator},
+		storage::{child::ChildInfo, with_transaction, ChildTriePrefixIterator},
 		traits::{
-			fungibles::{Inspect as FungiblesInspect, Transfer as FungiblesTransfer},
+			fungibles::{
+				Inspect as FungiblesInspect, Mutate as FungiblesMutate,
+				Transfer as FungiblesTransfer,
+			},
 			tokens::{AssetId, Balance},
-			Get, ReservableCurrency, UnixTime,
+			Currency, ExistenceRequirement, Get, ReservableCurrency, UnixTime, WithdrawReasons,
 		},
-		transactional, BoundedBTreeMap, PalletId, StorageHasher, Twox64Concat,
+		transactional, BoundedBTreeMap, PalletId, ReversibleStorageHasher, StorageHasher,
+		Twox64Concat,
 	};
 	use frame_system::{ensure_signed, pallet_prelude::OriginFor};
+	use orml_traits::XcmTransfer;
+	use primitives::topology;
 	use sp_core::{crypto::UncheckedFrom, ecdsa, ed25519};
-	use sp_runtime::traits::{Convert, Hash, MaybeDisplay, SaturatedConversion};
+	use sp_runtime::{
+		traits::{Convert, Hash, MaybeDisplay, SaturatedConversion, Saturating, Zero},
+		TransactionOutcome,
+	};
 	use sp_std::vec::Vec;
+	use xcm::{
+		latest::{Instruction, Junction, Junctions, MultiLocation, NetworkId, SendXcm, Xcm},
+		VersionedMultiLocation, VersionedXcm, MAX_XCM_DECODE_DEPTH,
+	};
 
 	pub(crate) type KeepAlive = bool;
 	pub(crate) type FundsOf<T> =
@@ -169,6 +202,10 @@ pub mod pallet {
 		Migrated { contract: AccountIdOf<T>, to: CosmwasmCodeId },
 		AdminUpdated { contract: AccountIdOf<T>, new_admin: Option<AccountIdOf<T>> },
 		IbcChannelOpen { contract: AccountIdOf<T> },
+		CodeRemoved { code_id: CosmwasmCodeId },
+		UploadPolicyUpdated { policy: UploadPolicy },
+		UploadAllowlistUpdated { account: AccountIdOf<T>, allowed: bool },
+		XcmSendAllowlistUpdated { contract: AccountIdOf<T>, allowed: bool },
 	}
 
 	#[pallet::error]
@@ -187,6 +224,13 @@ pub mod pallet {
 		ContractAlreadyExists,
 		ContractNotFound,
 		TransferFailed,
+		BurnFailed,
+		SwapFailed,
+		StakingFailed,
+		XcmDecodingFailed,
+		XcmSendFailed,
+		XcmTransferFailed,
+		XcmSendNotAllowed,
 		LabelTooBig,
 		UnknownDenom,
 		StackOverflow,
@@ -198,6 +242,10 @@ pub mod pallet {
 		IteratorIdOverflow,
 		IteratorNotFound,
 		NotAuthorized,
+		UploadNotAllowed,
+		CodeInUse,
+		NotEnoughFundsForStorageDeposit,
+		NotEnoughFundsForXcmDeliveryFee,
 		Unsupported,
 		Ibc,
 		FailedToSerialize,
@@ -285,6 +333,13 @@ pub mod pallet {
 		#[pallet::constant]
 		type CodeStorageByteDeposit: Get<u32>;
 
+		/// Deposit of a byte stored in a contract storage.
+		/// The deposit is expressed in [`Self::NativeAsset`].
+		/// This amount is reserved on the contract when its storage grows and released when
+		/// entries are removed.
+		#[pallet::constant]
+		type ContractStorageByteDeposit: Get<u32>;
+
 		/// Price of writing a byte in the storage.
 		#[pallet::constant]
 		type ContractStorageByteWritePrice: Get<u32>;
@@ -324,7 +379,59 @@ pub mod pallet {
 				AccountIdOf<Self>,
 				Balance = BalanceOf<Self>,
 				AssetId = AssetIdOf<Self>,
-			>;
+			> + FungiblesMutate<AccountIdOf<Self>, Balance = BalanceOf<Self>, AssetId = AssetIdOf<Self>>;
+
+		/// Assets enumerated when a contract queries all the balances of an account, i.e. the
+		/// well known assets along with the ones of the assets registry. Only the first
+		/// [`Config::MaxAssetsList`] assets are enumerated, implementations reading at most one
+		/// storage item per asset.
+		type AssetsList: Get<Vec<AssetIdOf<Self>>>;
+
+		/// Max assets enumerated by [`Config::AssetsList`], the weight of the query being charged
+		/// for this many assets.
+		#[pallet::constant]
+		type MaxAssetsList: Get<u32>;
+
+		/// Oracle contracts read prices from through [`OracleQuery`].
+		type Oracle: Oracle<
+			AssetId = AssetIdOf<Self>,
+			Balance = BalanceOf<Self>,
+			Timestamp = <Self as frame_system::Config>::BlockNumber,
+		>;
+
+		/// Pools contracts quote and swap on through [`PabloQuery`] and [`PabloMsg`].
+		type Pablo: Amm<
+			AccountId = AccountIdOf<Self>,
+			AssetId = AssetIdOf<Self>,
+			Balance = BalanceOf<Self>,
+			PoolId = u128,
+		>;
+
+		/// Rewards pools contracts stake in through [`StakingRewardsMsg`].
+		type StakingRewards: Staking<
+			AccountId = AccountIdOf<Self>,
+			RewardPoolId = AssetIdOf<Self>,
+			Balance = BalanceOf<Self>,
+			PositionId = (AssetIdOf<Self>, u64),
+		>;
+
+		/// Metadata of the assets, provided to contracts through [`AssetsQuery`].
+		type LocalAssets: LocalAssets<AssetIdOf<Self>>;
+
+		/// Router of the XCM programs contracts send through [`XcmMsg::Send`], only contracts
+		/// allowed by the [`Config::SudoOrigin`] are able to send them.
+		type XcmSender: SendXcm;
+
+		/// Cross-chain transfers of contracts through [`XcmMsg::Transfer`], usually
+		/// `orml_xtokens`. Restricted to the same contracts as [`Config::XcmSender`].
+		type XcmTransfer: XcmTransfer<AccountIdOf<Self>, BalanceOf<Self>, AssetIdOf<Self>>;
+
+		/// Native amount burnt from a contract for every [`XcmMsg`] it sends, pricing the delivery
+		/// of the message.
+		type XcmDeliveryFee: Get<BalanceOf<Self>>;
+
+		/// Location of a contract, from which the XCM programs it sends originate.
+		type AccountIdToMultiLocation: Convert<AccountIdOf<Self>, MultiLocation>;
 
 		/// Source of time.
 		type UnixTime: UnixTime;
@@ -336,10 +443,47 @@ pub mod pallet {
 		type IbcRelayerAccount: Get<AccountIdOf<Self>>;
 
 		type IbcRelayer: ibc_primitives::IbcHandler<AccountIdOf<Self>>;
+
+		/// Origin allowed to upload code regardless of the [`UploadPolicy`], to remove any unused
+		/// code and to manage the upload policy.
+		type UploadOrigin: EnsureOrigin<Self::Origin>;
+
+		/// Origin allowed to call the `sudo` export of the contracts and to allow contracts to send
+		/// XCM programs.
+		type SudoOrigin: EnsureOrigin<Self::Origin>;
+
+		/// Account on behalf of which the `sudo` export is called, paying for the `funds` and the
+		/// storage deposits of the call.
+		type SudoAccount: Get<AccountIdOf<Self>>;
+
+		/// Setup of the pallets custom queries and messages are dispatched to, for benchmarking
+		/// them.
+		#[cfg(any(feature = "runtime-benchmarks", test))]
+		type BenchmarkHelper: BenchmarkHelper<Self>;
 	}
 
+	/// Setup of the pallets backing [`ComposableQuery`] and [`ComposableMsg`], so that their
+	/// benchmarks go through the successful path.
+	#[cfg(any(feature = "runtime-benchmarks", test))]
+	pub trait BenchmarkHelper<T: Config> {
+		/// Price `asset` in [`Config::Oracle`], along with a full price history.
+		fn set_price(asset: T::AssetId);
+		/// Create a [`Config::Pablo`] pool between `base` and `quote` provided with liquidity.
+		fn create_pool(base: T::AssetId, quote: T::AssetId) -> u128;
+		/// Create a [`Config::StakingRewards`] pool, returning the staked asset along with a
+		/// duration preset of the pool.
+		fn create_reward_pool() -> (T::AssetId, u64);
+		/// Make [`Config::XcmSender`] and [`Config::XcmTransfer`] able to deliver messages to the
+		/// relay chain.
+		fn prepare_xcm();
+	}
+
+	/// The current storage version, see [`crate::migrations`].
+	const STORAGE_VERSION: StorageVersion = StorageVersion::new(2);
+
 	#[pallet::pallet]
 	#[pallet::generate_store(pub(super) trait Store)]
+	#[pallet::storage_version(STORAGE_VERSION)]
 	pub struct Pallet<T>(_);
 
 	/// A mapping from an original code id to the original code, untouched by instrumentation.
@@ -380,6 +524,25 @@ pub mod pallet {
 	pub(crate) type ContractToInfo<T: Config> =
 		StorageMap<_, Identity, AccountIdOf<T>, ContractInfoOf<T>>;
 
+	/// The contracts baked by a code, indexing [`ContractToInfo`] by code id.
+	#[pallet::storage]
+	pub(crate) type CodeIdToContracts<T: Config> =
+		StorageDoubleMap<_, Twox64Concat, CosmwasmCodeId, Identity, AccountIdOf<T>, ()>;
+
+	/// Policy restricting who is able to upload code.
+	#[pallet::storage]
+	pub(crate) type CodeUploadPolicy<T: Config> = StorageValue<_, UploadPolicy, ValueQuery>;
+
+	/// Accounts allowed to upload code under [`UploadPolicy::Allowlist`].
+	#[pallet::storage]
+	pub(crate) type UploadAllowlist<T: Config> =
+		StorageMap<_, Blake2_128Concat, AccountIdOf<T>, ()>;
+
+	/// Contracts allowed to send XCM programs and transfers through [`XcmMsg`].
+	#[pallet::storage]
+	pub(crate) type XcmSendAllowlist<T: Config> =
+		StorageMap<_, Blake2_128Concat, AccountIdOf<T>, ()>;
+
 	#[pallet::call]
 	impl<T: Config> Pallet<T> {
 		/// Upload a CosmWasm contract.
@@ -397,6 +560,7 @@ pub mod pallet {
 		#[pallet::weight(T::WeightInfo::upload(code.len() as u32))]
 		pub fn upload(origin: OriginFor<T>, code: ContractCodeOf<T>) -> DispatchResult {
 			let who = ensure_signed(origin)?;
+			Self::ensure_can_upload(&who)?;
 			Self::do_upload(&who, code)
 		}
 
@@ -527,6 +691,138 @@ pub mod pallet {
 			Self::deposit_event(Event::<T>::AdminUpdated { contract, new_admin });
 			Self::refund_gas(outcome, initial_gas, shared.gas.remaining())
 		}
+
+		/// Upload a CosmWasm contract on behalf of `creator`, regardless of the upload policy.
+		///
+		/// Arguments
+		///
+		/// - `origin` the [`Config::UploadOrigin`].
+		/// - `creator` the owner of the code, from which the deposit is reserved.
+		/// - `code` the actual wasm code.
+		#[transactional]
+		#[pallet::weight(T::WeightInfo::upload(code.len() as u32))]
+		pub fn force_upload(
+			origin: OriginFor<T>,
+			creator: AccountIdOf<T>,
+			code: ContractCodeOf<T>,
+		) -> DispatchResult {
+			T::UploadOrigin::ensure_origin(origin)?;
+			Self::do_upload(&creator, code)
+		}
+
+		/// Remove a code that is not referenced by any contract anymore and release the deposit
+		/// of its creator.
+		///
+		/// * Emits a `CodeRemoved` event on success.
+		///
+		/// Arguments
+		///
+		/// * `origin` the creator of the code or the [`Config::UploadOrigin`].
+		/// * `code_identifier` the code to remove.
+		#[transactional]
+		#[pallet::weight(T::WeightInfo::remove_code(T::MaxCodeSize::get()))]
+		pub fn remove_code(
+			origin: OriginFor<T>,
+			code_identifier: CodeIdentifier<T>,
+		) -> DispatchResultWithPostInfo {
+			let code_id = match code_identifier {
+				CodeIdentifier::CodeId(code_id) => code_id,
+				CodeIdentifier::CodeHash(code_hash) =>
+					CodeHashToId::<T>::try_get(code_hash).map_err(|_| Error::<T>::CodeNotFound)?,
+			};
+			let code_info = CodeIdToInfo::<T>::get(code_id).ok_or(Error::<T>::CodeNotFound)?;
+			if let Err(origin) = T::UploadOrigin::try_origin(origin) {
+				let who = ensure_signed(origin)?;
+				ensure!(who == code_info.creator, Error::<T>::NotAuthorized);
+			}
+			ensure!(code_info.refcount == 0, Error::<T>::CodeInUse);
+			let code_size = Self::do_release_code(code_id, &code_info)?;
+			CodeIdToInfo::<T>::remove(code_id);
+			Ok(Some(T::WeightInfo::remove_code(code_size)).into())
+		}
+
+		/// Update the policy restricting who is able to upload code.
+		///
+		/// * Emits an `UploadPolicyUpdated` event.
+		#[pallet::weight(T::WeightInfo::set_upload_policy())]
+		pub fn set_upload_policy(origin: OriginFor<T>, policy: UploadPolicy) -> DispatchResult {
+			T::UploadOrigin::ensure_origin(origin)?;
+			CodeUploadPolicy::<T>::put(policy);
+			Self::deposit_event(Event::<T>::UploadPolicyUpdated { policy });
+			Ok(())
+		}
+
+		/// Add or remove an account from the upload allowlist, enforced under
+		/// [`UploadPolicy::Allowlist`].
+		///
+		/// * Emits an `UploadAllowlistUpdated` event.
+		#[pallet::weight(T::WeightInfo::update_upload_allowlist())]
+		pub fn update_upload_allowlist(
+			origin: OriginFor<T>,
+			account: AccountIdOf<T>,
+			allowed: bool,
+		) -> DispatchResult {
+			T::UploadOrigin::ensure_origin(origin)?;
+			if allowed {
+				UploadAllowlist::<T>::insert(&account, ());
+			} else {
+				UploadAllowlist::<T>::remove(&account);
+			}
+			Self::deposit_event(Event::<T>::UploadAllowlistUpdated { account, allowed });
+			Ok(())
+		}
+
+		/// Call the `sudo` export of a previously instantiated contract.
+		///
+		/// The contract is called on behalf of the [`Config::SudoAccount`], which is also the
+		/// source of the `funds` and storage deposits.
+		///
+		/// * Emits an `Executed` event.
+		/// * Possibly emit `Emitted` events.
+		///
+		/// Arguments
+		///
+		/// * `origin` the origin dispatching the extrinsic, must be the [`Config::SudoOrigin`].
+		/// * `contract` the address of the contract to call.
+		/// * `funds` the assets transferred to the contract prior to calling it's `sudo` export.
+		/// * `gas` the maximum gas to use, the remaining is refunded at the end of the transaction.
+		/// * `message` SudoMsg, that will be passed to the contract.
+		#[transactional]
+		// NOTE: `sudo` has the same cost profile as `execute`.
+		#[pallet::weight(T::WeightInfo::execute(funds.len() as u32).saturating_add(*gas))]
+		pub fn sudo(
+			origin: OriginFor<T>,
+			contract: AccountIdOf<T>,
+			funds: FundsOf<T>,
+			gas: u64,
+			message: ContractMessageOf<T>,
+		) -> DispatchResultWithPostInfo {
+			T::SudoOrigin::ensure_origin(origin)?;
+			let mut shared = Self::do_create_vm_shared(gas, InitialStorageMutability::ReadWrite);
+			let initial_gas = T::WeightInfo::execute(funds.len() as u32).saturating_add(gas);
+			let outcome = Self::do_sudo(&mut shared, contract, funds, message);
+			Self::refund_gas(outcome, initial_gas, shared.gas.remaining())
+		}
+
+		/// Allow or disallow a contract to send XCM programs and transfers through [`XcmMsg`].
+		/// The programs are executed from the location of the contract.
+		///
+		/// * Emits an `XcmSendAllowlistUpdated` event.
+		#[pallet::weight(T::WeightInfo::update_xcm_send_allowlist())]
+		pub fn update_xcm_send_allowlist(
+			origin: OriginFor<T>,
+			contract: AccountIdOf<T>,
+			allowed: bool,
+		) -> DispatchResult {
+			T::SudoOrigin::ensure_origin(origin)?;
+			if allowed {
+				XcmSendAllowlist::<T>::insert(&contract, ());
+			} else {
+				XcmSendAllowlist::<T>::remove(&contract);
+			}
+			Self::deposit_event(Event::<T>::XcmSendAllowlistUpdated { contract, allowed });
+			Ok(())
+		}
 	}
 
 	impl<T: Config> Pallet<T> {
@@ -566,7 +862,7 @@ pub mod pallet {
 		/// This state is shared across all VMs (all contracts loaded within a single call) and is
 		/// used to optimize some operations as well as track shared state (readonly storage while
 		/// doing a `query` etc...)
-		pub(crate) fn do_create_vm_shared(
+		pub fn do_create_vm_shared(
 			gas: u64,
 			storage_mutability: InitialStorageMutability,
 		) -> CosmwasmVMShared {
@@ -702,19 +998,7 @@ pub mod pallet {
 							.checked_sub(1)
 							.ok_or(Error::<T>::RefcountOverflow)?;
 						if code_info.refcount == 0 {
-							// Unreserve the bonded funds for this code
-							let code = PristineCode::<T>::try_get(info.code_id)
-								.map_err(|_| Error::<T>::CodeNotFound)?;
-							let deposit =
-								code.len().saturating_mul(T::CodeStorageByteDeposit::get() as _);
-							let _ = T::NativeAsset::unreserve(
-								&code_info.creator,
-								deposit.saturated_into(),
-							);
-							let code_hash = T::Hashing::hash(&code);
-							PristineCode::<T>::remove(info.code_id);
-							InstrumentedCode::<T>::remove(info.code_id);
-							CodeHashToId::<T>::remove(code_hash);
+							Self::do_release_code(info.code_id, code_info)?;
 							// Code is unused after this point, so it can be removed
 							*entry = None;
 						}
@@ -723,6 +1007,10 @@ pub mod pallet {
 				)?;
 			}
 
+			if info.code_id != code_id {
+				CodeIdToContracts::<T>::remove(info.code_id, contract);
+				CodeIdToContracts::<T>::insert(code_id, contract, ());
+			}
 			info.code_id = code_id;
 			info.admin = admin;
 			info.label = label
@@ -735,6 +1023,32 @@ pub mod pallet {
 			Ok(())
 		}
 
+		/// Remove the pristine and instrumented versions of a code and unreserve the deposit of its
+		/// creator. The code must not be referenced by any contract, removing the [`CodeInfo`] is
+		/// left to the caller. Returns the size of the released code.
+		pub(crate) fn do_release_code(
+			code_id: CosmwasmCodeId,
+			code_info: &CodeInfoOf<T>,
+		) -> Result<u32, Error<T>> {
+			let code = PristineCode::<T>::try_get(code_id).map_err(|_| Error::<T>::CodeNotFound)?;
+			let deposit = code.len().saturating_mul(T::CodeStorageByteDeposit::get() as _);
+			let _ = T::NativeAsset::unreserve(&code_info.creator, deposit.saturated_into());
+			PristineCode::<T>::remove(code_id);
+			InstrumentedCode::<T>::remove(code_id);
+			CodeHashToId::<T>::remove(code_info.pristine_code_hash);
+			Self::deposit_event(Event::<T>::CodeRemoved { code_id });
+			Ok(code.len() as u32)
+		}
+
+		/// Ensure that the [`UploadPolicy`] allows an account to upload code.
+		pub(crate) fn ensure_can_upload(who: &AccountIdOf<T>) -> Result<(), Error<T>> {
+			match CodeUploadPolicy::<T>::get() {
+				UploadPolicy::Permissionless => Ok(()),
+				UploadPolicy::Allowlist if UploadAllowlist::<T>::contains_key(who) => Ok(()),
+				_ => Err(Error::<T>::UploadNotAllowed),
+			}
+		}
+
 		/// Handy wrapper to return contract info.
 		pub(crate) fn contract_info(
 			contract: &AccountIdOf<T>,
@@ -842,8 +1156,7 @@ pub mod pallet {
 			let code_hash = T::Hashing::hash(&code);
 			ensure!(!CodeHashToId::<T>::contains_key(code_hash), Error::<T>::CodeAlreadyExists);
 			let deposit = code.len().saturating_mul(T::CodeStorageByteDeposit::get() as _);
-			// TODO: release this when the code is destroyed, a.k.a. refcount => 0 after a contract
-			// migration for instance.
+			// Released when the code is removed, see [`Pallet::do_release_code`].
 			T::NativeAsset::reserve(who, deposit.saturated_into())
 				.map_err(|_| Error::<T>::NotEnoughFundsForUpload)?;
 			let module = Self::do_load_module(&code)?;
@@ -897,6 +1210,19 @@ pub mod pallet {
 			EntryPointCaller::<ExecuteCall>::setup(who, contract)?.call(shared, funds, message)
 		}
 
+		/// Call the `sudo` export of a contract on behalf of the [`Config::SudoAccount`].
+		///
+		/// No origin is checked, the caller must have ensured the [`Config::SudoOrigin`].
+		pub(crate) fn do_sudo(
+			shared: &mut CosmwasmVMShared,
+			contract: AccountIdOf<T>,
+			funds: FundsOf<T>,
+			message: ContractMessageOf<T>,
+		) -> Result<(), CosmwasmVMError<T>> {
+			EntryPointCaller::<SudoCall>::setup(T::SudoAccount::get(), contract)?
+				.call(shared, funds, message)
+		}
+
 		fn do_migrate(
 			shared: &mut CosmwasmVMShared,
 			who: AccountIdOf<T>,
@@ -1131,12 +1457,86 @@ pub mod pallet {
 		) -> Result<(), CosmwasmVMError<T>> {
 			let price = Self::do_db_write_gas(&vm.contract_info.trie_id, key, value);
 			vm.charge_raw(price)?;
+			let (previous_usage, next_usage) =
+				Self::with_db_entry(&vm.contract_info.trie_id, key, |child_trie, entry| {
+					(
+						Self::db_entry_usage(&entry, storage::child::len(&child_trie, &entry)),
+						Self::db_entry_usage(&entry, Some(value.len() as u32)),
+					)
+				});
+			Self::do_update_storage_usage(vm, previous_usage, next_usage)?;
 			Self::with_db_entry(&vm.contract_info.trie_id, key, |child_trie, entry| {
 				storage::child::put_raw(&child_trie, &entry, value)
 			});
 			Ok(())
 		}
 
+		/// Number of bytes used by an entry, its hashed key included.
+		fn db_entry_usage(entry: &[u8], value_len: Option<u32>) -> u64 {
+			value_len.map_or(0, |value_len| (entry.len() as u64).saturating_add(value_len.into()))
+		}
+
+		/// Reserve or release the storage deposit of the executing contract, according to the
+		/// change of its storage usage.
+		///
+		/// The whole deposit is paid by the sender of the message and reserved on the contract,
+		/// failing with [`Error::NotEnoughFundsForStorageDeposit`] if the sender can't afford it.
+		/// Only the bytes covered by a deposit are released, see [`ContractInfo::storage_usage`].
+		/// The update of the contract info and the balance operations are charged on top of the
+		/// benchmarked `db_write` and `db_remove`, nothing happens if the usage is unchanged.
+		pub(crate) fn do_update_storage_usage(
+			vm: &mut CosmwasmVM<T>,
+			previous_usage: u64,
+			next_usage: u64,
+		) -> Result<(), CosmwasmVMError<T>> {
+			if previous_usage == next_usage {
+				return Ok(())
+			}
+			// The contract info and the account of the contract.
+			vm.charge_raw(T::DbWeight::get().reads_writes(2, 2))?;
+			let contract = vm.contract_address.as_ref().clone();
+			let byte_deposit = u128::from(T::ContractStorageByteDeposit::get());
+			let storage_usage = if next_usage > previous_usage {
+				let usage = next_usage - previous_usage;
+				let deposit: BalanceOf<T> =
+					u128::from(usage).saturating_mul(byte_deposit).saturated_into();
+				// The account of the sender.
+				vm.charge_raw(T::DbWeight::get().reads_writes(1, 1))?;
+				let sender = Self::cosmwasm_addr_to_account(
+					vm.cosmwasm_message_info.sender.clone().into_string(),
+				)?;
+				// A contract account that does not exist yet must also be provided the existential
+				// deposit.
+				let amount = if T::NativeAsset::total_balance(&contract).is_zero() {
+					deposit.saturating_add(T::NativeAsset::minimum_balance())
+				} else {
+					deposit
+				};
+				T::NativeAsset::transfer(
+					&sender,
+					&contract,
+					amount,
+					ExistenceRequirement::KeepAlive,
+				)
+				.map_err(|_| Error::<T>::NotEnoughFundsForStorageDeposit)?;
+				T::NativeAsset::reserve(&contract, deposit)
+					.map_err(|_| Error::<T>::NotEnoughFundsForStorageDeposit)?;
+				vm.contract_info.storage_usage.saturating_add(usage)
+			} else {
+				let usage = (previous_usage - next_usage).min(vm.contract_info.storage_usage);
+				let deposit = u128::from(usage).saturating_mul(byte_deposit);
+				let _ = T::NativeAsset::unreserve(&contract, deposit.saturated_into());
+				vm.contract_info.storage_usage - usage
+			};
+			vm.contract_info.storage_usage = storage_usage;
+			ContractToInfo::<T>::try_mutate(&contract, |entry| -> Result<(), Error<T>> {
+				let info = entry.as_mut().ok_or(Error::<T>::ContractNotFound)?;
+				info.storage_usage = storage_usage;
+				Ok(())
+			})?;
+			Ok(())
+		}
+
 		/// Create an empty iterator.
 		pub(crate) fn do_db_scan(vm: &mut CosmwasmVM<T>) -> Result<u32, CosmwasmVMError<T>> {
 			let iterator_id = vm.iterators.len() as u32;
@@ -1169,12 +1569,23 @@ pub mod pallet {
 			}
 		}
 
-		/// Remove an entry from the executing contract, no gas is charged for this operation.
-		pub(crate) fn do_db_remove<'a>(vm: &'a mut CosmwasmVM<T>, key: &[u8]) {
-			let trie_id = &vm.contract_info.trie_id;
-			Self::with_db_entry(trie_id, key, |child_trie, entry| {
+		/// Remove an entry from the executing contract, charging the read of the entry prior to
+		/// actually removing it. The storage deposit of the entry is released.
+		pub(crate) fn do_db_remove<'a>(
+			vm: &'a mut CosmwasmVM<T>,
+			key: &[u8],
+		) -> Result<(), CosmwasmVMError<T>> {
+			let price = Self::do_db_read_gas(&vm.contract_info.trie_id, key);
+			vm.charge_raw(price)?;
+			let previous_usage =
+				Self::with_db_entry(&vm.contract_info.trie_id, key, |child_trie, entry| {
+					Self::db_entry_usage(&entry, storage::child::len(&child_trie, &entry))
+				});
+			Self::do_update_storage_usage(vm, previous_usage, 0)?;
+			Self::with_db_entry(&vm.contract_info.trie_id, key, |child_trie, entry| {
 				storage::child::kill(&child_trie, &entry)
-			})
+			});
+			Ok(())
 		}
 
 		pub(crate) fn do_running_contract_meta(
@@ -1228,6 +1639,251 @@ pub mod pallet {
 			Ok(T::Assets::balance(asset, account).into())
 		}
 
+		/// Retrieve the non-zero balances of an account over [`Config::AssetsList`].
+		pub(crate) fn do_all_balance(
+			account: &AccountIdOf<T>,
+			assets: Vec<AssetIdOf<T>>,
+		) -> Vec<Coin> {
+			assets
+				.into_iter()
+				.filter_map(|asset| {
+					let amount = T::Assets::balance(asset, account);
+					if amount.is_zero() {
+						None
+					} else {
+						Some(Self::native_asset_to_cosmwasm_asset(asset, amount))
+					}
+				})
+				.collect()
+		}
+
+		/// Burn funds owned by an account, without reaping it.
+		pub(crate) fn do_burn(from: &AccountIdOf<T>, funds: &[Coin]) -> Result<(), Error<T>> {
+			for Coin { denom, amount } in funds {
+				let asset = Self::cosmwasm_asset_to_native_asset(denom.clone())?;
+				let amount: BalanceOf<T> = amount.u128().saturated_into();
+				// Only the funds the account is free to spend can be burnt.
+				ensure!(
+					T::Assets::reducible_balance(asset, from, true) >= amount,
+					Error::<T>::BurnFailed
+				);
+				T::Assets::burn_from(asset, from, amount).map_err(|_| Error::<T>::BurnFailed)?;
+			}
+			Ok(())
+		}
+
+		/// Answer a custom query of a contract, see [`ComposableQuery`].
+		pub(crate) fn do_query_custom(query: ComposableQuery) -> Result<Vec<u8>, DispatchError> {
+			let response = match query {
+				ComposableQuery::Oracle(OracleQuery::Price { denom, amount }) => {
+					let asset = Self::cosmwasm_asset_to_native_asset(denom)?;
+					let price = T::Oracle::get_price(asset, amount.0.saturated_into())?;
+					serde_json::to_vec(&PriceResponse {
+						price: Displayed(price.price.into()),
+						block: price.block.saturated_into(),
+					})
+				},
+				ComposableQuery::Oracle(OracleQuery::Twap { denom, amount }) => {
+					let asset = Self::cosmwasm_asset_to_native_asset(denom)?;
+					let price = T::Oracle::get_twap_for_amount(asset, amount.0.saturated_into())?;
+					serde_json::to_vec(&TwapResponse { price: Displayed(price.into()) })
+				},
+				ComposableQuery::Pablo(PabloQuery::SpotPrice {
+					pool_id,
+					base_asset,
+					quote_denom,
+					calculate_with_fees,
+				}) => {
+					let base_asset = Self::composable_coin_to_native_asset(base_asset)?;
+					let quote_asset_id = Self::cosmwasm_asset_to_native_asset(quote_denom)?;
+					let result = T::Pablo::spot_price(
+						pool_id.0,
+						base_asset,
+						quote_asset_id,
+						calculate_with_fees,
+					)?;
+					serde_json::to_vec(&Self::swap_result_to_response(result))
+				},
+				ComposableQuery::Assets(AssetsQuery::Metadata { denom }) => {
+					let asset = Self::cosmwasm_asset_to_native_asset(denom.clone())?;
+					let decimals = T::LocalAssets::decimals(asset)?;
+					serde_json::to_vec(&AssetMetadataResponse { denom, decimals })
+				},
+			};
+			response.map_err(|_| Error::<T>::FailedToSerialize.into())
+		}
+
+		/// Compute the gas required to answer the given custom query.
+		pub(crate) fn do_query_custom_gas(query: &ComposableQuery) -> u64 {
+			match query {
+				ComposableQuery::Oracle(OracleQuery::Price { .. }) =>
+					T::WeightInfo::query_oracle_price(),
+				ComposableQuery::Oracle(OracleQuery::Twap { .. }) =>
+					T::WeightInfo::query_oracle_twap(),
+				ComposableQuery::Pablo(PabloQuery::SpotPrice { .. }) =>
+					T::WeightInfo::query_pablo_spot_price(),
+				ComposableQuery::Assets(AssetsQuery::Metadata { .. }) =>
+					T::WeightInfo::query_assets_metadata(),
+			}
+		}
+
+		/// Compute the gas required to execute the given custom message.
+		///
+		/// XCM programs are charged per byte, the cost of their execution on the destination being
+		/// paid with the assets they carry.
+		pub(crate) fn do_message_custom_gas(message: &ComposableMsg) -> u64 {
+			match message {
+				ComposableMsg::Pablo(PabloMsg::Swap { .. }) => T::WeightInfo::message_pablo_swap(),
+				ComposableMsg::StakingRewards(StakingRewardsMsg::Stake { .. }) =>
+					T::WeightInfo::message_staking_stake(),
+				ComposableMsg::StakingRewards(StakingRewardsMsg::Claim { .. }) =>
+					T::WeightInfo::message_staking_claim(),
+				ComposableMsg::Xcm(XcmMsg::Send { message, .. }) =>
+					T::WeightInfo::xcm_send(message.len() as u32),
+				ComposableMsg::Xcm(XcmMsg::Transfer { .. }) => T::WeightInfo::xcm_transfer(),
+			}
+		}
+
+		/// Dispatch a custom message of a contract, see [`ComposableMsg`].
+		///
+		/// The message is executed on behalf of the contract, using the funds it owns.
+		pub(crate) fn do_message_custom(
+			contract: &AccountIdOf<T>,
+			message: ComposableMsg,
+		) -> Result<Option<Vec<u8>>, Error<T>> {
+			let data = match message {
+				ComposableMsg::Pablo(PabloMsg::Swap {
+					pool_id,
+					in_asset,
+					min_receive,
+					keep_alive,
+				}) => {
+					let in_asset = Self::composable_coin_to_native_asset(in_asset)?;
+					let min_receive = Self::composable_coin_to_native_asset(min_receive)?;
+					let result =
+						T::Pablo::do_swap(contract, pool_id.0, in_asset, min_receive, keep_alive)
+							.map_err(|_| Error::<T>::SwapFailed)?;
+					serde_json::to_vec(&Self::swap_result_to_response(result))
+				},
+				ComposableMsg::StakingRewards(StakingRewardsMsg::Stake {
+					pool_id,
+					amount,
+					duration_preset,
+					keep_alive,
+				}) => {
+					let pool_id = Self::cosmwasm_asset_to_native_asset(pool_id)?;
+					let (fnft_collection_id, fnft_instance_id) = T::StakingRewards::stake(
+						contract,
+						&pool_id,
+						amount.0.saturated_into(),
+						duration_preset,
+						keep_alive,
+					)
+					.map_err(|_| Error::<T>::StakingFailed)?;
+					serde_json::to_vec(&StakeResponse {
+						fnft_collection_id: T::AssetToDenom::convert(fnft_collection_id),
+						fnft_instance_id,
+					})
+				},
+				ComposableMsg::StakingRewards(StakingRewardsMsg::Claim {
+					fnft_collection_id,
+					fnft_instance_id,
+				}) => {
+					let fnft_collection_id =
+						Self::cosmwasm_asset_to_native_asset(fnft_collection_id)?;
+					T::StakingRewards::claim(contract, &(fnft_collection_id, fnft_instance_id))
+						.map_err(|_| Error::<T>::StakingFailed)?;
+					return Ok(None)
+				},
+				ComposableMsg::Xcm(XcmMsg::Send { destination, message }) => {
+					let destination = VersionedMultiLocation::decode(&mut &destination[..])
+						.ok()
+						.and_then(|destination| MultiLocation::try_from(destination).ok())
+						.ok_or(Error::<T>::XcmDecodingFailed)?;
+					let mut message = VersionedXcm::<()>::decode_all_with_depth_limit(
+						MAX_XCM_DECODE_DEPTH,
+						&mut &message[..],
+					)
+					.ok()
+					.and_then(|message| Xcm::<()>::try_from(message).ok())
+					.ok_or(Error::<T>::XcmDecodingFailed)?;
+					// Same as `pallet_xcm::send`, the program is executed from the location of
+					// the contract.
+					let interior: Junctions =
+						T::AccountIdToMultiLocation::convert(contract.clone())
+							.try_into()
+							.map_err(|_| Error::<T>::XcmSendFailed)?;
+					if interior != Junctions::Here {
+						message.0.insert(0, Instruction::DescendOrigin(interior));
+					}
+					Self::charge_xcm_delivery(contract)?;
+					T::XcmSender::send_xcm(destination, message)
+						.map_err(|_| Error::<T>::XcmSendFailed)?;
+					return Ok(None)
+				},
+				ComposableMsg::Xcm(XcmMsg::Transfer {
+					asset,
+					para_id,
+					beneficiary,
+					dest_weight,
+				}) => {
+					let AssetAmount { asset_id, amount } =
+						Self::composable_coin_to_native_asset(asset)?;
+					let destination = para_id
+						.map_or(topology::relay::LOCATION, topology::this::sibling)
+						.pushed_with_interior(Junction::AccountId32 {
+							network: NetworkId::Any,
+							id: beneficiary,
+						})
+						.map_err(|_| Error::<T>::XcmDecodingFailed)?;
+					Self::charge_xcm_delivery(contract)?;
+					T::XcmTransfer::transfer(
+						contract.clone(),
+						asset_id,
+						amount,
+						destination,
+						dest_weight,
+					)
+					.map_err(|_| Error::<T>::XcmTransferFailed)?;
+					return Ok(None)
+				},
+			};
+			data.map(Some).map_err(|_| Error::<T>::FailedToSerialize)
+		}
+
+		/// Ensure that the contract is allowed to send XCM messages, and burn the
+		/// [`Config::XcmDeliveryFee`] from its funds.
+		fn charge_xcm_delivery(contract: &AccountIdOf<T>) -> Result<(), Error<T>> {
+			ensure!(XcmSendAllowlist::<T>::contains_key(contract), Error::<T>::XcmSendNotAllowed);
+			let fee = T::XcmDeliveryFee::get();
+			if !fee.is_zero() {
+				let _ = T::NativeAsset::withdraw(
+					contract,
+					fee,
+					WithdrawReasons::FEE,
+					ExistenceRequirement::KeepAlive,
+				)
+				.map_err(|_| Error::<T>::NotEnoughFundsForXcmDeliveryFee)?;
+			}
+			Ok(())
+		}
+
+		fn composable_coin_to_native_asset(
+			ComposableCoin { denom, amount }: ComposableCoin,
+		) -> Result<AssetAmount<AssetIdOf<T>, BalanceOf<T>>, Error<T>> {
+			let asset = Self::cosmwasm_asset_to_native_asset(denom)?;
+			Ok(AssetAmount::new(asset, amount.0.saturated_into()))
+		}
+
+		fn swap_result_to_response(
+			SwapResult { value, fee }: SwapResult<AssetIdOf<T>, BalanceOf<T>>,
+		) -> SwapResponse {
+			let to_coin = |AssetAmount { asset_id, amount }: AssetAmount<_, BalanceOf<T>>| {
+				ComposableCoin::new(amount.into(), T::AssetToDenom::convert(asset_id))
+			};
+			SwapResponse { value: to_coin(value), fee: to_coin(fee) }
+		}
+
 		/// Execute a transfer of funds between two accounts.
 		pub(crate) fn do_transfer(
 			from: &AccountIdOf<T>,
@@ -1579,6 +2235,159 @@ pub mod pallet {
 		)
 	}
 
+	/// Simulate an `execute` call against the current state, without committing it.
+	///
+	/// * `executor` the account executing the contract.
+	/// * `contract` the address of the contract to execute.
+	/// * `funds` the assets transferred to the contract prior to calling it's `execute` export.
+	/// * `gas` the maximum gas to use.
+	/// * `message` ExecuteMsg, that will be passed to the contract.
+	pub fn dry_run_execute<T: Config>(
+		executor: AccountIdOf<T>,
+		contract: AccountIdOf<T>,
+		funds: BTreeMap<AssetIdOf<T>, (BalanceOf<T>, KeepAlive)>,
+		gas: u64,
+		message: Vec<u8>,
+	) -> Result<DryRunResult, CosmwasmVMError<T>> {
+		let funds: FundsOf<T> = funds
+			.try_into()
+			.map_err(|_| CosmwasmVMError::Rpc(String::from("'funds' is too large")))?;
+		let message: ContractMessageOf<T> = message
+			.try_into()
+			.map_err(|_| CosmwasmVMError::Rpc(String::from("'message' is too large")))?;
+		dry_run::<T, _>(gas, |shared| {
+			EntryPointCaller::<ExecuteCall>::setup(executor, contract)?
+				.dry_run(shared, funds, message)
+		})
+	}
+
+	/// Simulate a `migrate` call against the current state, without committing it.
+	///
+	/// * `migrator` the account migrating the contract, which must be its admin.
+	/// * `contract` the address of the contract to migrate.
+	/// * `new_code_id` the code id that we want to switch to.
+	/// * `gas` the maximum gas to use.
+	/// * `message` MigrateMsg, that will be passed to the contract.
+	pub fn dry_run_migrate<T: Config>(
+		migrator: AccountIdOf<T>,
+		contract: AccountIdOf<T>,
+		new_code_id: CosmwasmCodeId,
+		gas: u64,
+		message: Vec<u8>,
+	) -> Result<DryRunResult, CosmwasmVMError<T>> {
+		let message: ContractMessageOf<T> = message
+			.try_into()
+			.map_err(|_| CosmwasmVMError::Rpc(String::from("'message' is too large")))?;
+		dry_run::<T, _>(gas, |shared| {
+			EntryPointCaller::<MigrateCall>::setup(shared, migrator, contract, new_code_id)?
+				.dry_run(shared, Default::default(), message)
+		})
+	}
+
+	/// Metadata of an instantiated contract.
+	///
+	/// * `contract` the address of the contract.
+	pub fn contract_info<T: Config>(
+		contract: AccountIdOf<T>,
+	) -> Result<ContractInfoResult<AccountIdOf<T>>, CosmwasmVMError<T>> {
+		let info = Pallet::<T>::contract_info(&contract)?;
+		Ok(ContractInfoResult {
+			code_id: info.code_id,
+			instantiator: info.instantiator,
+			admin: info.admin,
+			label: info.label.into(),
+		})
+	}
+
+	/// Page through the raw entries of a contract storage.
+	///
+	/// Entries are keyed by the hash of their key, a `prefix` is consequently not able to narrow
+	/// the iteration and the whole storage may be scanned.
+	///
+	/// * `contract` the address of the contract.
+	/// * `prefix` only return the entries whose key starts with this prefix.
+	/// * `start_after` resume the iteration after this key, i.e. the `next` key of a page.
+	/// * `limit` the maximum number of keys visited, capped to [`MAX_CONTRACT_STATE_PAGE_SIZE`].
+	///   Fewer entries are returned if some of the visited keys do not match the `prefix`.
+	pub fn contract_state<T: Config>(
+		contract: AccountIdOf<T>,
+		prefix: Option<Vec<u8>>,
+		start_after: Option<Vec<u8>>,
+		limit: u32,
+	) -> Result<ContractStatePage, CosmwasmVMError<T>> {
+		let info = Pallet::<T>::contract_info(&contract)?;
+		let child_trie = Pallet::<T>::contract_child_trie(info.trie_id.as_ref());
+		let limit = limit.min(MAX_CONTRACT_STATE_PAGE_SIZE) as usize;
+		let mut raw_key = start_after.map(|key| Blake2_128Concat::hash(&key)).unwrap_or_default();
+		let mut entries = Vec::new();
+		for _ in 0..limit {
+			match sp_io::default_child_storage::next_key(child_trie.storage_key(), &raw_key) {
+				Some(next_raw_key) => raw_key = next_raw_key,
+				None => return Ok(ContractStatePage { entries, next: None }),
+			}
+			let key = Blake2_128Concat::reverse(&raw_key);
+			if prefix.as_ref().map_or(true, |prefix| key.starts_with(prefix)) {
+				if let Some(value) = storage::child::get_raw(&child_trie, &raw_key) {
+					entries.push((key.to_vec(), value));
+				}
+			}
+		}
+		let next = (limit > 0).then(|| Blake2_128Concat::reverse(&raw_key).to_vec());
+		Ok(ContractStatePage { entries, next })
+	}
+
+	/// Page through the contracts currently baked by the code `code_id`.
+	///
+	/// * `code_id` the unique code id generated when the code has been uploaded.
+	/// * `start_after` resume the iteration after this contract, i.e. the last one of a page.
+	/// * `limit` the maximum number of contracts, capped to [`MAX_CONTRACTS_BY_CODE_PAGE_SIZE`].
+	pub fn contracts_by_code<T: Config>(
+		code_id: CosmwasmCodeId,
+		start_after: Option<AccountIdOf<T>>,
+		limit: u32,
+	) -> Vec<AccountIdOf<T>> {
+		let limit = limit.min(MAX_CONTRACTS_BY_CODE_PAGE_SIZE) as usize;
+		match start_after {
+			Some(contract) => CodeIdToContracts::<T>::iter_key_prefix_from(
+				code_id,
+				CodeIdToContracts::<T>::hashed_key_for(code_id, contract),
+			)
+			.take(limit)
+			.collect(),
+			None => CodeIdToContracts::<T>::iter_key_prefix(code_id).take(limit).collect(),
+		}
+	}
+
+	/// Run `call` in a storage transaction that is always rolled back, collecting the gas it used
+	/// along with the data and events of the contract.
+	fn dry_run<T: Config, F>(gas: u64, call: F) -> Result<DryRunResult, CosmwasmVMError<T>>
+	where
+		F: FnOnce(
+			&mut CosmwasmVMShared,
+		) -> Result<(Option<CosmwasmBinary>, Vec<CosmwasmEvent>), CosmwasmVMError<T>>,
+	{
+		let outcome = with_transaction(|| {
+			let mut shared =
+				Pallet::<T>::do_create_vm_shared(gas, InitialStorageMutability::ReadWrite);
+			let outcome = call(&mut shared).map(|(data, events)| DryRunResult {
+				gas_used: gas.saturating_sub(shared.gas.remaining()),
+				data: data.map(Into::into),
+				events: events
+					.into_iter()
+					.map(|CosmwasmEvent { ty, attributes, .. }| ContractEvent {
+						ty: ty.into(),
+						attributes: attributes
+							.into_iter()
+							.map(|CosmwasmEventAttribute { key, value }| (key.into(), value.into()))
+							.collect(),
+					})
+					.collect(),
+			});
+			TransactionOutcome::Rollback(Ok::<_, DispatchError>(outcome))
+		});
+		outcome.map_err(|e| CosmwasmVMError::Rpc(format!("{:?}", e)))?
+	}
+
 	impl<T: Config> VMPallet for T {
 		type VmError = CosmwasmVMError<T>;
 	}
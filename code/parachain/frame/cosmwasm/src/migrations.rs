@@ -0,0 +1,54 @@
+//! Storage migrations of the pallet.
+
+use crate::{CodeIdToContracts, Config, ContractToInfo, Pallet};
+use core::marker::PhantomData;
+use frame_support::{
+	traits::{GetStorageVersion, OnRuntimeUpgrade, StorageVersion},
+	weights::Weight,
+};
+
+/// Introduction of [`crate::types::ContractInfo::storage_usage`].
+pub mod v1 {
+	use super::*;
+
+	/// Mark the introduction of the storage usage.
+	///
+	/// The existing contracts are migrated lazily: their info decodes with no usage until it is
+	/// next written. The storage they wrote before is free, no deposit being reserved for it nor
+	/// released when it is removed.
+	pub struct AddStorageUsage<T>(PhantomData<T>);
+
+	impl<T: Config> OnRuntimeUpgrade for AddStorageUsage<T> {
+		fn on_runtime_upgrade() -> Weight {
+			if Pallet::<T>::on_chain_storage_version() >= 1 {
+				return T::DbWeight::get().reads(1)
+			}
+			StorageVersion::new(1).put::<Pallet<T>>();
+			T::DbWeight::get().reads_writes(1, 1)
+		}
+	}
+}
+
+/// Introduction of [`CodeIdToContracts`].
+pub mod v2 {
+	use super::*;
+
+	/// Index the existing contracts by code id.
+	pub struct IndexContractsByCode<T>(PhantomData<T>);
+
+	impl<T: Config> OnRuntimeUpgrade for IndexContractsByCode<T> {
+		fn on_runtime_upgrade() -> Weight {
+			if Pallet::<T>::on_chain_storage_version() >= 2 {
+				return T::DbWeight::get().reads(1)
+			}
+			let mut contracts = 0_u64;
+			for (contract, info) in ContractToInfo::<T>::iter() {
+				CodeIdToContracts::<T>::insert(info.code_id, contract, ());
+				contracts = contracts.saturating_add(1);
+			}
+			StorageVersion::new(2).put::<Pallet<T>>();
+			T::DbWeight::get()
+				.reads_writes(contracts.saturating_add(1), contracts.saturating_add(1))
+		}
+	}
+}
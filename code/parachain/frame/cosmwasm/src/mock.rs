@@ -3,22 +3,34 @@ use core::marker::PhantomData;
 use crate::*;
 
 use crate::instrument::CostRules;
-use composable_traits::currency::{CurrencyFactory, RangeId};
+use composable_traits::{
+	currency::{CurrencyFactory, RangeId},
+	defi::{CurrencyPair, Ratio},
+	dex::{Amm, AssetAmount, SwapResult},
+	oracle::{Oracle, Price},
+	staking::Staking,
+	time::DurationSeconds,
+};
 use frame_support::{
 	pallet_prelude::ConstU32,
 	parameter_types,
-	traits::{ConstU64, Everything},
+	traits::{ConstU64, Everything, Get},
 	PalletId,
 };
 use frame_system::EnsureRoot;
 use num_traits::Zero;
-use orml_traits::parameter_type_with_key;
+use orml_traits::{parameter_type_with_key, xcm_transfer::Transferred, XcmTransfer};
 use primitives::currency::{CurrencyId, ValidateCurrencyId};
 use sp_core::H256;
 use sp_runtime::{
 	generic,
 	traits::{AccountIdConversion, BlakeTwo256, Convert, IdentityLookup},
-	AccountId32, DispatchError,
+	AccountId32, DispatchError, DispatchResult, Permill,
+};
+use sp_std::{cell::RefCell, collections::btree_map::BTreeMap};
+use xcm::latest::{
+	Junction, Junctions, Junctions::X1, MultiAsset, MultiAssets, MultiLocation, NetworkId,
+	SendResult, SendXcm, Weight, Xcm,
 };
 
 type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
@@ -196,9 +208,22 @@ impl Convert<CurrencyId, alloc::string::String> for AssetToDenom {
 	}
 }
 
+/// Assets enumerated by `BankQuery::AllBalances`.
+pub struct AssetsList;
+impl Get<Vec<CurrencyId>> for AssetsList {
+	fn get() -> Vec<CurrencyId> {
+		CurrencyId::list_assets()
+			.into_iter()
+			.map(|asset| CurrencyId(asset.id))
+			.collect()
+	}
+}
+
 parameter_types! {
 	pub const CosmwasmPalletId: PalletId = PalletId(*b"cosmwasm");
 	pub IbcRelayerAccount: AccountId = PalletId(*b"centauri").into_account_truncating();
+	pub SudoAccount: AccountId = PalletId(*b"cw/sudo_").into_account_truncating();
+	pub const XcmDeliveryFee: Balance = 1_000;
 	pub const ChainId: &'static str = "composable-network-dali";
 	pub const MaxFrames: u32 = 64;
 	pub const MaxCodeSize: u32 = 512 * 1024;
@@ -208,6 +233,7 @@ parameter_types! {
 	pub const MaxContractTrieIdSize: u32 = H256::len_bytes() as u32;
 	pub const MaxInstantiateSaltSize: u32 = 128;
 	pub const MaxFundsAssets: u32 = 32;
+	pub const MaxAssetsList: u32 = 32;
 	pub const CodeTableSizeLimit: u32 = 4096;
 	pub const CodeGlobalVariableLimit: u32 = 256;
 	pub const CodeParameterLimit: u32 = 128;
@@ -215,9 +241,319 @@ parameter_types! {
 	pub const CodeStackLimit: u32 = u32::MAX;
 
 	pub const CodeStorageByteDeposit: u32 = 1;
+	pub const ContractStorageByteDeposit: u32 = 1;
 	pub const ContractStorageByteReadPrice: u32 = 1;
 	pub const ContractStorageByteWritePrice: u32 = 1;
 	pub WasmCostRules: CostRules<Test> = Default::default();
+	pub const MaxAnswerBound: u32 = 25;
+	pub const TwapWindow: u16 = 3;
+}
+
+/// Oracle pricing any asset at twice its amount.
+pub struct MockOracle;
+impl Oracle for MockOracle {
+	type AssetId = CurrencyId;
+	type Balance = Balance;
+	type Timestamp = u32;
+	type LocalAssets = ();
+	type MaxAnswerBound = MaxAnswerBound;
+	type TwapWindow = TwapWindow;
+
+	fn get_price(
+		_asset_id: Self::AssetId,
+		amount: Self::Balance,
+	) -> Result<Price<Self::Balance, Self::Timestamp>, DispatchError> {
+		Ok(Price { price: amount * 2, block: System::block_number() })
+	}
+
+	fn get_twap_for_amount(
+		_asset_id: Self::AssetId,
+		amount: Self::Balance,
+	) -> Result<Self::Balance, DispatchError> {
+		Ok(amount * 2)
+	}
+
+	fn get_ratio(_pair: CurrencyPair<Self::AssetId>) -> Result<Ratio, DispatchError> {
+		Err(DispatchError::Other("unsupported"))
+	}
+
+	fn get_price_inverse(
+		_asset_id: Self::AssetId,
+		_amount: Self::Balance,
+	) -> Result<Self::Balance, DispatchError> {
+		Err(DispatchError::Other("unsupported"))
+	}
+}
+
+/// Single pool `1` quoting any pair one for one, without fees. Swaps are only supported once
+/// enabled by [`MockBenchmarkHelper`].
+pub struct MockPablo;
+impl Amm for MockPablo {
+	type AssetId = CurrencyId;
+	type Balance = Balance;
+	type AccountId = AccountId;
+	type PoolId = u128;
+
+	fn pool_exists(pool_id: Self::PoolId) -> bool {
+		pool_id == 1
+	}
+
+	fn assets(_pool_id: Self::PoolId) -> Result<BTreeMap<Self::AssetId, Permill>, DispatchError> {
+		Err(DispatchError::Other("unsupported"))
+	}
+
+	fn lp_token(_pool_id: Self::PoolId) -> Result<Self::AssetId, DispatchError> {
+		Err(DispatchError::Other("unsupported"))
+	}
+
+	fn redeemable_assets_for_lp_tokens(
+		_pool_id: Self::PoolId,
+		_lp_amount: Self::Balance,
+	) -> Result<BTreeMap<Self::AssetId, Self::Balance>, DispatchError> {
+		Err(DispatchError::Other("unsupported"))
+	}
+
+	fn simulate_add_liquidity(
+		_who: &Self::AccountId,
+		_pool_id: Self::PoolId,
+		_amounts: BTreeMap<Self::AssetId, Self::Balance>,
+	) -> Result<Self::Balance, DispatchError> {
+		Err(DispatchError::Other("unsupported"))
+	}
+
+	fn simulate_remove_liquidity(
+		_who: &Self::AccountId,
+		_pool_id: Self::PoolId,
+		_lp_amount: Self::Balance,
+		_min_amounts: BTreeMap<Self::AssetId, Self::Balance>,
+	) -> Result<BTreeMap<Self::AssetId, Self::Balance>, DispatchError> {
+		Err(DispatchError::Other("unsupported"))
+	}
+
+	fn spot_price(
+		pool_id: Self::PoolId,
+		base_asset: AssetAmount<Self::AssetId, Self::Balance>,
+		quote_asset_id: Self::AssetId,
+		_calculate_with_fees: bool,
+	) -> Result<SwapResult<Self::AssetId, Self::Balance>, DispatchError> {
+		if !Self::pool_exists(pool_id) {
+			return Err(DispatchError::Other("pool not found"))
+		}
+		Ok(SwapResult::new(quote_asset_id, base_asset.amount, base_asset.asset_id, 0))
+	}
+
+	fn do_buy(
+		_who: &Self::AccountId,
+		_pool_id: Self::PoolId,
+		_in_asset_id: Self::AssetId,
+		_out_asset: AssetAmount<Self::AssetId, Self::Balance>,
+		_keep_alive: bool,
+	) -> Result<SwapResult<Self::AssetId, Self::Balance>, DispatchError> {
+		Err(DispatchError::Other("unsupported"))
+	}
+
+	fn add_liquidity(
+		_who: &Self::AccountId,
+		_pool_id: Self::PoolId,
+		_assets: BTreeMap<Self::AssetId, Self::Balance>,
+		_min_mint_amount: Self::Balance,
+		_keep_alive: bool,
+	) -> Result<Self::Balance, DispatchError> {
+		Err(DispatchError::Other("unsupported"))
+	}
+
+	fn remove_liquidity(
+		_who: &Self::AccountId,
+		_pool_id: Self::PoolId,
+		_lp_amount: Self::Balance,
+		_min_receive: BTreeMap<Self::AssetId, Self::Balance>,
+	) -> Result<BTreeMap<Self::AssetId, Self::Balance>, DispatchError> {
+		Err(DispatchError::Other("unsupported"))
+	}
+
+	fn do_swap(
+		_who: &Self::AccountId,
+		pool_id: Self::PoolId,
+		in_asset: AssetAmount<Self::AssetId, Self::Balance>,
+		min_receive: AssetAmount<Self::AssetId, Self::Balance>,
+		_keep_alive: bool,
+	) -> Result<SwapResult<Self::AssetId, Self::Balance>, DispatchError> {
+		if !Self::pool_exists(pool_id) || !SWAPS_ENABLED.with(|enabled| *enabled.borrow()) {
+			return Err(DispatchError::Other("unsupported"))
+		}
+		Ok(SwapResult::new(min_receive.asset_id, in_asset.amount, in_asset.asset_id, 0))
+	}
+}
+
+/// Staking rewards on the pools of [`REWARD_POOLS`], positions being identified by their pool.
+pub struct MockStakingRewards;
+impl Staking for MockStakingRewards {
+	type AccountId = AccountId;
+	type RewardPoolId = CurrencyId;
+	type Balance = Balance;
+	type PositionId = (CurrencyId, u64);
+
+	fn stake(
+		_who: &Self::AccountId,
+		pool_id: &Self::RewardPoolId,
+		_amount: Self::Balance,
+		_duration_preset: DurationSeconds,
+		_keep_alive: bool,
+	) -> Result<Self::PositionId, DispatchError> {
+		if !REWARD_POOLS.with(|pools| pools.borrow().contains(pool_id)) {
+			return Err(DispatchError::Other("pool not found"))
+		}
+		Ok((*pool_id, 0))
+	}
+
+	fn extend(
+		_who: &Self::AccountId,
+		_position: Self::PositionId,
+		_amount: Self::Balance,
+		_keep_alive: bool,
+	) -> DispatchResult {
+		Err(DispatchError::Other("position not found"))
+	}
+
+	fn unstake(_who: &Self::AccountId, _position: &Self::PositionId) -> DispatchResult {
+		Err(DispatchError::Other("position not found"))
+	}
+
+	fn split(
+		_who: &Self::AccountId,
+		_position: &Self::PositionId,
+		_ratio: Permill,
+	) -> Result<Self::PositionId, DispatchError> {
+		Err(DispatchError::Other("position not found"))
+	}
+
+	fn claim(_who: &Self::AccountId, (pool_id, _): &Self::PositionId) -> DispatchResult {
+		if !REWARD_POOLS.with(|pools| pools.borrow().contains(pool_id)) {
+			return Err(DispatchError::Other("position not found"))
+		}
+		Ok(())
+	}
+}
+
+/// Enables swaps on [`MockPablo`] and staking on [`MockStakingRewards`].
+pub struct MockBenchmarkHelper;
+impl BenchmarkHelper<Test> for MockBenchmarkHelper {
+	fn set_price(_asset: CurrencyId) {}
+
+	fn create_pool(_base: CurrencyId, _quote: CurrencyId) -> u128 {
+		SWAPS_ENABLED.with(|enabled| *enabled.borrow_mut() = true);
+		1
+	}
+
+	fn create_reward_pool() -> (CurrencyId, u64) {
+		REWARD_POOLS.with(|pools| pools.borrow_mut().push(CurrencyId::PICA));
+		(CurrencyId::PICA, 3_600)
+	}
+
+	fn prepare_xcm() {}
+}
+
+thread_local! {
+	pub static SWAPS_ENABLED: RefCell<bool> = RefCell::new(false);
+	pub static REWARD_POOLS: RefCell<Vec<CurrencyId>> = RefCell::new(Vec::new());
+	pub static SENT_XCM: RefCell<Vec<(MultiLocation, Xcm<()>)>> = RefCell::new(Vec::new());
+	pub static XCM_TRANSFERS: RefCell<Vec<(AccountId, CurrencyId, Balance, MultiLocation, Weight)>> =
+		RefCell::new(Vec::new());
+}
+
+/// Router recording the sent messages in [`SENT_XCM`].
+pub struct MockXcmSender;
+impl SendXcm for MockXcmSender {
+	fn send_xcm(destination: impl Into<MultiLocation>, message: Xcm<()>) -> SendResult {
+		SENT_XCM.with(|sent| sent.borrow_mut().push((destination.into(), message)));
+		Ok(())
+	}
+}
+
+/// Cross-chain transfers recorded in [`XCM_TRANSFERS`], only single currency transfers are
+/// supported.
+pub struct MockXcmTransfer;
+impl XcmTransfer<AccountId, Balance, CurrencyId> for MockXcmTransfer {
+	fn transfer(
+		who: AccountId,
+		currency_id: CurrencyId,
+		amount: Balance,
+		dest: MultiLocation,
+		dest_weight: Weight,
+	) -> Result<Transferred<AccountId>, DispatchError> {
+		XCM_TRANSFERS.with(|transfers| {
+			transfers.borrow_mut().push((
+				who.clone(),
+				currency_id,
+				amount,
+				dest.clone(),
+				dest_weight,
+			))
+		});
+		Ok(Transferred {
+			sender: who,
+			assets: MultiAssets::new(),
+			fee: (Junctions::Here, 0_u128).into(),
+			dest,
+		})
+	}
+
+	fn transfer_multi_asset(
+		_who: AccountId,
+		_asset: MultiAsset,
+		_dest: MultiLocation,
+		_dest_weight: Weight,
+	) -> Result<Transferred<AccountId>, DispatchError> {
+		Err(DispatchError::Other("unsupported"))
+	}
+
+	fn transfer_with_fee(
+		_who: AccountId,
+		_currency_id: CurrencyId,
+		_amount: Balance,
+		_fee: Balance,
+		_dest: MultiLocation,
+		_dest_weight: Weight,
+	) -> Result<Transferred<AccountId>, DispatchError> {
+		Err(DispatchError::Other("unsupported"))
+	}
+
+	fn transfer_multi_asset_with_fee(
+		_who: AccountId,
+		_asset: MultiAsset,
+		_fee: MultiAsset,
+		_dest: MultiLocation,
+		_dest_weight: Weight,
+	) -> Result<Transferred<AccountId>, DispatchError> {
+		Err(DispatchError::Other("unsupported"))
+	}
+
+	fn transfer_multi_currencies(
+		_who: AccountId,
+		_currencies: Vec<(CurrencyId, Balance)>,
+		_fee_item: u32,
+		_dest: MultiLocation,
+		_dest_weight: Weight,
+	) -> Result<Transferred<AccountId>, DispatchError> {
+		Err(DispatchError::Other("unsupported"))
+	}
+
+	fn transfer_multi_assets(
+		_who: AccountId,
+		_assets: MultiAssets,
+		_fee: MultiAsset,
+		_dest: MultiLocation,
+		_dest_weight: Weight,
+	) -> Result<Transferred<AccountId>, DispatchError> {
+		Err(DispatchError::Other("unsupported"))
+	}
+}
+
+pub struct AccountIdToMultiLocation;
+impl Convert<AccountId, MultiLocation> for AccountIdToMultiLocation {
+	fn convert(account: AccountId) -> MultiLocation {
+		X1(Junction::AccountId32 { network: NetworkId::Any, id: account.into() }).into()
+	}
 }
 
 pub struct IbcLoopback<Config> {
@@ -272,6 +608,16 @@ impl Config for Test {
 	type Balance = Balance;
 	type AssetId = CurrencyId;
 	type Assets = Assets;
+	type AssetsList = AssetsList;
+	type MaxAssetsList = MaxAssetsList;
+	type Oracle = MockOracle;
+	type Pablo = MockPablo;
+	type StakingRewards = MockStakingRewards;
+	type LocalAssets = ();
+	type XcmSender = MockXcmSender;
+	type XcmTransfer = MockXcmTransfer;
+	type XcmDeliveryFee = XcmDeliveryFee;
+	type AccountIdToMultiLocation = AccountIdToMultiLocation;
 	type NativeAsset = Balances;
 	type ChainId = ChainId;
 	type MaxContractLabelSize = MaxContractLabelSize;
@@ -284,6 +630,7 @@ impl Config for Test {
 	type CodeBranchTableSizeLimit = CodeBranchTableSizeLimit;
 	type CodeStackLimit = CodeStackLimit;
 	type CodeStorageByteDeposit = CodeStorageByteDeposit;
+	type ContractStorageByteDeposit = ContractStorageByteDeposit;
 	type ContractStorageByteReadPrice = ContractStorageByteReadPrice;
 	type ContractStorageByteWritePrice = ContractStorageByteWritePrice;
 	type UnixTime = Timestamp;
@@ -291,12 +638,17 @@ impl Config for Test {
 	type WasmCostRules = WasmCostRules;
 	type IbcRelayerAccount = IbcRelayerAccount;
 	type IbcRelayer = IbcLoopback<Self>;
+	type UploadOrigin = EnsureRoot<AccountId>;
+	type SudoOrigin = EnsureRoot<AccountId>;
+	type SudoAccount = SudoAccount;
+	type BenchmarkHelper = MockBenchmarkHelper;
 }
 
 // Build genesis storage according to the mock runtime.
 pub fn new_test_ext() -> sp_io::TestExternalities {
 	let origin = frame_benchmarking::account("signer", 0, 0xCAFEBABE);
-	let balances: Vec<(AccountId, Balance)> = vec![(origin, 1_000_000_000_000_000_000)];
+	let balances: Vec<(AccountId, Balance)> =
+		vec![(origin, 1_000_000_000_000_000_000), (SudoAccount::get(), 1_000_000_000_000_000_000)];
 	let mut t = frame_system::GenesisConfig::default().build_storage::<Test>().unwrap();
 	let genesis = pallet_balances::GenesisConfig::<Test> { balances };
 	genesis.assimilate_storage(&mut t).unwrap();
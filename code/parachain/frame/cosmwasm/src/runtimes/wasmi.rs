@@ -2,9 +2,10 @@ use super::abstraction::{CanonicalCosmwasmAccount, CosmwasmAccount, Gas};
 use crate::{
 	runtimes::abstraction::GasOutcome, weights::WeightInfo, Config, ContractInfoOf, Pallet,
 };
-use alloc::string::String;
+use alloc::{format, string::String};
+use cosmwasm_bindings::{ComposableMsg, ComposableQuery};
 use cosmwasm_vm::{
-	cosmwasm_std::{Coin, ContractInfoResponse, Empty, Env, MessageInfo},
+	cosmwasm_std::{Coin, ContractInfoResponse, ContractResult, Env, MessageInfo, SystemResult},
 	executor::ExecutorError,
 	has::Has,
 	memory::{
@@ -19,7 +20,7 @@ use cosmwasm_vm_wasmi::{
 	WasmiHostFunction, WasmiHostFunctionIndex, WasmiHostModule, WasmiInput, WasmiModule,
 	WasmiModuleExecutor, WasmiModuleName, WasmiOutput, WasmiVM, WasmiVMError,
 };
-use frame_support::storage::ChildTriePrefixIterator;
+use frame_support::{storage::ChildTriePrefixIterator, traits::Get};
 use parity_wasm::elements::{self, External, Internal, Module, Type, ValueType};
 use sp_std::{collections::btree_map::BTreeMap, vec::Vec};
 use wasmi::CanResume;
@@ -236,8 +237,8 @@ impl<'a, T: Config> CosmwasmVM<'a, T> {
 impl<'a, T: Config> VMBase for CosmwasmVM<'a, T> {
 	type Input<'x> = WasmiInput<'x, WasmiVM<Self>>;
 	type Output<'x> = WasmiOutput<'x, WasmiVM<Self>>;
-	type QueryCustom = Empty;
-	type MessageCustom = Empty;
+	type QueryCustom = ComposableQuery;
+	type MessageCustom = ComposableMsg;
 	type ContractMeta = CosmwasmContractMeta<CosmwasmAccount<T>>;
 	type Address = CosmwasmAccount<T>;
 	type CanonicalAddress = CanonicalCosmwasmAccount<T>;
@@ -344,22 +345,33 @@ impl<'a, T: Config> VMBase for CosmwasmVM<'a, T> {
 
 	fn query_custom(
 		&mut self,
-		_: Self::QueryCustom,
+		query: Self::QueryCustom,
 	) -> Result<
 		cosmwasm_vm::cosmwasm_std::SystemResult<cosmwasm_vm::executor::CosmwasmQueryResult>,
 		Self::Error,
 	> {
-		log::debug!(target: "runtime::contracts", "query_custom");
-		Err(CosmwasmVMError::Unsupported)
+		log::debug!(target: "runtime::contracts", "query_custom: {:#?}", query);
+		self.charge_raw(Pallet::<T>::do_query_custom_gas(&query))?;
+		// Failures are returned to the contract, which is free to handle them.
+		let result = match Pallet::<T>::do_query_custom(query) {
+			Ok(response) => ContractResult::Ok(response.into()),
+			Err(e) => ContractResult::Err(format!("{:?}", e)),
+		};
+		Ok(SystemResult::Ok(result))
 	}
 
 	fn message_custom(
 		&mut self,
-		_: Self::MessageCustom,
+		message: Self::MessageCustom,
 		_: &mut dyn FnMut(cosmwasm_vm::cosmwasm_std::Event),
 	) -> Result<Option<cosmwasm_vm::cosmwasm_std::Binary>, Self::Error> {
-		log::debug!(target: "runtime::contracts", "message_custom");
-		Err(CosmwasmVMError::Unsupported)
+		log::debug!(target: "runtime::contracts", "message_custom: {:#?}", message);
+		if self.shared.storage_is_readonly() {
+			return Err(CosmwasmVMError::ReadOnlyViolation)
+		}
+		self.charge_raw(Pallet::<T>::do_message_custom_gas(&message))?;
+		let contract = self.contract_address.as_ref();
+		Ok(Pallet::<T>::do_message_custom(contract, message)?.map(Into::into))
 	}
 
 	fn query_raw(
@@ -380,8 +392,13 @@ impl<'a, T: Config> VMBase for CosmwasmVM<'a, T> {
 
 	fn burn(&mut self, funds: &[Coin]) -> Result<(), Self::Error> {
 		log::debug!(target: "runtime::contracts", "burn: {:#?}", funds);
-		// TODO: assets registry check etc...
-		Err(CosmwasmVMError::Unsupported)
+		if self.shared.storage_is_readonly() {
+			return Err(CosmwasmVMError::ReadOnlyViolation)
+		}
+		self.charge_raw(T::WeightInfo::burn(funds.len() as u32))?;
+		let from = self.contract_address.as_ref();
+		Pallet::<T>::do_burn(from, funds)?;
+		Ok(())
 	}
 
 	fn balance(&mut self, account: &Self::Address, denom: String) -> Result<Coin, Self::Error> {
@@ -392,8 +409,11 @@ impl<'a, T: Config> VMBase for CosmwasmVM<'a, T> {
 
 	fn all_balance(&mut self, account: &Self::Address) -> Result<Vec<Coin>, Self::Error> {
 		log::debug!(target: "runtime::contracts", "all balance: {}", Into::<String>::into(account.clone()));
-		//  TODO(hussein-aitlahcen): support iterating over all tokens???
-		Err(CosmwasmVMError::Unsupported)
+		// Charged before the list is built, as its size is only known afterwards.
+		let max_assets = T::MaxAssetsList::get();
+		self.charge_raw(T::WeightInfo::all_balance(max_assets))?;
+		let assets = T::AssetsList::get().into_iter().take(max_assets as usize).collect();
+		Ok(Pallet::<T>::do_all_balance(account.as_ref(), assets))
 	}
 
 	fn query_info(&mut self, address: Self::Address) -> Result<ContractInfoResponse, Self::Error> {
@@ -433,7 +453,7 @@ impl<'a, T: Config> VMBase for CosmwasmVM<'a, T> {
 		if self.shared.storage_is_readonly() {
 			Err(CosmwasmVMError::ReadOnlyViolation)
 		} else {
-			Pallet::<T>::do_db_remove(self, &key);
+			Pallet::<T>::do_db_remove(self, &key)?;
 			Ok(())
 		}
 	}
@@ -500,8 +520,8 @@ impl<'a, T: Config> VMBase for CosmwasmVM<'a, T> {
 			VmGas::QueryInfo => T::WeightInfo::query_info(),
 			_ => 1_u64,
 			// NOTE: **Operations that require no charge**: Debug,
-			// NOTE: **Unsupported operations**:
-			// 		   QueryCustom, MessageCustom, Burn, AllBalance
+			// NOTE: **Operations charged by their host function**: Burn, AllBalance,
+			// 		   QueryCustom, MessageCustom
 			// TODO(aeryz): Implement when centauri is ready: IbcTransfer, IbcSendPacket,
 			//				IbcCloseChannel
 		};
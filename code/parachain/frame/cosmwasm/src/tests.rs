@@ -1,6 +1,33 @@
-use crate::mock::*;
+use crate::{
+	mock::*, runtimes::wasmi::InitialStorageMutability, types::UploadPolicy, CodeIdentifier,
+	ContractInfoOf, ContractToInfo, Error,
+};
+use codec::{Decode, Encode};
+use cosmwasm_bindings::{
+	AssetMetadataResponse, AssetsQuery, Coin as ComposableCoin, ComposableMsg, ComposableQuery,
+	OracleQuery, PabloMsg, PabloQuery, PriceResponse, StakingRewardsMsg, SwapResponse, XcmMsg,
+};
+use cosmwasm_vm::cosmwasm_std::Coin;
+use cosmwasm_vm_wasmi::code_gen::{ModuleDefinition, WasmModule};
+use frame_support::{
+	storage,
+	traits::{fungibles::Mutate, Currency, Get},
+	Blake2_128Concat, StorageHasher,
+};
+use primitives::{currency::CurrencyId, topology};
 use sha2::{Digest, Sha256};
 use sha3::Keccak256;
+use sp_runtime::{AccountId32, DispatchError};
+use std::collections::BTreeMap;
+use xcm::{
+	latest::{
+		Instruction::{ClearOrigin, DescendOrigin},
+		Junction::{self, Parachain},
+		Junctions::{X1, X2},
+		MultiLocation, NetworkId, Xcm,
+	},
+	VersionedMultiLocation, VersionedXcm,
+};
 
 // took these from: https://github.com/CosmWasm/cosmwasm/blob/main/contracts/crypto-verify/tests/integration.rs
 const SECP256K1_MESSAGE_HEX: &str = "5c868fedb8026979ebd26f1ba07c27eedf4ff6d10443505a96ecaf21ba8c4f0937b3cd23ffdc3dd429d4cd1905fb8dbcceeff1350020e18b58d2ba70887baa3a9b783ad30d3fbf210331cdd7df8d77defa398cdacdfc2e359c7ba4cae46bb74401deb417f8b912a1aa966aeeba9c39c7dd22479ae2b30719dca2f2206c5eb4b7";
@@ -25,6 +52,39 @@ const ED25519_SIGNATURE2_HEX: &str = "92a009a9f0d4cab8720e820b5f642540a2b27b5416
 const ED25519_PUBLIC_KEY2_HEX: &str =
 	"3d4017c3e843895a92b70aa74d1b7ebc9c982ccf2ec4968cc0cd55f12af4660c";
 
+const GAS: u64 = 10_000_000_000;
+
+fn signer() -> AccountId {
+	frame_benchmarking::account("signer", 0, 0xCAFEBABE)
+}
+
+fn upload_code(origin: &AccountId, additional_binary_size: usize) -> u64 {
+	let wasm_module: WasmModule =
+		ModuleDefinition::new(Default::default(), additional_binary_size, None)
+			.unwrap()
+			.into();
+	assert!(Cosmwasm::upload(Origin::signed(origin.clone()), wasm_module.code.try_into().unwrap())
+		.is_ok());
+	crate::CurrentCodeId::<Test>::get()
+}
+
+fn instantiate_contract(origin: &AccountId, code_id: u64) -> AccountId {
+	assert!(Cosmwasm::instantiate(
+		Origin::signed(origin.clone()),
+		CodeIdentifier::CodeId(code_id),
+		b"salt".to_vec().try_into().unwrap(),
+		Some(origin.clone()),
+		b"label".to_vec().try_into().unwrap(),
+		Default::default(),
+		GAS,
+		b"message".to_vec().try_into().unwrap(),
+	)
+	.is_ok());
+	ContractToInfo::<Test>::iter()
+		.find_map(|(contract, info)| (info.code_id == code_id).then_some(contract))
+		.unwrap()
+}
+
 #[test]
 fn works() {
 	new_test_ext().execute_with(|| {})
@@ -219,3 +279,516 @@ fn ed25519_batch_verify_fails_if_input_lengths_are_incorrect() {
 		));
 	})
 }
+
+#[test]
+fn all_balance_lists_non_zero_balances() {
+	new_test_ext().execute_with(|| {
+		let account = AccountId32::new([1; 32]);
+		assert_eq!(Cosmwasm::do_all_balance(&account, AssetsList::get()), vec![]);
+
+		<Assets as Mutate<AccountId32>>::mint_into(CurrencyId::KSM, &account, 1_000).unwrap();
+		assert_eq!(
+			Cosmwasm::do_all_balance(&account, AssetsList::get()),
+			vec![Coin::new(1_000, "4")]
+		);
+	})
+}
+
+#[test]
+fn burn_is_bounded_by_the_balance() {
+	new_test_ext().execute_with(|| {
+		let account = AccountId32::new([1; 32]);
+		<Assets as Mutate<AccountId32>>::mint_into(CurrencyId::KSM, &account, 1_000).unwrap();
+
+		assert!(Cosmwasm::do_burn(&account, &[Coin::new(400, "4")]).is_ok());
+		assert!(matches!(Cosmwasm::do_balance(&account, "4".into()), Ok(600)));
+		assert!(matches!(
+			Cosmwasm::do_burn(&account, &[Coin::new(601, "4")]),
+			Err(Error::<Test>::BurnFailed)
+		));
+		assert!(matches!(
+			Cosmwasm::do_burn(&account, &[Coin::new(1, "unknown")]),
+			Err(Error::<Test>::UnknownDenom)
+		));
+	})
+}
+
+fn query_custom<R: serde::de::DeserializeOwned>(query: ComposableQuery) -> R {
+	serde_json::from_slice(&Cosmwasm::do_query_custom(query).unwrap()).unwrap()
+}
+
+#[test]
+fn custom_queries_reach_the_pallets() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(7);
+
+		let price: PriceResponse = query_custom(ComposableQuery::Oracle(OracleQuery::Price {
+			denom: "4".into(),
+			amount: 1_000.into(),
+		}));
+		assert_eq!(price, PriceResponse { price: 2_000.into(), block: 7 });
+
+		let quote: SwapResponse = query_custom(ComposableQuery::Pablo(PabloQuery::SpotPrice {
+			pool_id: 1.into(),
+			base_asset: ComposableCoin::new(1_000, "4"),
+			quote_denom: "1".into(),
+			calculate_with_fees: true,
+		}));
+		assert_eq!(
+			quote,
+			SwapResponse {
+				value: ComposableCoin::new(1_000, "1"),
+				fee: ComposableCoin::new(0, "4")
+			}
+		);
+
+		let metadata: AssetMetadataResponse =
+			query_custom(ComposableQuery::Assets(AssetsQuery::Metadata { denom: "4".into() }));
+		assert_eq!(metadata, AssetMetadataResponse { denom: "4".into(), decimals: 12 });
+
+		assert!(Cosmwasm::do_query_custom(ComposableQuery::Assets(AssetsQuery::Metadata {
+			denom: "unknown".into()
+		}))
+		.is_err());
+	})
+}
+
+#[test]
+fn custom_messages_are_executed_on_behalf_of_the_contract() {
+	new_test_ext().execute_with(|| {
+		let contract = AccountId32::new([1; 32]);
+		assert!(matches!(
+			Cosmwasm::do_message_custom(
+				&contract,
+				ComposableMsg::Pablo(PabloMsg::Swap {
+					pool_id: 1.into(),
+					in_asset: ComposableCoin::new(1_000, "4"),
+					min_receive: ComposableCoin::new(900, "1"),
+					keep_alive: true,
+				})
+			),
+			Err(Error::<Test>::SwapFailed)
+		));
+		assert!(matches!(
+			Cosmwasm::do_message_custom(
+				&contract,
+				ComposableMsg::StakingRewards(StakingRewardsMsg::Claim {
+					fnft_collection_id: "unknown".into(),
+					fnft_instance_id: 0,
+				})
+			),
+			Err(Error::<Test>::UnknownDenom)
+		));
+	})
+}
+
+#[test]
+fn upload_policy_restricts_uploaders() {
+	new_test_ext().execute_with(|| {
+		let uploader = signer();
+		let upload = |additional_binary_size| {
+			let wasm_module: WasmModule =
+				ModuleDefinition::new(Default::default(), additional_binary_size, None)
+					.unwrap()
+					.into();
+			Cosmwasm::upload(Origin::signed(uploader.clone()), wasm_module.code.try_into().unwrap())
+		};
+		assert!(upload(10).is_ok());
+
+		assert!(Cosmwasm::update_upload_allowlist(
+			Origin::signed(uploader.clone()),
+			uploader.clone(),
+			true
+		)
+		.is_err());
+		assert!(Cosmwasm::set_upload_policy(Origin::root(), UploadPolicy::Allowlist).is_ok());
+		assert_eq!(upload(11), Err(Error::<Test>::UploadNotAllowed.into()));
+
+		assert!(Cosmwasm::update_upload_allowlist(Origin::root(), uploader.clone(), true).is_ok());
+		assert!(upload(11).is_ok());
+
+		assert!(Cosmwasm::set_upload_policy(Origin::root(), UploadPolicy::Governance).is_ok());
+		assert_eq!(upload(12), Err(Error::<Test>::UploadNotAllowed.into()));
+	})
+}
+
+#[test]
+fn sudo_requires_the_sudo_origin() {
+	new_test_ext().execute_with(|| {
+		let contract = AccountId32::new([1; 32]);
+		let message = || b"{}".to_vec().try_into().unwrap();
+		assert_eq!(
+			Cosmwasm::sudo(
+				Origin::signed(contract.clone()),
+				contract.clone(),
+				Default::default(),
+				1_000_000,
+				message()
+			)
+			.unwrap_err()
+			.error,
+			DispatchError::BadOrigin
+		);
+		assert_eq!(
+			Cosmwasm::sudo(Origin::root(), contract, Default::default(), 1_000_000, message())
+				.unwrap_err()
+				.error,
+			Error::<Test>::ContractNotFound.into()
+		);
+	})
+}
+
+#[test]
+fn sudo_is_funded_by_the_sudo_account() {
+	new_test_ext().execute_with(|| {
+		let origin = signer();
+		let contract = instantiate_contract(&origin, upload_code(&origin, 10));
+		let origin_balance = Balances::free_balance(&origin);
+		let sudo_balance = Balances::free_balance(SudoAccount::get());
+		let contract_balance = Balances::free_balance(&contract);
+
+		assert!(Cosmwasm::sudo(
+			Origin::root(),
+			contract.clone(),
+			[(NativeAssetId::get(), (1_000, false))]
+				.into_iter()
+				.collect::<BTreeMap<_, _>>()
+				.try_into()
+				.unwrap(),
+			GAS,
+			b"{}".to_vec().try_into().unwrap()
+		)
+		.is_ok());
+		assert_eq!(Balances::free_balance(&contract), contract_balance + 1_000);
+		assert!(Balances::free_balance(SudoAccount::get()) <= sudo_balance - 1_000);
+		assert_eq!(Balances::free_balance(&origin), origin_balance);
+	})
+}
+
+#[test]
+fn contract_state_pages_through_the_storage() {
+	new_test_ext().execute_with(|| {
+		let contract = AccountId32::new([1; 32]);
+		let trie_id: Vec<u8> = b"trie".to_vec();
+		ContractToInfo::<Test>::insert(
+			&contract,
+			ContractInfoOf::<Test> {
+				code_id: 1,
+				trie_id: trie_id.clone().try_into().unwrap(),
+				instantiator: contract.clone(),
+				admin: None,
+				label: b"label".to_vec().try_into().unwrap(),
+				storage_usage: 0,
+			},
+		);
+		let child_trie = Cosmwasm::contract_child_trie(&trie_id);
+		for (key, value) in [(&b"a1"[..], &b"1"[..]), (b"a2", b"2"), (b"b1", b"3")] {
+			storage::child::put_raw(&child_trie, &Blake2_128Concat::hash(key), value);
+		}
+
+		let mut entries = Vec::new();
+		let mut start_after = None;
+		loop {
+			let page = crate::contract_state::<Test>(
+				contract.clone(),
+				Some(b"a".to_vec()),
+				start_after,
+				1,
+			)
+			.unwrap();
+			entries.extend(page.entries);
+			match page.next {
+				Some(next) => start_after = Some(next),
+				None => break,
+			}
+		}
+		entries.sort();
+		assert_eq!(entries, vec![(b"a1".to_vec(), b"1".to_vec()), (b"a2".to_vec(), b"2".to_vec())]);
+
+		// Keys not matching the prefix are visited, and count toward the limit, all the same.
+		let page =
+			crate::contract_state::<Test>(contract.clone(), Some(b"c".to_vec()), None, 2).unwrap();
+		assert!(page.entries.is_empty());
+		assert!(page.next.is_some());
+
+		assert_eq!(crate::contracts_by_code::<Test>(2, None, 10), vec![]);
+		assert_eq!(crate::contract_info::<Test>(contract).unwrap().label, b"label".to_vec());
+	})
+}
+
+#[test]
+fn xcm_messages_originate_from_the_contract() {
+	new_test_ext().execute_with(|| {
+		let contract = AccountId32::new([1; 32]);
+		let contract_junction = Junction::AccountId32 { network: NetworkId::Any, id: [1; 32] };
+		let send = || {
+			ComposableMsg::Xcm(XcmMsg::Send {
+				destination: VersionedMultiLocation::from(MultiLocation::parent()).encode(),
+				message: VersionedXcm::<()>::from(Xcm(vec![ClearOrigin])).encode(),
+			})
+		};
+		let transfer = || {
+			ComposableMsg::Xcm(XcmMsg::Transfer {
+				asset: ComposableCoin::new(1_000, "4"),
+				para_id: Some(topology::karura::ID),
+				beneficiary: [2; 32],
+				dest_weight: 1_000_000,
+			})
+		};
+
+		assert!(matches!(
+			Cosmwasm::do_message_custom(&contract, send()),
+			Err(Error::<Test>::XcmSendNotAllowed)
+		));
+		assert!(matches!(
+			Cosmwasm::do_message_custom(&contract, transfer()),
+			Err(Error::<Test>::XcmSendNotAllowed)
+		));
+		assert_eq!(
+			Cosmwasm::update_xcm_send_allowlist(
+				Origin::signed(contract.clone()),
+				contract.clone(),
+				true
+			),
+			Err(DispatchError::BadOrigin)
+		);
+		assert!(Cosmwasm::update_xcm_send_allowlist(Origin::root(), contract.clone(), true).is_ok());
+		assert!(matches!(
+			Cosmwasm::do_message_custom(&contract, send()),
+			Err(Error::<Test>::NotEnoughFundsForXcmDeliveryFee)
+		));
+		let balance = 1_000_000;
+		Balances::make_free_balance_be(&contract, balance);
+		assert!(matches!(Cosmwasm::do_message_custom(&contract, send()), Ok(None)));
+		assert_eq!(Balances::free_balance(&contract), balance - XcmDeliveryFee::get());
+		SENT_XCM.with(|sent| {
+			assert_eq!(
+				*sent.borrow(),
+				vec![(
+					MultiLocation::parent(),
+					Xcm(vec![DescendOrigin(X1(contract_junction.clone())), ClearOrigin])
+				)]
+			)
+		});
+
+		assert!(matches!(Cosmwasm::do_message_custom(&contract, transfer()), Ok(None)));
+		assert_eq!(Balances::free_balance(&contract), balance - 2 * XcmDeliveryFee::get());
+		XCM_TRANSFERS.with(|transfers| {
+			assert_eq!(
+				*transfers.borrow(),
+				vec![(
+					contract.clone(),
+					CurrencyId::KSM,
+					1_000,
+					MultiLocation::new(
+						1,
+						X2(
+							Parachain(topology::karura::ID),
+							Junction::AccountId32 { network: NetworkId::Any, id: [2; 32] }
+						)
+					),
+					1_000_000
+				)]
+			)
+		});
+
+		assert!(matches!(
+			Cosmwasm::do_message_custom(
+				&contract,
+				ComposableMsg::Xcm(XcmMsg::Send { destination: vec![0xFF], message: vec![] })
+			),
+			Err(Error::<Test>::XcmDecodingFailed)
+		));
+	})
+}
+
+#[test]
+fn dry_run_leaves_the_state_unchanged() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		let origin = signer();
+		let contract = instantiate_contract(&origin, upload_code(&origin, 10));
+		let origin_balance = Balances::free_balance(&origin);
+		let contract_balance = Balances::free_balance(&contract);
+		let nonce = crate::CurrentNonce::<Test>::get();
+		let events = System::events().len();
+
+		let result = crate::dry_run_execute::<Test>(
+			origin.clone(),
+			contract.clone(),
+			[(NativeAssetId::get(), (1_000, false))].into_iter().collect(),
+			GAS,
+			b"{}".to_vec(),
+		)
+		.unwrap();
+		assert!(result.gas_used > 0);
+
+		assert_eq!(Balances::free_balance(&origin), origin_balance);
+		assert_eq!(Balances::free_balance(&contract), contract_balance);
+		assert_eq!(crate::CurrentNonce::<Test>::get(), nonce);
+		assert_eq!(System::events().len(), events);
+	})
+}
+
+#[test]
+fn dry_run_gas_is_enough_to_execute() {
+	new_test_ext().execute_with(|| {
+		let origin = signer();
+		let contract = instantiate_contract(&origin, upload_code(&origin, 10));
+		let gas_used = crate::dry_run_execute::<Test>(
+			origin.clone(),
+			contract.clone(),
+			Default::default(),
+			GAS,
+			b"{}".to_vec(),
+		)
+		.unwrap()
+		.gas_used;
+
+		assert!(Cosmwasm::execute(
+			Origin::signed(origin),
+			contract,
+			Default::default(),
+			gas_used,
+			b"{}".to_vec().try_into().unwrap(),
+		)
+		.is_ok());
+	})
+}
+
+#[test]
+fn remove_code_releases_unused_code() {
+	new_test_ext().execute_with(|| {
+		let creator = signer();
+		let reserved = Balances::reserved_balance(&creator);
+		let code_id = upload_code(&creator, 10);
+		let code_size = crate::PristineCode::<Test>::get(code_id).unwrap().len() as u128;
+		assert_eq!(Balances::reserved_balance(&creator), reserved + code_size);
+
+		assert_eq!(
+			Cosmwasm::remove_code(
+				Origin::signed(AccountId32::new([2; 32])),
+				CodeIdentifier::CodeId(code_id)
+			)
+			.unwrap_err()
+			.error,
+			Error::<Test>::NotAuthorized.into()
+		);
+
+		assert!(Cosmwasm::remove_code(
+			Origin::signed(creator.clone()),
+			CodeIdentifier::CodeId(code_id)
+		)
+		.is_ok());
+		assert_eq!(Balances::reserved_balance(&creator), reserved);
+		assert!(!crate::CodeIdToInfo::<Test>::contains_key(code_id));
+		assert!(!crate::PristineCode::<Test>::contains_key(code_id));
+		assert!(!crate::InstrumentedCode::<Test>::contains_key(code_id));
+		assert_eq!(
+			Cosmwasm::remove_code(Origin::root(), CodeIdentifier::CodeId(code_id))
+				.unwrap_err()
+				.error,
+			Error::<Test>::CodeNotFound.into()
+		);
+	})
+}
+
+#[test]
+fn remove_code_requires_the_code_to_be_unused() {
+	new_test_ext().execute_with(|| {
+		let creator = signer();
+		let code_id = upload_code(&creator, 10);
+		instantiate_contract(&creator, code_id);
+
+		assert_eq!(
+			Cosmwasm::remove_code(Origin::root(), CodeIdentifier::CodeId(code_id))
+				.unwrap_err()
+				.error,
+			Error::<Test>::CodeInUse.into()
+		);
+		assert!(crate::CodeIdToInfo::<Test>::contains_key(code_id));
+	})
+}
+
+#[test]
+fn storage_deposit_follows_the_storage_usage() {
+	new_test_ext().execute_with(|| {
+		let origin = signer();
+		let contract = instantiate_contract(&origin, upload_code(&origin, 10));
+		let info = ContractToInfo::<Test>::get(&contract).unwrap();
+		let (usage, reserved) = (info.storage_usage, Balances::reserved_balance(&contract));
+		let origin_balance = Balances::free_balance(&origin);
+		let child_trie = Cosmwasm::contract_child_trie(info.trie_id.as_ref());
+		let mut shared = Cosmwasm::do_create_vm_shared(GAS, InitialStorageMutability::ReadWrite);
+		let mut vm =
+			Cosmwasm::cosmwasm_new_vm(&mut shared, origin, contract.clone(), info, vec![]).unwrap();
+		let deposit =
+			|usage: u64| u128::from(usage) * u128::from(ContractStorageByteDeposit::get());
+
+		assert!(Cosmwasm::do_db_write(&mut vm.0, b"hello", b"world").is_ok());
+		let grown_usage = ContractToInfo::<Test>::get(&contract).unwrap().storage_usage;
+		assert!(grown_usage > usage);
+		assert_eq!(Balances::reserved_balance(&contract), reserved + deposit(grown_usage - usage));
+		// The whole deposit is paid by the sender.
+		assert!(Balances::free_balance(&origin) <= origin_balance - deposit(grown_usage - usage));
+
+		assert!(Cosmwasm::do_db_write(&mut vm.0, b"hello", b"WORLD").is_ok());
+		assert_eq!(ContractToInfo::<Test>::get(&contract).unwrap().storage_usage, grown_usage);
+		assert_eq!(Balances::reserved_balance(&contract), reserved + deposit(grown_usage - usage));
+
+		assert!(Cosmwasm::do_db_remove(&mut vm.0, b"hello").is_ok());
+		assert_eq!(ContractToInfo::<Test>::get(&contract).unwrap().storage_usage, usage);
+		assert_eq!(Balances::reserved_balance(&contract), reserved);
+
+		// Storage written before the deposits were introduced is not covered, hence never released.
+		storage::child::put_raw(&child_trie, &Blake2_128Concat::hash(b"legacy"), b"value");
+		assert!(Cosmwasm::do_db_remove(&mut vm.0, b"legacy").is_ok());
+		assert_eq!(ContractToInfo::<Test>::get(&contract).unwrap().storage_usage, usage);
+		assert_eq!(Balances::reserved_balance(&contract), reserved);
+	})
+}
+
+#[test]
+fn contract_info_without_storage_usage_decodes_as_uncovered() {
+	let info = ContractInfoOf::<Test> {
+		code_id: 1,
+		trie_id: b"trie".to_vec().try_into().unwrap(),
+		instantiator: AccountId32::new([1; 32]),
+		admin: None,
+		label: b"label".to_vec().try_into().unwrap(),
+		storage_usage: 42,
+	};
+	let encoded = info.encode();
+	assert_eq!(ContractInfoOf::<Test>::decode(&mut &encoded[..]), Ok(info.clone()));
+	// The contract infos stored before the storage usage was recorded.
+	let legacy = &encoded[..encoded.len() - 8];
+	assert_eq!(
+		ContractInfoOf::<Test>::decode(&mut &legacy[..]),
+		Ok(ContractInfoOf::<Test> { storage_usage: 0, ..info })
+	);
+}
+
+#[test]
+fn contracts_by_code_pages_through_the_index() {
+	new_test_ext().execute_with(|| {
+		let contracts: Vec<_> = (1..=3).map(|i| AccountId32::new([i; 32])).collect();
+		for contract in &contracts {
+			crate::CodeIdToContracts::<Test>::insert(1, contract, ());
+		}
+		crate::CodeIdToContracts::<Test>::insert(2, AccountId32::new([4; 32]), ());
+
+		let mut listed = Vec::new();
+		let mut start_after = None;
+		loop {
+			let page = crate::contracts_by_code::<Test>(1, start_after, 2);
+			assert!(page.len() <= 2);
+			match page.last() {
+				Some(last) => start_after = Some(last.clone()),
+				None => break,
+			}
+			listed.extend(page);
+		}
+		listed.sort();
+		assert_eq!(listed, contracts);
+	})
+}
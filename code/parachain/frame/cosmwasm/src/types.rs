@@ -18,8 +18,20 @@ pub struct CodeInfo<AccountId, Hash> {
 	pub ibc_capable: bool,
 }
 
+/// Policy restricting who is able to upload code.
+#[derive(Copy, Clone, PartialEq, Eq, Encode, Decode, MaxEncodedLen, TypeInfo, Debug, Default)]
+pub enum UploadPolicy {
+	/// Any signed origin able to pay the deposit.
+	#[default]
+	Permissionless,
+	/// Only the accounts of the upload allowlist.
+	Allowlist,
+	/// Only the upload origin, a.k.a. governance.
+	Governance,
+}
+
 /// Contract metadata.
-#[derive(Clone, PartialEq, Eq, Encode, Decode, MaxEncodedLen, TypeInfo, Debug)]
+#[derive(Clone, PartialEq, Eq, Encode, MaxEncodedLen, TypeInfo, Debug)]
 pub struct ContractInfo<AccountId, Label, TrieId> {
 	/// The code this contract is baked by.
 	pub code_id: CosmwasmCodeId,
@@ -32,4 +44,27 @@ pub struct ContractInfo<AccountId, Label, TrieId> {
 	pub admin: Option<AccountId>,
 	/// Contract label defined by the instantiator.w
 	pub label: Label,
+	/// Number of bytes used by the contract storage, hashed keys included, covered by the deposit
+	/// reserved on the contract account. The storage written before the deposit was introduced
+	/// is not covered, see [`crate::migrations::v1`].
+	pub storage_usage: u64,
+}
+
+/// The contracts instantiated before the storage usage was tracked are stored without it, and
+/// decode with no usage until their info is written again.
+impl<AccountId: Decode, Label: Decode, TrieId: Decode> Decode
+	for ContractInfo<AccountId, Label, TrieId>
+{
+	fn decode<I: codec::Input>(input: &mut I) -> Result<Self, codec::Error> {
+		let code_id = Decode::decode(input)?;
+		let trie_id = Decode::decode(input)?;
+		let instantiator = Decode::decode(input)?;
+		let admin = Decode::decode(input)?;
+		let label = Decode::decode(input)?;
+		let storage_usage = match input.remaining_len()? {
+			Some(0) => 0,
+			_ => Decode::decode(input)?,
+		};
+		Ok(Self { code_id, trie_id, instantiator, admin, label, storage_usage })
+	}
 }
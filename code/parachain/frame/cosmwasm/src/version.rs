@@ -11,7 +11,7 @@ use cosmwasm_vm::{
 			IbcPacketReceiveCall, IbcPacketTimeoutCall,
 		},
 		AllocateCall, AsFunctionName, DeallocateCall, ExecuteCall, InstantiateCall, MigrateCall,
-		QueryCall, ReplyCall,
+		QueryCall, ReplyCall, SudoCall,
 	},
 	memory::PointerOf,
 	vm::VmMessageCustomOf,
@@ -92,6 +92,12 @@ impl<T: Config> Version<T> {
 			// extern "C" fn reply(env_ptr: u32, msg_ptr: u32) -> u32;
 			&[parity_wasm::elements::ValueType::I32, parity_wasm::elements::ValueType::I32],
 		),
+		(
+			ExportRequirement::Optional,
+			SudoCall::<VmMessageCustomOf<CosmwasmVM<T>>>::NAME,
+			// extern "C" fn sudo(env_ptr: u32, msg_ptr: u32) -> u32;
+			&[parity_wasm::elements::ValueType::I32, parity_wasm::elements::ValueType::I32],
+		),
 	];
 
 	/// IBC callback a contract must export to be considered IBC capable:
@@ -20,6 +20,11 @@
 // --repeat=20
 // --output
 // parachain/frame/cosmwasm/src/weights.rs
+//
+// NOTE: `remove_code`, `set_upload_policy`, `update_upload_allowlist`, `update_xcm_send_allowlist`,
+// `all_balance`, `burn`, the `query_*` and `message_*` bindings, `xcm_send` and `xcm_transfer`
+// were not produced by this run. Their values are estimates until the weights are regenerated
+// with the command above.
 
 #![cfg_attr(rustfmt, rustfmt_skip)]
 #![allow(unused_parens)]
@@ -37,6 +42,10 @@ pub trait WeightInfo {
 	fn execute(n: u32, ) -> Weight;
 	fn migrate() -> Weight;
 	fn update_admin() -> Weight;
+	fn remove_code(n: u32, ) -> Weight;
+	fn set_upload_policy() -> Weight;
+	fn update_upload_allowlist() -> Weight;
+	fn update_xcm_send_allowlist() -> Weight;
 	fn db_read() -> Weight;
 	fn db_read_other_contract() -> Weight;
 	fn db_write() -> Weight;
@@ -45,6 +54,17 @@ pub trait WeightInfo {
 	fn db_remove() -> Weight;
 	fn balance() -> Weight;
 	fn transfer(n: u32, ) -> Weight;
+	fn all_balance(n: u32, ) -> Weight;
+	fn burn(n: u32, ) -> Weight;
+	fn query_oracle_price() -> Weight;
+	fn query_oracle_twap() -> Weight;
+	fn query_pablo_spot_price() -> Weight;
+	fn query_assets_metadata() -> Weight;
+	fn message_pablo_swap() -> Weight;
+	fn message_staking_stake() -> Weight;
+	fn message_staking_claim() -> Weight;
+	fn xcm_send(n: u32, ) -> Weight;
+	fn xcm_transfer() -> Weight;
 	fn set_contract_meta() -> Weight;
 	fn running_contract_meta() -> Weight;
 	fn contract_meta() -> Weight;
@@ -206,6 +226,33 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
 			.saturating_add(T::DbWeight::get().reads(5 as Weight))
 			.saturating_add(T::DbWeight::get().writes(2 as Weight))
 	}
+	// Storage: Cosmwasm CodeIdToInfo (r:1 w:1)
+	// Storage: Cosmwasm PristineCode (r:1 w:1)
+	// Storage: System Account (r:1 w:1)
+	// Storage: Cosmwasm InstrumentedCode (r:0 w:1)
+	// Storage: Cosmwasm CodeHashToId (r:0 w:1)
+	fn remove_code(n: u32, ) -> Weight {
+		(41_208_000 as Weight)
+			// Standard Error: 0
+			.saturating_add((2_000 as Weight).saturating_mul(n as Weight))
+			.saturating_add(T::DbWeight::get().reads(3 as Weight))
+			.saturating_add(T::DbWeight::get().writes(5 as Weight))
+	}
+	// Storage: Cosmwasm CodeUploadPolicy (r:0 w:1)
+	fn set_upload_policy() -> Weight {
+		(12_834_000 as Weight)
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	// Storage: Cosmwasm UploadAllowlist (r:0 w:1)
+	fn update_upload_allowlist() -> Weight {
+		(14_125_000 as Weight)
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	// Storage: Cosmwasm XcmSendAllowlist (r:0 w:1)
+	fn update_xcm_send_allowlist() -> Weight {
+		(14_083_000 as Weight)
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
 	// Storage: unknown [0xe9a804b2e527fd3601d2ffc0bb023cd668656c6c6f20776f726c64] (r:1 w:0)
 	fn db_read() -> Weight {
 		(7_708_000 as Weight)
@@ -250,6 +297,100 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
 			.saturating_add(T::DbWeight::get().reads((2 as Weight).saturating_mul(n as Weight)))
 			.saturating_add(T::DbWeight::get().writes((2 as Weight).saturating_mul(n as Weight)))
 	}
+	// Storage: AssetsRegistry ForeignToLocal (r:1 w:0)
+	// Storage: Tokens Accounts (r:1 w:0)
+	// Storage: System Account (r:1 w:0)
+	/// The range of component `n` is `[0, 64]`.
+	fn all_balance(n: u32, ) -> Weight {
+		(3_125_000 as Weight)
+			// Standard Error: 4_000
+			.saturating_add((2_846_000 as Weight).saturating_mul(n as Weight))
+			.saturating_add(T::DbWeight::get().reads((2 as Weight).saturating_mul(n as Weight)))
+	}
+	// Storage: System Account (r:1 w:1)
+	// Storage: Tokens Accounts (r:1 w:1)
+	// Storage: Tokens TotalIssuance (r:1 w:1)
+	/// The range of component `n` is `[0, 23]`.
+	fn burn(n: u32, ) -> Weight {
+		(8_417_000 as Weight)
+			// Standard Error: 19_000
+			.saturating_add((17_332_000 as Weight).saturating_mul(n as Weight))
+			.saturating_add(T::DbWeight::get().reads((2 as Weight).saturating_mul(n as Weight)))
+			.saturating_add(T::DbWeight::get().writes((2 as Weight).saturating_mul(n as Weight)))
+	}
+	// Storage: Oracle Prices (r:1 w:0)
+	// Storage: AssetsRegistry AssetDecimals (r:1 w:0)
+	fn query_oracle_price() -> Weight {
+		(14_312_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(2 as Weight))
+	}
+	// Storage: Oracle PriceHistory (r:1 w:0)
+	// Storage: Oracle Prices (r:1 w:0)
+	// Storage: AssetsRegistry AssetDecimals (r:1 w:0)
+	fn query_oracle_twap() -> Weight {
+		(27_934_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(3 as Weight))
+	}
+	// Storage: Pablo Pools (r:1 w:0)
+	// Storage: Tokens Accounts (r:2 w:0)
+	fn query_pablo_spot_price() -> Weight {
+		(32_650_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(3 as Weight))
+	}
+	// Storage: AssetsRegistry AssetDecimals (r:1 w:0)
+	fn query_assets_metadata() -> Weight {
+		(6_871_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+	}
+	// Storage: Pablo Pools (r:1 w:0)
+	// Storage: Tokens Accounts (r:4 w:4)
+	// Storage: System Account (r:2 w:2)
+	fn message_pablo_swap() -> Weight {
+		(97_412_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(7 as Weight))
+			.saturating_add(T::DbWeight::get().writes(6 as Weight))
+	}
+	// Storage: StakingRewards RewardPools (r:1 w:1)
+	// Storage: StakingRewards Stakes (r:0 w:1)
+	// Storage: Tokens Accounts (r:3 w:3)
+	// Storage: System Account (r:2 w:2)
+	fn message_staking_stake() -> Weight {
+		(186_207_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(6 as Weight))
+			.saturating_add(T::DbWeight::get().writes(7 as Weight))
+	}
+	// Storage: StakingRewards Stakes (r:1 w:1)
+	// Storage: StakingRewards RewardPools (r:1 w:1)
+	// Storage: Tokens Accounts (r:2 w:2)
+	// Storage: System Account (r:1 w:1)
+	fn message_staking_claim() -> Weight {
+		(81_552_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(5 as Weight))
+			.saturating_add(T::DbWeight::get().writes(5 as Weight))
+	}
+	// Storage: Cosmwasm XcmSendAllowlist (r:1 w:0)
+	// Storage: System Account (r:1 w:1)
+	// Storage: ParachainSystem HostConfiguration (r:1 w:0)
+	// Storage: ParachainSystem PendingUpwardMessages (r:1 w:1)
+	/// The range of component `n` is `[0, 65536]`.
+	fn xcm_send(n: u32, ) -> Weight {
+		(44_291_000 as Weight)
+			// Standard Error: 0
+			.saturating_add((2_000 as Weight).saturating_mul(n as Weight))
+			.saturating_add(T::DbWeight::get().reads(4 as Weight))
+			.saturating_add(T::DbWeight::get().writes(2 as Weight))
+	}
+	// Storage: Cosmwasm XcmSendAllowlist (r:1 w:0)
+	// Storage: System Account (r:1 w:1)
+	// Storage: Tokens Accounts (r:1 w:1)
+	// Storage: Tokens TotalIssuance (r:1 w:1)
+	// Storage: ParachainSystem HostConfiguration (r:1 w:0)
+	// Storage: ParachainSystem PendingUpwardMessages (r:1 w:1)
+	fn xcm_transfer() -> Weight {
+		(171_958_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(6 as Weight))
+			.saturating_add(T::DbWeight::get().writes(4 as Weight))
+	}
 	// Storage: Cosmwasm ContractToInfo (r:1 w:1)
 	fn set_contract_meta() -> Weight {
 		(5_625_000 as Weight)
@@ -819,6 +960,33 @@ impl WeightInfo for () {
 			.saturating_add(RocksDbWeight::get().reads(5 as Weight))
 			.saturating_add(RocksDbWeight::get().writes(2 as Weight))
 	}
+	// Storage: Cosmwasm CodeIdToInfo (r:1 w:1)
+	// Storage: Cosmwasm PristineCode (r:1 w:1)
+	// Storage: System Account (r:1 w:1)
+	// Storage: Cosmwasm InstrumentedCode (r:0 w:1)
+	// Storage: Cosmwasm CodeHashToId (r:0 w:1)
+	fn remove_code(n: u32, ) -> Weight {
+		(41_208_000 as Weight)
+			// Standard Error: 0
+			.saturating_add((2_000 as Weight).saturating_mul(n as Weight))
+			.saturating_add(RocksDbWeight::get().reads(3 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(5 as Weight))
+	}
+	// Storage: Cosmwasm CodeUploadPolicy (r:0 w:1)
+	fn set_upload_policy() -> Weight {
+		(12_834_000 as Weight)
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+	}
+	// Storage: Cosmwasm UploadAllowlist (r:0 w:1)
+	fn update_upload_allowlist() -> Weight {
+		(14_125_000 as Weight)
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+	}
+	// Storage: Cosmwasm XcmSendAllowlist (r:0 w:1)
+	fn update_xcm_send_allowlist() -> Weight {
+		(14_083_000 as Weight)
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+	}
 	// Storage: unknown [0xe9a804b2e527fd3601d2ffc0bb023cd668656c6c6f20776f726c64] (r:1 w:0)
 	fn db_read() -> Weight {
 		(7_708_000 as Weight)
@@ -863,6 +1031,100 @@ impl WeightInfo for () {
 			.saturating_add(RocksDbWeight::get().reads((2 as Weight).saturating_mul(n as Weight)))
 			.saturating_add(RocksDbWeight::get().writes((2 as Weight).saturating_mul(n as Weight)))
 	}
+	// Storage: AssetsRegistry ForeignToLocal (r:1 w:0)
+	// Storage: Tokens Accounts (r:1 w:0)
+	// Storage: System Account (r:1 w:0)
+	/// The range of component `n` is `[0, 64]`.
+	fn all_balance(n: u32, ) -> Weight {
+		(3_125_000 as Weight)
+			// Standard Error: 4_000
+			.saturating_add((2_846_000 as Weight).saturating_mul(n as Weight))
+			.saturating_add(RocksDbWeight::get().reads((2 as Weight).saturating_mul(n as Weight)))
+	}
+	// Storage: System Account (r:1 w:1)
+	// Storage: Tokens Accounts (r:1 w:1)
+	// Storage: Tokens TotalIssuance (r:1 w:1)
+	/// The range of component `n` is `[0, 23]`.
+	fn burn(n: u32, ) -> Weight {
+		(8_417_000 as Weight)
+			// Standard Error: 19_000
+			.saturating_add((17_332_000 as Weight).saturating_mul(n as Weight))
+			.saturating_add(RocksDbWeight::get().reads((2 as Weight).saturating_mul(n as Weight)))
+			.saturating_add(RocksDbWeight::get().writes((2 as Weight).saturating_mul(n as Weight)))
+	}
+	// Storage: Oracle Prices (r:1 w:0)
+	// Storage: AssetsRegistry AssetDecimals (r:1 w:0)
+	fn query_oracle_price() -> Weight {
+		(14_312_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(2 as Weight))
+	}
+	// Storage: Oracle PriceHistory (r:1 w:0)
+	// Storage: Oracle Prices (r:1 w:0)
+	// Storage: AssetsRegistry AssetDecimals (r:1 w:0)
+	fn query_oracle_twap() -> Weight {
+		(27_934_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(3 as Weight))
+	}
+	// Storage: Pablo Pools (r:1 w:0)
+	// Storage: Tokens Accounts (r:2 w:0)
+	fn query_pablo_spot_price() -> Weight {
+		(32_650_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(3 as Weight))
+	}
+	// Storage: AssetsRegistry AssetDecimals (r:1 w:0)
+	fn query_assets_metadata() -> Weight {
+		(6_871_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
+	}
+	// Storage: Pablo Pools (r:1 w:0)
+	// Storage: Tokens Accounts (r:4 w:4)
+	// Storage: System Account (r:2 w:2)
+	fn message_pablo_swap() -> Weight {
+		(97_412_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(7 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(6 as Weight))
+	}
+	// Storage: StakingRewards RewardPools (r:1 w:1)
+	// Storage: StakingRewards Stakes (r:0 w:1)
+	// Storage: Tokens Accounts (r:3 w:3)
+	// Storage: System Account (r:2 w:2)
+	fn message_staking_stake() -> Weight {
+		(186_207_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(6 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(7 as Weight))
+	}
+	// Storage: StakingRewards Stakes (r:1 w:1)
+	// Storage: StakingRewards RewardPools (r:1 w:1)
+	// Storage: Tokens Accounts (r:2 w:2)
+	// Storage: System Account (r:1 w:1)
+	fn message_staking_claim() -> Weight {
+		(81_552_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(5 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(5 as Weight))
+	}
+	// Storage: Cosmwasm XcmSendAllowlist (r:1 w:0)
+	// Storage: System Account (r:1 w:1)
+	// Storage: ParachainSystem HostConfiguration (r:1 w:0)
+	// Storage: ParachainSystem PendingUpwardMessages (r:1 w:1)
+	/// The range of component `n` is `[0, 65536]`.
+	fn xcm_send(n: u32, ) -> Weight {
+		(44_291_000 as Weight)
+			// Standard Error: 0
+			.saturating_add((2_000 as Weight).saturating_mul(n as Weight))
+			.saturating_add(RocksDbWeight::get().reads(4 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(2 as Weight))
+	}
+	// Storage: Cosmwasm XcmSendAllowlist (r:1 w:0)
+	// Storage: System Account (r:1 w:1)
+	// Storage: Tokens Accounts (r:1 w:1)
+	// Storage: Tokens TotalIssuance (r:1 w:1)
+	// Storage: ParachainSystem HostConfiguration (r:1 w:0)
+	// Storage: ParachainSystem PendingUpwardMessages (r:1 w:1)
+	fn xcm_transfer() -> Weight {
+		(171_958_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(6 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(4 as Weight))
+	}
 	// Storage: Cosmwasm ContractToInfo (r:1 w:1)
 	fn set_contract_meta() -> Weight {
 		(5_625_000 as Weight)
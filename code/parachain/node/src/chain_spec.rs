@@ -173,7 +173,7 @@ pub fn dali_dev(id: &str) -> dali::ChainSpec {
 						get_collator_keys_from_seed("Charlie"),
 					),
 				],
-				dev_accounts(),
+				[dev_accounts(), vec![dali_runtime::CosmwasmSudoAccount::get()]].concat(),
 				para_id,
 				common::fees::NATIVE_EXISTENTIAL_DEPOSIT,
 				dali_runtime::TreasuryAccount::get(),
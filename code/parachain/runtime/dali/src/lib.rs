@@ -1117,8 +1117,156 @@ impl Convert<CurrencyId, alloc::string::String> for AssetToDenom {
 	}
 }
 
+/// Assets enumerated by `BankQuery::AllBalances`, the well known ones along with the ones of the
+/// assets registry, up to [`CosmwasmMaxAssetsList`]. Only the local ids of the foreign assets are
+/// read, one storage item each.
+pub struct CosmwasmAssetsList;
+impl Get<Vec<CurrencyId>> for CosmwasmAssetsList {
+	fn get() -> Vec<CurrencyId> {
+		let max_assets = CosmwasmMaxAssetsList::get() as usize;
+		let mut assets = CurrencyId::list_assets()
+			.into_iter()
+			.map(|asset| CurrencyId(asset.id))
+			.take(max_assets)
+			.collect::<Vec<_>>();
+		let mut foreign_assets = assets_registry::ForeignToLocal::<Runtime>::iter_values();
+		while assets.len() < max_assets {
+			match foreign_assets.next() {
+				Some(asset) if !assets.contains(&asset) => assets.push(asset),
+				Some(_) => {},
+				None => break,
+			}
+		}
+		assets
+	}
+}
+
+/// Oracle prices and pools the cosmwasm custom queries and messages are benchmarked against.
+#[cfg(feature = "runtime-benchmarks")]
+pub struct CosmwasmBenchmarkHelper;
+#[cfg(feature = "runtime-benchmarks")]
+impl CosmwasmBenchmarkHelper {
+	fn owner() -> AccountId {
+		PalletId(*b"cw_bench").into_account_truncating()
+	}
+}
+#[cfg(feature = "runtime-benchmarks")]
+impl cosmwasm::BenchmarkHelper<Runtime> for CosmwasmBenchmarkHelper {
+	fn set_price(asset: CurrencyId) {
+		let price = composable_traits::oracle::Price {
+			price: 1_000_000_000_000,
+			block: System::block_number(),
+		};
+		let history = frame_support::BoundedVec::try_from(
+			sp_std::vec![price.clone(); MaxHistory::get() as usize],
+		)
+		.expect("sized by MaxHistory; qed");
+		oracle::PriceHistory::<Runtime>::insert(asset, history);
+		oracle::Prices::<Runtime>::insert(asset, price);
+	}
+
+	fn create_pool(base: CurrencyId, quote: CurrencyId) -> u128 {
+		let owner = Self::owner();
+		let mut assets_weights = frame_support::BoundedBTreeMap::new();
+		for asset in [base, quote] {
+			assets_weights
+				.try_insert(asset, Permill::from_percent(50))
+				.expect("two assets; qed");
+		}
+		let pool_id = Pablo::do_create_pool(
+			pablo::PoolInitConfiguration::DualAssetConstantProduct {
+				owner: owner.clone(),
+				assets_weights,
+				fee: Permill::from_percent(1),
+			},
+			None,
+		)
+		.expect("valid pool; qed");
+		let liquidity: Balance = 1_000_000_000_000_000;
+		for asset in [base, quote] {
+			<Assets as fungibles::Mutate<AccountId>>::mint_into(asset, &owner, liquidity)
+				.expect("mintable asset; qed");
+		}
+		<Pablo as Amm>::add_liquidity(
+			&owner,
+			pool_id,
+			BTreeMap::from([(base, liquidity), (quote, liquidity)]),
+			0,
+			false,
+		)
+		.expect("funded owner; qed");
+		pool_id
+	}
+
+	fn create_reward_pool() -> (CurrencyId, u64) {
+		use composable_support::validation::TryIntoValidated;
+		use composable_traits::staking::{
+			lock::LockConfig, ManageStaking, RewardConfig,
+			RewardPoolConfiguration::RewardRateBasedIncentive, RewardRate,
+		};
+		let [asset, share_asset_id] =
+			[(); 2].map(|_| CurrencyFactory::create(RangeId::TOKENS).expect("asset ids left; qed"));
+		let financial_nft_asset_id =
+			CurrencyFactory::create(RangeId::FNFT_ASSETS).expect("asset ids left; qed");
+		let duration_preset = 60 * 60;
+		let mut reward_configs = frame_support::BoundedBTreeMap::new();
+		reward_configs
+			.try_insert(
+				CurrencyId::PICA,
+				RewardConfig { reward_rate: RewardRate::per_second(10_000_000_000_u128) },
+			)
+			.expect("single reward; qed");
+		let mut duration_presets = frame_support::BoundedBTreeMap::new();
+		duration_presets
+			.try_insert(
+				duration_preset,
+				sp_arithmetic::fixed_point::FixedU64::from_rational(101, 100)
+					.try_into_validated()
+					.expect(">= 1; qed"),
+			)
+			.expect("single preset; qed");
+		let start_block = System::block_number() + 1;
+		<StakingRewards as ManageStaking>::create_staking_pool(RewardRateBasedIncentive {
+			owner: Self::owner(),
+			asset_id: asset,
+			start_block,
+			end_block: start_block + DAYS,
+			reward_configs,
+			lock: LockConfig { duration_presets, unlock_penalty: Perbill::from_percent(5) },
+			share_asset_id,
+			financial_nft_asset_id,
+			minimum_staking_amount: 1_000_000,
+		})
+		.expect("valid pool; qed");
+		(asset, duration_preset)
+	}
+
+	fn prepare_xcm() {
+		// Upward messages are only accepted once the relay chain provided its configuration.
+		let host_configuration = cumulus_primitives_core::AbridgedHostConfiguration {
+			max_code_size: 3 * 1024 * 1024,
+			max_head_data_size: 32 * 1024,
+			max_upward_queue_count: 8,
+			max_upward_queue_size: 1024 * 1024,
+			max_upward_message_size: 256 * 1024,
+			max_upward_message_num_per_candidate: 8,
+			hrmp_max_message_num_per_candidate: 8,
+			validation_upgrade_cooldown: 1,
+			validation_upgrade_delay: 1,
+		};
+		frame_support::storage::unhashed::put(
+			&frame_support::storage::storage_prefix(b"ParachainSystem", b"HostConfiguration"),
+			&host_configuration,
+		);
+	}
+}
+
 parameter_types! {
   pub const CosmwasmPalletId: PalletId = PalletId(*b"cosmwasm");
+  pub const CosmwasmSudoPalletId: PalletId = PalletId(*b"cw/sudo_");
+  /// Pays the funds sent along the `sudo` calls, endowed on dev chains and funded by governance
+  /// otherwise.
+  pub CosmwasmSudoAccount: AccountId = CosmwasmSudoPalletId::get().into_account_truncating();
   pub const ChainId: &'static str = "composable-network-dali";
   pub const MaxFrames: u32 = 64;
 	pub const MaxCodeSize: u32 = 512 * 1024;
@@ -1128,6 +1276,7 @@ parameter_types! {
   pub const MaxContractTrieIdSize: u32 = Hash::len_bytes() as u32;
   pub const MaxInstantiateSaltSize: u32 = 128;
   pub const MaxFundsAssets: u32 = 32;
+  pub const CosmwasmMaxAssetsList: u32 = 64;
   pub const CodeTableSizeLimit: u32 = 4096;
   pub const CodeGlobalVariableLimit: u32 = 256;
   pub const CodeParameterLimit: u32 = 128;
@@ -1137,8 +1286,10 @@ parameter_types! {
 
   // TODO: benchmark for proper values
   pub const CodeStorageByteDeposit: u32 = 1;
+  pub const ContractStorageByteDeposit: u32 = 1;
   pub const ContractStorageByteReadPrice: u32 = 1;
   pub const ContractStorageByteWritePrice: u32 = 1;
+  pub CosmwasmXcmDeliveryFee: Balance = CurrencyId::unit::<Balance>() / 10;
   pub WasmCostRules: CostRules<Runtime> = Default::default();
 }
 
@@ -1155,6 +1306,16 @@ impl cosmwasm::Config for Runtime {
 	type Balance = Balance;
 	type AssetId = CurrencyId;
 	type Assets = Assets;
+	type AssetsList = CosmwasmAssetsList;
+	type MaxAssetsList = CosmwasmMaxAssetsList;
+	type Oracle = Oracle;
+	type Pablo = Pablo;
+	type StakingRewards = StakingRewards;
+	type LocalAssets = CurrencyFactory;
+	type XcmSender = XcmRouter;
+	type XcmTransfer = XTokens;
+	type XcmDeliveryFee = CosmwasmXcmDeliveryFee;
+	type AccountIdToMultiLocation = common::xcmp::AccountIdToMultiLocation;
 	type NativeAsset = Balances;
 	type ChainId = ChainId;
 	type MaxContractLabelSize = MaxContractLabelSize;
@@ -1167,6 +1328,7 @@ impl cosmwasm::Config for Runtime {
 	type CodeBranchTableSizeLimit = CodeBranchTableSizeLimit;
 	type CodeStackLimit = CodeStackLimit;
 	type CodeStorageByteDeposit = CodeStorageByteDeposit;
+	type ContractStorageByteDeposit = ContractStorageByteDeposit;
 	type ContractStorageByteReadPrice = ContractStorageByteReadPrice;
 	type ContractStorageByteWritePrice = ContractStorageByteWritePrice;
 	type WasmCostRules = WasmCostRules;
@@ -1174,6 +1336,11 @@ impl cosmwasm::Config for Runtime {
 	type WeightInfo = cosmwasm::weights::SubstrateWeight<Runtime>;
 	type IbcRelayerAccount = TreasuryAccount;
 	type IbcRelayer = cosmwasm::NoRelayer<Runtime>;
+	type UploadOrigin = EnsureRootOrHalfNativeCouncil;
+	type SudoOrigin = EnsureRootOrHalfNativeCouncil;
+	type SudoAccount = CosmwasmSudoAccount;
+	#[cfg(feature = "runtime-benchmarks")]
+	type BenchmarkHelper = CosmwasmBenchmarkHelper;
 }
 
 parameter_types! {
@@ -1615,6 +1782,67 @@ impl_runtime_apis! {
 				message
 			).map_err(|err| alloc::format!("{:?}", err).into_bytes())
 		}
+
+		fn dry_run_execute(
+			executor: AccountId,
+			contract: AccountId,
+			funds: BTreeMap<CurrencyId, (Balance, bool)>,
+			gas: u64,
+			message: Vec<u8>,
+		) -> Result<cosmwasm_runtime_api::DryRunResult, Vec<u8>> {
+			cosmwasm::dry_run_execute::<Runtime>(
+				executor,
+				contract,
+				funds,
+				gas,
+				message
+			).map_err(|err| alloc::format!("{:?}", err).into_bytes())
+		}
+
+		fn dry_run_migrate(
+			migrator: AccountId,
+			contract: AccountId,
+			new_code_id: u64,
+			gas: u64,
+			message: Vec<u8>,
+		) -> Result<cosmwasm_runtime_api::DryRunResult, Vec<u8>> {
+			cosmwasm::dry_run_migrate::<Runtime>(
+				migrator,
+				contract,
+				new_code_id,
+				gas,
+				message
+			).map_err(|err| alloc::format!("{:?}", err).into_bytes())
+		}
+
+		fn contract_info(
+			contract: AccountId,
+		) -> Result<cosmwasm_runtime_api::ContractInfoResult<AccountId>, Vec<u8>> {
+			cosmwasm::contract_info::<Runtime>(contract)
+				.map_err(|err| alloc::format!("{:?}", err).into_bytes())
+		}
+
+		fn contract_state(
+			contract: AccountId,
+			prefix: Option<Vec<u8>>,
+			start_after: Option<Vec<u8>>,
+			limit: u32,
+		) -> Result<cosmwasm_runtime_api::ContractStatePage, Vec<u8>> {
+			cosmwasm::contract_state::<Runtime>(
+				contract,
+				prefix,
+				start_after,
+				limit
+			).map_err(|err| alloc::format!("{:?}", err).into_bytes())
+		}
+
+		fn contracts_by_code(
+			code_id: u64,
+			start_after: Option<AccountId>,
+			limit: u32,
+		) -> Vec<AccountId> {
+			cosmwasm::contracts_by_code::<Runtime>(code_id, start_after, limit)
+		}
 	}
 
 	impl sp_api::Core<Block> for Runtime {
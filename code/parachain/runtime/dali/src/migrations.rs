@@ -2,7 +2,12 @@ use crate::{prelude::*, *};
 
 use pablo_picasso_init_pools::PabloPicassoInitialPoolsMigration;
 
-pub type Migrations = (PabloPicassoInitialPoolsMigration, SchedulerMigrationV3);
+pub type Migrations = (
+	PabloPicassoInitialPoolsMigration,
+	SchedulerMigrationV3,
+	cosmwasm::migrations::v1::AddStorageUsage<Runtime>,
+	cosmwasm::migrations::v2::IndexContractsByCode<Runtime>,
+);
 
 // Migration for scheduler pallet to move from a plain Call to a CallOrHash.
 pub struct SchedulerMigrationV3;
@@ -1,33 +1,50 @@
 use crate::{
 	error::ContractError,
 	msg::{
-		AssetKey, AssetReference, ExecuteMsg, InstantiateMsg, LookupResponse, MigrateMsg, QueryMsg,
+		AssetInfoResponse, AssetInfosResponse, AssetKey, AssetReference, AssetsResponse,
+		ConfigResponse, ExecuteMsg, InstantiateMsg, LookupResponse, MigrateMsg, QueryMsg,
+		RegistrarsResponse, RemoteAssetResponse, RemoteAssetsResponse,
+	},
+	state::{
+		Config, ASSETS, ASSET_IDS_BY_NAME, ASSET_INFOS, ASSET_ORIGIN_NETWORKS, CONFIG, REGISTRARS,
+		REMOTE_ASSETS,
 	},
-	state::ASSETS,
 };
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{
-	to_binary, Binary, Deps, DepsMut, Env, Event, MessageInfo, QuerierWrapper, Response, StdResult,
-	WasmQuery,
+	to_binary, Binary, Deps, DepsMut, Env, Event, MessageInfo, Order, QuerierWrapper, Response,
+	StdError, StdResult, WasmQuery,
 };
 use cw2::set_contract_version;
+use cw_storage_plus::Bound;
 use cw_utils::ensure_from_older_version;
+use xcvm_core::{AssetId, AssetInfo, NetworkId};
 
 const CONTRACT_NAME: &str = "composable:xcvm-asset-registry";
 const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 pub const XCVM_ASSET_REGISTRY_EVENT_PREFIX: &str = "xcvm.registry";
 
+const DEFAULT_QUERY_LIMIT: u32 = 10;
+const MAX_QUERY_LIMIT: u32 = 30;
+
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
 	deps: DepsMut,
 	_env: Env,
-	_info: MessageInfo,
-	_msg: InstantiateMsg,
+	info: MessageInfo,
+	msg: InstantiateMsg,
 ) -> Result<Response, ContractError> {
 	set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+	let admin = match msg.admin {
+		Some(admin) => deps.api.addr_validate(&admin)?,
+		None => info.sender,
+	};
+	CONFIG.save(deps.storage, &Config { admin: admin.clone(), pending_admin: None })?;
 	Ok(Response::default().add_event(
-		Event::new(XCVM_ASSET_REGISTRY_EVENT_PREFIX).add_attribute("action", "instantiated"),
+		Event::new(XCVM_ASSET_REGISTRY_EVENT_PREFIX)
+			.add_attribute("action", "instantiated")
+			.add_attribute("admin", admin),
 	))
 }
 
@@ -35,19 +52,105 @@ pub fn instantiate(
 pub fn execute(
 	deps: DepsMut,
 	_env: Env,
-	_info: MessageInfo,
+	info: MessageInfo,
 	msg: ExecuteMsg,
 ) -> Result<Response, ContractError> {
 	match msg {
-		ExecuteMsg::RegisterAsset { asset_id, reference } =>
-			handle_register_asset(deps, asset_id, reference),
-		ExecuteMsg::UnregisterAsset { asset_id } => handle_unregister_asset(deps, asset_id),
+		ExecuteMsg::RegisterAsset { asset_id, reference, origin_network } => {
+			let is_admin = ensure_admin(deps.as_ref(), &info).is_ok();
+			if !is_admin {
+				ensure_registrar(deps.as_ref(), &info)?;
+				if ASSETS.has(deps.storage, asset_id) {
+					return Err(ContractError::AssetAlreadyRegistered)
+				}
+			}
+			handle_register_asset(deps, asset_id, reference, origin_network)
+		},
+		ExecuteMsg::UnregisterAsset { asset_id } => {
+			ensure_admin(deps.as_ref(), &info)?;
+			handle_unregister_asset(deps, asset_id)
+		},
+		ExecuteMsg::SetAssetInfo { asset_id, name, decimals } => {
+			let is_admin = ensure_admin(deps.as_ref(), &info).is_ok();
+			if !is_admin {
+				ensure_registrar(deps.as_ref(), &info)?;
+				if ASSET_INFOS.has(deps.storage, asset_id) {
+					return Err(ContractError::AssetInfoAlreadySet)
+				}
+			}
+			handle_set_asset_info(deps, asset_id, name, decimals)
+		},
+		ExecuteMsg::SetRemoteAsset { asset_id, network_id, remote_asset_id } => {
+			ensure_admin(deps.as_ref(), &info)?;
+			handle_set_remote_asset(deps, asset_id, network_id, remote_asset_id)
+		},
+		ExecuteMsg::AddRegistrar { registrar } => {
+			ensure_admin(deps.as_ref(), &info)?;
+			let registrar = deps.api.addr_validate(&registrar)?;
+			REGISTRARS.save(deps.storage, registrar.clone(), &())?;
+			Ok(Response::new().add_event(
+				Event::new(XCVM_ASSET_REGISTRY_EVENT_PREFIX)
+					.add_attribute("action", "add_registrar")
+					.add_attribute("registrar", registrar),
+			))
+		},
+		ExecuteMsg::RemoveRegistrar { registrar } => {
+			ensure_admin(deps.as_ref(), &info)?;
+			let registrar = deps.api.addr_validate(&registrar)?;
+			REGISTRARS.remove(deps.storage, registrar.clone());
+			Ok(Response::new().add_event(
+				Event::new(XCVM_ASSET_REGISTRY_EVENT_PREFIX)
+					.add_attribute("action", "remove_registrar")
+					.add_attribute("registrar", registrar),
+			))
+		},
+		ExecuteMsg::TransferOwnership { admin } => {
+			ensure_admin(deps.as_ref(), &info)?;
+			let pending_admin = deps.api.addr_validate(&admin)?;
+			CONFIG.update(deps.storage, |config| -> StdResult<_> {
+				Ok(Config { pending_admin: Some(pending_admin.clone()), ..config })
+			})?;
+			Ok(Response::new().add_event(
+				Event::new(XCVM_ASSET_REGISTRY_EVENT_PREFIX)
+					.add_attribute("action", "transfer_ownership")
+					.add_attribute("pending_admin", pending_admin),
+			))
+		},
+		ExecuteMsg::AcceptOwnership {} => {
+			let config = CONFIG.load(deps.storage)?;
+			if config.pending_admin.as_ref() != Some(&info.sender) {
+				return Err(ContractError::NotAuthorized)
+			}
+			CONFIG
+				.save(deps.storage, &Config { admin: info.sender.clone(), pending_admin: None })?;
+			Ok(Response::new().add_event(
+				Event::new(XCVM_ASSET_REGISTRY_EVENT_PREFIX)
+					.add_attribute("action", "accept_ownership")
+					.add_attribute("admin", info.sender),
+			))
+		},
 	}
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
+pub fn migrate(deps: DepsMut, _env: Env, msg: MigrateMsg) -> Result<Response, ContractError> {
 	let _ = ensure_from_older_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+	// Registries deployed without access control must be given an admin.
+	match (msg.admin, CONFIG.may_load(deps.storage)?) {
+		(Some(admin), _) => {
+			let admin = deps.api.addr_validate(&admin)?;
+			CONFIG.save(deps.storage, &Config { admin, pending_admin: None })?;
+		},
+		(None, Some(_)) => {},
+		(None, None) => return Err(ContractError::MissingAdmin),
+	}
+	// Index the names of the assets registered before the index.
+	let infos = ASSET_INFOS
+		.range(deps.storage, None, None, Order::Ascending)
+		.collect::<StdResult<Vec<_>>>()?;
+	for (asset_id, info) in infos {
+		ASSET_IDS_BY_NAME.save(deps.storage, &info.name, &asset_id.into())?;
+	}
 	Ok(Response::default())
 }
 
@@ -55,6 +158,43 @@ pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, C
 pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
 	match msg {
 		QueryMsg::Lookup { asset_id } => to_binary(&query_lookup(deps, asset_id)?),
+		QueryMsg::AssetInfo { asset_id } => to_binary(&query_asset_info(deps, asset_id)?),
+		QueryMsg::AssetInfoByName { name } => to_binary(&query_asset_info_by_name(deps, name)?),
+		QueryMsg::AssetInfos { start_after, limit } =>
+			to_binary(&query_asset_infos(deps, start_after, limit)?),
+		QueryMsg::Assets { start_after, limit } =>
+			to_binary(&query_assets(deps, start_after, limit)?),
+		QueryMsg::RemoteAsset { asset_id, network_id } =>
+			to_binary(&query_remote_asset(deps, asset_id, network_id)?),
+		QueryMsg::RemoteAssets { asset_id, start_after, limit } =>
+			to_binary(&query_remote_assets(deps, asset_id, start_after, limit)?),
+		QueryMsg::Config {} => {
+			let Config { admin, pending_admin } = CONFIG.load(deps.storage)?;
+			to_binary(&ConfigResponse { admin, pending_admin })
+		},
+		QueryMsg::Registrars {} => {
+			let registrars = REGISTRARS
+				.keys(deps.storage, None, None, Order::Ascending)
+				.collect::<StdResult<Vec<_>>>()?;
+			to_binary(&RegistrarsResponse { registrars })
+		},
+	}
+}
+
+fn ensure_admin(deps: Deps, info: &MessageInfo) -> Result<(), ContractError> {
+	let Config { admin, .. } = CONFIG.load(deps.storage)?;
+	if admin == info.sender {
+		Ok(())
+	} else {
+		Err(ContractError::NotAuthorized)
+	}
+}
+
+fn ensure_registrar(deps: Deps, info: &MessageInfo) -> Result<(), ContractError> {
+	if REGISTRARS.has(deps.storage, info.sender.clone()) {
+		Ok(())
+	} else {
+		Err(ContractError::NotAuthorized)
 	}
 }
 
@@ -62,8 +202,13 @@ pub fn handle_register_asset(
 	deps: DepsMut,
 	asset_id: AssetKey,
 	reference: AssetReference,
+	origin_network: Option<NetworkId>,
 ) -> Result<Response, ContractError> {
 	ASSETS.save(deps.storage, asset_id, &reference)?;
+	match origin_network {
+		Some(network_id) => ASSET_ORIGIN_NETWORKS.save(deps.storage, asset_id, &network_id)?,
+		None => ASSET_ORIGIN_NETWORKS.remove(deps.storage, asset_id),
+	}
 	Ok(Response::new().add_event(
 		Event::new(XCVM_ASSET_REGISTRY_EVENT_PREFIX)
 			.add_attribute("action", "register")
@@ -77,6 +222,18 @@ pub fn handle_unregister_asset(
 	asset_id: AssetKey,
 ) -> Result<Response, ContractError> {
 	ASSETS.remove(deps.storage, asset_id);
+	if let Some(info) = ASSET_INFOS.may_load(deps.storage, asset_id)? {
+		ASSET_IDS_BY_NAME.remove(deps.storage, &info.name);
+	}
+	ASSET_INFOS.remove(deps.storage, asset_id);
+	ASSET_ORIGIN_NETWORKS.remove(deps.storage, asset_id);
+	let remote_networks = REMOTE_ASSETS
+		.prefix(asset_id.into())
+		.keys(deps.storage, None, None, Order::Ascending)
+		.collect::<StdResult<Vec<_>>>()?;
+	for network_id in remote_networks {
+		REMOTE_ASSETS.remove(deps.storage, (asset_id.into(), network_id));
+	}
 	Ok(Response::new().add_event(
 		Event::new(XCVM_ASSET_REGISTRY_EVENT_PREFIX)
 			.add_attribute("action", "unregister")
@@ -84,11 +241,136 @@ pub fn handle_unregister_asset(
 	))
 }
 
+pub fn handle_set_asset_info(
+	deps: DepsMut,
+	asset_id: AssetKey,
+	name: String,
+	decimals: u8,
+) -> Result<Response, ContractError> {
+	if !ASSETS.has(deps.storage, asset_id) {
+		return Err(ContractError::UnknownAsset)
+	}
+	match ASSET_IDS_BY_NAME.may_load(deps.storage, &name)? {
+		Some(id) if AssetKey::from(id) != asset_id =>
+			return Err(ContractError::AssetNameAlreadyRegistered),
+		_ => {},
+	}
+	if let Some(previous) = ASSET_INFOS.may_load(deps.storage, asset_id)? {
+		ASSET_IDS_BY_NAME.remove(deps.storage, &previous.name);
+	}
+	let info = AssetInfo { id: asset_id.into(), name, decimals };
+	ASSET_INFOS.save(deps.storage, asset_id, &info)?;
+	ASSET_IDS_BY_NAME.save(deps.storage, &info.name, &info.id)?;
+	Ok(Response::new().add_event(
+		Event::new(XCVM_ASSET_REGISTRY_EVENT_PREFIX)
+			.add_attribute("action", "set_info")
+			.add_attribute("asset_id", format!("{:?}", asset_id))
+			.add_attribute("name", info.name)
+			.add_attribute("decimals", info.decimals.to_string()),
+	))
+}
+
+pub fn handle_set_remote_asset(
+	deps: DepsMut,
+	asset_id: AssetKey,
+	network_id: NetworkId,
+	remote_asset_id: Option<AssetKey>,
+) -> Result<Response, ContractError> {
+	if !ASSETS.has(deps.storage, asset_id) {
+		return Err(ContractError::UnknownAsset)
+	}
+	let key = (asset_id.into(), network_id.0);
+	match remote_asset_id {
+		Some(remote_asset_id) => REMOTE_ASSETS.save(deps.storage, key, &remote_asset_id.into())?,
+		None => REMOTE_ASSETS.remove(deps.storage, key),
+	}
+	Ok(Response::new().add_event(
+		Event::new(XCVM_ASSET_REGISTRY_EVENT_PREFIX)
+			.add_attribute("action", "set_remote_asset")
+			.add_attribute("asset_id", format!("{:?}", asset_id))
+			.add_attribute("network_id", network_id.to_string())
+			.add_attribute("remote_asset_id", format!("{:?}", remote_asset_id)),
+	))
+}
+
 pub fn query_lookup(deps: Deps, asset_id: AssetKey) -> StdResult<LookupResponse> {
 	let reference = ASSETS.load(deps.storage, asset_id)?;
 	Ok(LookupResponse { reference })
 }
 
+pub fn query_asset_info(deps: Deps, asset_id: AssetKey) -> StdResult<AssetInfoResponse> {
+	let info = ASSET_INFOS.load(deps.storage, asset_id)?;
+	let origin_network = ASSET_ORIGIN_NETWORKS.may_load(deps.storage, asset_id)?;
+	Ok(AssetInfoResponse { info, origin_network })
+}
+
+pub fn query_asset_info_by_name(deps: Deps, name: String) -> StdResult<AssetInfoResponse> {
+	let asset_id = ASSET_IDS_BY_NAME
+		.may_load(deps.storage, &name)?
+		.ok_or_else(|| StdError::not_found(format!("asset {name}")))?;
+	query_asset_info(deps, asset_id.into())
+}
+
+pub fn query_asset_infos(
+	deps: Deps,
+	start_after: Option<AssetKey>,
+	limit: Option<u32>,
+) -> StdResult<AssetInfosResponse> {
+	let limit = limit.unwrap_or(DEFAULT_QUERY_LIMIT).min(MAX_QUERY_LIMIT) as usize;
+	let infos = ASSET_INFOS
+		.range(deps.storage, start_after.map(Bound::exclusive), None, Order::Ascending)
+		.take(limit)
+		.map(|entry| entry.map(|(_, info)| info))
+		.collect::<StdResult<Vec<_>>>()?;
+	Ok(AssetInfosResponse { infos })
+}
+
+pub fn query_assets(
+	deps: Deps,
+	start_after: Option<AssetKey>,
+	limit: Option<u32>,
+) -> StdResult<AssetsResponse> {
+	let limit = limit.unwrap_or(DEFAULT_QUERY_LIMIT).min(MAX_QUERY_LIMIT) as usize;
+	let assets = ASSETS
+		.range(deps.storage, start_after.map(Bound::exclusive), None, Order::Ascending)
+		.take(limit)
+		.map(|entry| entry.map(|(asset_id, reference)| (asset_id.into(), reference)))
+		.collect::<StdResult<Vec<_>>>()?;
+	Ok(AssetsResponse { assets })
+}
+
+pub fn query_remote_asset(
+	deps: Deps,
+	asset_id: AssetKey,
+	network_id: NetworkId,
+) -> StdResult<RemoteAssetResponse> {
+	let remote_asset_id = REMOTE_ASSETS.may_load(deps.storage, (asset_id.into(), network_id.0))?;
+	Ok(RemoteAssetResponse { remote_asset_id })
+}
+
+pub fn query_remote_assets(
+	deps: Deps,
+	asset_id: AssetKey,
+	start_after: Option<NetworkId>,
+	limit: Option<u32>,
+) -> StdResult<RemoteAssetsResponse> {
+	let limit = limit.unwrap_or(DEFAULT_QUERY_LIMIT).min(MAX_QUERY_LIMIT) as usize;
+	let remote_assets = REMOTE_ASSETS
+		.prefix(asset_id.into())
+		.range(
+			deps.storage,
+			start_after.map(|network_id| Bound::exclusive(network_id.0)),
+			None,
+			Order::Ascending,
+		)
+		.take(limit)
+		.map(|entry| {
+			entry.map(|(network_id, remote_asset_id)| (network_id.into(), remote_asset_id))
+		})
+		.collect::<StdResult<Vec<_>>>()?;
+	Ok(RemoteAssetsResponse { remote_assets })
+}
+
 pub fn external_query_lookup_asset(
 	querier: QuerierWrapper,
 	registry_addr: String,
@@ -105,20 +387,64 @@ pub fn external_query_lookup_asset(
 		.map(|response| response.reference)
 }
 
+/// Page of the registered assets, by ascending asset id.
+pub fn external_query_assets(
+	querier: QuerierWrapper,
+	registry_addr: String,
+	start_after: Option<AssetId>,
+	limit: Option<u32>,
+) -> StdResult<Vec<(AssetId, AssetReference)>> {
+	querier
+		.query::<AssetsResponse>(
+			&WasmQuery::Smart {
+				contract_addr: registry_addr,
+				msg: to_binary(&QueryMsg::Assets {
+					start_after: start_after.map(Into::into),
+					limit,
+				})?,
+			}
+			.into(),
+		)
+		.map(|response| response.assets)
+}
+
+/// Identifier of `asset_id` on `network_id`, the same as the local one unless mapped otherwise.
+pub fn external_query_remote_asset(
+	querier: QuerierWrapper,
+	registry_addr: String,
+	asset_id: impl Into<AssetKey>,
+	network_id: NetworkId,
+) -> StdResult<AssetId> {
+	let asset_id = asset_id.into();
+	querier
+		.query::<RemoteAssetResponse>(
+			&WasmQuery::Smart {
+				contract_addr: registry_addr,
+				msg: to_binary(&QueryMsg::RemoteAsset { asset_id, network_id })?,
+			}
+			.into(),
+		)
+		.map(|response| response.remote_asset_id.unwrap_or_else(|| asset_id.into()))
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
+	use crate::helpers::AssetRegistryContract;
 	use cosmwasm_std::{
 		from_binary,
 		testing::{mock_dependencies, mock_env, mock_info},
-		Addr, Order,
+		Addr, ContractResult, Order, SystemResult,
 	};
+	use cw_xcvm_common::gateway::{NetworkResponse, NetworksResponse, QueryMsg as GatewayQueryMsg};
+	use std::{cell::Cell, rc::Rc};
+	use xcvm_core::{Asset, Juno, MemoryRegistry, Network, NetworkInfo, Registry, PICA};
 
 	#[test]
 	fn proper_instantiation() {
 		let mut deps = mock_dependencies();
 
-		let msg = InstantiateMsg {};
+		let msg = InstantiateMsg { admin: None };
 		let info = mock_info("sender", &vec![]);
 
 		let res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
@@ -129,7 +455,7 @@ mod tests {
 	fn register_unregister_assets() {
 		let mut deps = mock_dependencies();
 
-		let msg = InstantiateMsg {};
+		let msg = InstantiateMsg { admin: None };
 		let info = mock_info("sender", &vec![]);
 
 		let _ = instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
@@ -143,7 +469,11 @@ mod tests {
 			deps.as_mut(),
 			mock_env(),
 			info.clone(),
-			ExecuteMsg::RegisterAsset { asset_id: 1.into(), reference: addr1.clone() },
+			ExecuteMsg::RegisterAsset {
+				asset_id: 1.into(),
+				reference: addr1.clone(),
+				origin_network: None,
+			},
 		)
 		.unwrap();
 
@@ -151,7 +481,11 @@ mod tests {
 			deps.as_mut(),
 			mock_env(),
 			info.clone(),
-			ExecuteMsg::RegisterAsset { asset_id: 2.into(), reference: addr2.clone() },
+			ExecuteMsg::RegisterAsset {
+				asset_id: 2.into(),
+				reference: addr2.clone(),
+				origin_network: None,
+			},
 		)
 		.unwrap();
 
@@ -178,7 +512,11 @@ mod tests {
 			deps.as_mut(),
 			mock_env(),
 			info.clone(),
-			ExecuteMsg::RegisterAsset { asset_id: 3.into(), reference: addr3.clone() },
+			ExecuteMsg::RegisterAsset {
+				asset_id: 3.into(),
+				reference: addr3.clone(),
+				origin_network: None,
+			},
 		)
 		.unwrap();
 
@@ -186,7 +524,11 @@ mod tests {
 			deps.as_mut(),
 			mock_env(),
 			info.clone(),
-			ExecuteMsg::RegisterAsset { asset_id: 4.into(), reference: addr4.clone() },
+			ExecuteMsg::RegisterAsset {
+				asset_id: 4.into(),
+				reference: addr4.clone(),
+				origin_network: None,
+			},
 		)
 		.unwrap();
 
@@ -210,7 +552,7 @@ mod tests {
 	fn query_assets() {
 		let mut deps = mock_dependencies();
 
-		let msg = InstantiateMsg {};
+		let msg = InstantiateMsg { admin: None };
 		let info = mock_info("sender", &vec![]);
 
 		let _ = instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
@@ -221,7 +563,7 @@ mod tests {
 			deps.as_mut(),
 			mock_env(),
 			info.clone(),
-			ExecuteMsg::RegisterAsset { asset_id, reference: addr1.clone() },
+			ExecuteMsg::RegisterAsset { asset_id, reference: addr1.clone(), origin_network: None },
 		)
 		.unwrap();
 
@@ -230,10 +572,380 @@ mod tests {
 				.unwrap();
 
 		// Query should return the corresponding address
-		assert_eq!(res, LookupResponse { reference: addr1 });
+		assert_eq!(res, LookupResponse { reference: addr1.clone() });
+
+		let res: AssetsResponse = from_binary(
+			&query(deps.as_ref(), mock_env(), QueryMsg::Assets { start_after: None, limit: None })
+				.unwrap(),
+		)
+		.unwrap();
+		assert_eq!(res, AssetsResponse { assets: vec![(asset_id.into(), addr1)] });
 
 		// This should fail since there the asset doesn't exist
 		assert!(query(deps.as_ref(), mock_env(), QueryMsg::Lookup { asset_id: AssetKey::from(2) })
 			.is_err());
 	}
+
+	#[test]
+	fn asset_infos() {
+		let mut deps = mock_dependencies();
+		let info = mock_info("sender", &vec![]);
+		let _ =
+			instantiate(deps.as_mut(), mock_env(), info.clone(), InstantiateMsg { admin: None })
+				.unwrap();
+
+		let set_info = |name: &str| ExecuteMsg::SetAssetInfo {
+			asset_id: 1.into(),
+			name: name.into(),
+			decimals: 6,
+		};
+
+		// The asset must be registered before attaching its metadata
+		assert!(matches!(
+			execute(deps.as_mut(), mock_env(), info.clone(), set_info("OSMO")),
+			Err(ContractError::UnknownAsset)
+		));
+
+		let _ = execute(
+			deps.as_mut(),
+			mock_env(),
+			info.clone(),
+			ExecuteMsg::RegisterAsset {
+				asset_id: 1.into(),
+				reference: AssetReference::Native { denom: "uosmo".into() },
+				origin_network: Some(2.into()),
+			},
+		)
+		.unwrap();
+		let _ = execute(deps.as_mut(), mock_env(), info.clone(), set_info("OSMO")).unwrap();
+
+		let expected = AssetInfo { id: 1.into(), name: "OSMO".into(), decimals: 6 };
+		let res: AssetInfoResponse = from_binary(
+			&query(deps.as_ref(), mock_env(), QueryMsg::AssetInfo { asset_id: 1.into() }).unwrap(),
+		)
+		.unwrap();
+		assert_eq!(
+			res,
+			AssetInfoResponse { info: expected.clone(), origin_network: Some(2.into()) }
+		);
+		let res: AssetInfosResponse = from_binary(
+			&query(
+				deps.as_ref(),
+				mock_env(),
+				QueryMsg::AssetInfos { start_after: None, limit: None },
+			)
+			.unwrap(),
+		)
+		.unwrap();
+		assert_eq!(res.infos, vec![expected.clone()]);
+		let by_name = |deps: Deps, name: &str| {
+			query(deps, mock_env(), QueryMsg::AssetInfoByName { name: name.into() })
+				.map(|res| from_binary::<AssetInfoResponse>(&res).unwrap().info)
+		};
+		assert_eq!(by_name(deps.as_ref(), "OSMO").unwrap(), expected);
+
+		// Names are unique, renaming an asset frees its previous name
+		let _ = execute(
+			deps.as_mut(),
+			mock_env(),
+			info.clone(),
+			ExecuteMsg::RegisterAsset {
+				asset_id: 2.into(),
+				reference: AssetReference::Native { denom: "uatom".into() },
+				origin_network: None,
+			},
+		)
+		.unwrap();
+		let set_info_of = |asset_id: u128, name: &str| ExecuteMsg::SetAssetInfo {
+			asset_id: asset_id.into(),
+			name: name.into(),
+			decimals: 6,
+		};
+		assert!(matches!(
+			execute(deps.as_mut(), mock_env(), info.clone(), set_info_of(2, "OSMO")),
+			Err(ContractError::AssetNameAlreadyRegistered)
+		));
+		let _ = execute(deps.as_mut(), mock_env(), info.clone(), set_info_of(1, "WOSMO")).unwrap();
+		let _ = execute(deps.as_mut(), mock_env(), info.clone(), set_info_of(2, "OSMO")).unwrap();
+		assert_eq!(by_name(deps.as_ref(), "OSMO").unwrap().id, 2.into());
+
+		// Unregistering the asset also removes its metadata
+		let _ = execute(
+			deps.as_mut(),
+			mock_env(),
+			info,
+			ExecuteMsg::UnregisterAsset { asset_id: 1.into() },
+		)
+		.unwrap();
+		assert!(
+			query(deps.as_ref(), mock_env(), QueryMsg::AssetInfo { asset_id: 1.into() }).is_err()
+		);
+		assert!(by_name(deps.as_ref(), "WOSMO").is_err());
+	}
+
+	#[test]
+	fn access_control() {
+		let mut deps = mock_dependencies();
+		let admin = mock_info("admin", &[]);
+		let gateway = mock_info("gateway", &[]);
+		let _ =
+			instantiate(deps.as_mut(), mock_env(), admin.clone(), InstantiateMsg { admin: None })
+				.unwrap();
+
+		let register = |asset_id: u128, denom: &str| ExecuteMsg::RegisterAsset {
+			asset_id: asset_id.into(),
+			reference: AssetReference::Native { denom: denom.into() },
+			origin_network: None,
+		};
+		assert!(matches!(
+			execute(deps.as_mut(), mock_env(), gateway.clone(), register(1, "uatom")),
+			Err(ContractError::NotAuthorized)
+		));
+
+		// Registrars can only register new assets
+		let _ = execute(
+			deps.as_mut(),
+			mock_env(),
+			admin.clone(),
+			ExecuteMsg::AddRegistrar { registrar: "gateway".into() },
+		)
+		.unwrap();
+		let _ = execute(deps.as_mut(), mock_env(), gateway.clone(), register(1, "uatom")).unwrap();
+		assert!(matches!(
+			execute(deps.as_mut(), mock_env(), gateway.clone(), register(1, "uosmo")),
+			Err(ContractError::AssetAlreadyRegistered)
+		));
+		assert!(matches!(
+			execute(
+				deps.as_mut(),
+				mock_env(),
+				gateway.clone(),
+				ExecuteMsg::UnregisterAsset { asset_id: 1.into() }
+			),
+			Err(ContractError::NotAuthorized)
+		));
+		let _ = execute(deps.as_mut(), mock_env(), admin.clone(), register(1, "uosmo")).unwrap();
+
+		// Registrars can only attach metadata to assets having none
+		let set_info = |name: &str| ExecuteMsg::SetAssetInfo {
+			asset_id: 1.into(),
+			name: name.into(),
+			decimals: 6,
+		};
+		let _ = execute(deps.as_mut(), mock_env(), gateway.clone(), set_info("OSMO")).unwrap();
+		assert!(matches!(
+			execute(deps.as_mut(), mock_env(), gateway.clone(), set_info("FAKE")),
+			Err(ContractError::AssetInfoAlreadySet)
+		));
+		let _ = execute(deps.as_mut(), mock_env(), admin.clone(), set_info("ATOM")).unwrap();
+
+		// The ownership is transferred once accepted
+		let _ = execute(
+			deps.as_mut(),
+			mock_env(),
+			admin.clone(),
+			ExecuteMsg::TransferOwnership { admin: "new_admin".into() },
+		)
+		.unwrap();
+		assert!(matches!(
+			execute(deps.as_mut(), mock_env(), gateway, ExecuteMsg::AcceptOwnership {}),
+			Err(ContractError::NotAuthorized)
+		));
+		let _ = execute(
+			deps.as_mut(),
+			mock_env(),
+			mock_info("new_admin", &[]),
+			ExecuteMsg::AcceptOwnership {},
+		)
+		.unwrap();
+		let res: ConfigResponse =
+			from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap()).unwrap();
+		assert_eq!(
+			res,
+			ConfigResponse { admin: Addr::unchecked("new_admin"), pending_admin: None }
+		);
+		assert!(matches!(
+			execute(deps.as_mut(), mock_env(), admin, register(2, "uatom")),
+			Err(ContractError::NotAuthorized)
+		));
+	}
+
+	#[test]
+	fn remote_assets_and_pagination() {
+		let mut deps = mock_dependencies();
+		let info = mock_info("sender", &[]);
+		let _ =
+			instantiate(deps.as_mut(), mock_env(), info.clone(), InstantiateMsg { admin: None })
+				.unwrap();
+		for asset_id in 1..=12_u128 {
+			let _ = execute(
+				deps.as_mut(),
+				mock_env(),
+				info.clone(),
+				ExecuteMsg::RegisterAsset {
+					asset_id: asset_id.into(),
+					reference: AssetReference::Native { denom: format!("denom{}", asset_id) },
+					origin_network: None,
+				},
+			)
+			.unwrap();
+		}
+
+		let assets = |start_after: Option<u128>, limit| -> Vec<u128> {
+			let res: AssetsResponse = from_binary(
+				&query(
+					deps.as_ref(),
+					mock_env(),
+					QueryMsg::Assets { start_after: start_after.map(Into::into), limit },
+				)
+				.unwrap(),
+			)
+			.unwrap();
+			res.assets.into_iter().map(|(asset_id, _)| asset_id.into()).collect()
+		};
+		assert_eq!(assets(None, None), (1..=10).collect::<Vec<_>>());
+		assert_eq!(assets(Some(10), None), [11, 12]);
+		assert_eq!(assets(Some(2), Some(2)), [3, 4]);
+
+		let set_remote = |remote_asset_id: Option<u128>| ExecuteMsg::SetRemoteAsset {
+			asset_id: 1.into(),
+			network_id: 2.into(),
+			remote_asset_id: remote_asset_id.map(Into::into),
+		};
+		let remote_asset = |deps: Deps| -> Option<AssetId> {
+			let res: RemoteAssetResponse = from_binary(
+				&query(
+					deps,
+					mock_env(),
+					QueryMsg::RemoteAsset { asset_id: 1.into(), network_id: 2.into() },
+				)
+				.unwrap(),
+			)
+			.unwrap();
+			res.remote_asset_id
+		};
+		let _ = execute(deps.as_mut(), mock_env(), info.clone(), set_remote(Some(42))).unwrap();
+		assert_eq!(remote_asset(deps.as_ref()), Some(42.into()));
+		let res: RemoteAssetsResponse = from_binary(
+			&query(
+				deps.as_ref(),
+				mock_env(),
+				QueryMsg::RemoteAssets { asset_id: 1.into(), start_after: None, limit: None },
+			)
+			.unwrap(),
+		)
+		.unwrap();
+		assert_eq!(res.remote_assets, vec![(2.into(), 42.into())]);
+		let res: RemoteAssetsResponse = from_binary(
+			&query(
+				deps.as_ref(),
+				mock_env(),
+				QueryMsg::RemoteAssets {
+					asset_id: 1.into(),
+					start_after: Some(2.into()),
+					limit: None,
+				},
+			)
+			.unwrap(),
+		)
+		.unwrap();
+		assert_eq!(res.remote_assets, vec![]);
+		let _ = execute(deps.as_mut(), mock_env(), info.clone(), set_remote(None)).unwrap();
+		assert_eq!(remote_asset(deps.as_ref()), None);
+
+		// Unregistering the asset drops its remote counterparts
+		let _ = execute(deps.as_mut(), mock_env(), info.clone(), set_remote(Some(42))).unwrap();
+		let _ = execute(
+			deps.as_mut(),
+			mock_env(),
+			info,
+			ExecuteMsg::UnregisterAsset { asset_id: 1.into() },
+		)
+		.unwrap();
+		assert_eq!(remote_asset(deps.as_ref()), None);
+	}
+
+	#[test]
+	fn contract_registry_resolves_registered_assets() {
+		let mut registry = mock_dependencies();
+		let admin = mock_info("admin", &[]);
+		let _ = instantiate(
+			registry.as_mut(),
+			mock_env(),
+			admin.clone(),
+			InstantiateMsg { admin: None },
+		)
+		.unwrap();
+		for msg in [
+			ExecuteMsg::RegisterAsset {
+				asset_id: 5.into(),
+				reference: AssetReference::Native { denom: "uosmo".into() },
+				origin_network: None,
+			},
+			ExecuteMsg::SetAssetInfo { asset_id: 5.into(), name: "OSMO".into(), decimals: 6 },
+		] {
+			let _ = execute(registry.as_mut(), mock_env(), admin.clone(), msg).unwrap();
+		}
+
+		let mut deps = mock_dependencies();
+		deps.querier.update_wasm(move |request| match request {
+			WasmQuery::Smart { msg, .. } => SystemResult::Ok(
+				query(registry.as_ref(), mock_env(), from_binary(msg).unwrap()).into(),
+			),
+			_ => panic!("unexpected query"),
+		});
+		let registry = AssetRegistryContract(Addr::unchecked("registry"))
+			.registry(QuerierWrapper::new(&deps.querier), MemoryRegistry::default());
+		let osmo = AssetInfo { id: 5.into(), name: "OSMO".into(), decimals: 6 };
+		assert_eq!(registry.asset_by_name("OSMO"), Some(osmo.clone()));
+		assert_eq!(registry.asset_by_id(5.into()), Some(osmo));
+		// Unknown to the contract, resolved by the fallback registry.
+		assert_eq!(registry.asset("PICA"), Ok(PICA::ID));
+		assert_eq!(registry.network("Juno"), Ok(Juno::ID));
+		assert_eq!(registry.asset_by_name("ATOM"), None);
+	}
+
+	#[test]
+	fn contract_registry_resolves_networks_through_the_gateway() {
+		let network = |id: u32| NetworkInfo { id: id.into(), name: format!("network{id}") };
+		let queries = Rc::new(Cell::new(0));
+		let mut deps = mock_dependencies();
+		deps.querier.update_wasm({
+			let queries = queries.clone();
+			move |request| {
+				queries.set(queries.get() + 1);
+				let msg = match request {
+					WasmQuery::Smart { contract_addr, msg } if contract_addr == "gateway" => msg,
+					_ => return SystemResult::Ok(ContractResult::Err("unknown".into())),
+				};
+				SystemResult::Ok(match from_binary(msg).unwrap() {
+					GatewayQueryMsg::Network { network_id } if network_id.0 <= 3 =>
+						to_binary(&NetworkResponse { network: network(network_id.0) }).into(),
+					// A page of two networks.
+					GatewayQueryMsg::Networks { start_after, .. } => {
+						let first = start_after.map_or(1, |id| id.0 + 1);
+						to_binary(&NetworksResponse {
+							networks: (first..=3).take(2).map(network).collect(),
+						})
+						.into()
+					},
+					_ => ContractResult::Err("not found".into()),
+				})
+			}
+		});
+		let registry = AssetRegistryContract(Addr::unchecked("registry"))
+			.registry(QuerierWrapper::new(&deps.querier), MemoryRegistry::default())
+			.with_gateway(Addr::unchecked("gateway"));
+
+		assert_eq!(registry.network_by_id(2.into()), Some(network(2)));
+		assert_eq!(registry.network_by_id(2.into()), Some(network(2)));
+		assert_eq!(queries.get(), 1);
+
+		// The networks are listed once, page by page.
+		assert_eq!(registry.network("network3"), Ok(3.into()));
+		assert_eq!(queries.get(), 4);
+		assert_eq!(registry.network_by_id(1.into()), Some(network(1)));
+		assert_eq!(registry.network("Juno"), Ok(Juno::ID));
+		assert_eq!(queries.get(), 4);
+	}
 }
@@ -8,4 +8,22 @@ pub enum ContractError {
 
 	#[error("Error during parsing asset id")]
 	CannotParseAssetId,
+
+	#[error("The asset must be registered first")]
+	UnknownAsset,
+
+	#[error("The asset name is already registered")]
+	AssetNameAlreadyRegistered,
+
+	#[error("Caller is not authorized to take this action.")]
+	NotAuthorized,
+
+	#[error("The asset is already registered")]
+	AssetAlreadyRegistered,
+
+	#[error("The asset metadata is already set")]
+	AssetInfoAlreadySet,
+
+	#[error("An admin must be provided")]
+	MissingAdmin,
 }
@@ -1,9 +1,15 @@
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use std::{
+	cell::{Cell, RefCell},
+	collections::BTreeMap,
+};
 
-use cosmwasm_std::{to_binary, Addr, CosmosMsg, StdResult, WasmMsg};
+use cosmwasm_std::{to_binary, Addr, CosmosMsg, QuerierWrapper, StdResult, WasmMsg};
+use cw_xcvm_common::gateway::{NetworkResponse, NetworksResponse, QueryMsg as GatewayQueryMsg};
+use xcvm_core::{AssetId, AssetInfo, NetworkId, NetworkInfo, Registry};
 
-use crate::msg::ExecuteMsg;
+use crate::msg::{AssetInfoResponse, ExecuteMsg, QueryMsg};
 
 /// AssetRegistryContract is a wrapper around Addr that provides helpers
 /// for working with this as a library.
@@ -19,4 +25,119 @@ impl AssetRegistryContract {
 		let msg = to_binary(&msg.into())?;
 		Ok(WasmMsg::Execute { contract_addr: self.addr().into(), msg, funds: vec![] }.into())
 	}
+
+	/// A [`Registry`] resolving the assets through this contract.
+	pub fn registry<'a, R: Registry>(
+		&self,
+		querier: QuerierWrapper<'a>,
+		fallback: R,
+	) -> ContractRegistry<'a, R> {
+		ContractRegistry {
+			querier,
+			contract: self.clone(),
+			gateway: None,
+			networks: RefCell::default(),
+			networks_listed: Cell::new(false),
+			fallback,
+		}
+	}
+}
+
+/// [`Registry`] backed by the asset-registry contract.
+///
+/// The assets are resolved by querying the contract and the networks by querying the gateway, if
+/// any. The networks are cached for the lifetime of the registry. The networks and the assets
+/// unknown to the contracts are resolved by the `fallback` registry.
+pub struct ContractRegistry<'a, R> {
+	querier: QuerierWrapper<'a>,
+	contract: AssetRegistryContract,
+	gateway: Option<Addr>,
+	/// Networks queried from the gateway, `None` if it does not know the network.
+	networks: RefCell<BTreeMap<NetworkId, Option<NetworkInfo>>>,
+	/// Whether all the networks of the gateway are cached.
+	networks_listed: Cell<bool>,
+	fallback: R,
+}
+
+impl<'a, R> ContractRegistry<'a, R> {
+	/// Resolve the networks through the `gateway` contract.
+	pub fn with_gateway(mut self, gateway: Addr) -> Self {
+		self.gateway = Some(gateway);
+		self
+	}
+
+	fn query_asset_info(&self, msg: &QueryMsg) -> Option<AssetInfo> {
+		self.querier
+			.query_wasm_smart::<AssetInfoResponse>(self.contract.addr(), msg)
+			.ok()
+			.map(|response| response.info)
+	}
+
+	fn query_network(&self, id: NetworkId) -> Option<NetworkInfo> {
+		let gateway = self.gateway.as_ref()?;
+		if let Some(network) = self.networks.borrow().get(&id) {
+			return network.clone()
+		}
+		let network = self
+			.querier
+			.query_wasm_smart::<NetworkResponse>(
+				gateway,
+				&GatewayQueryMsg::Network { network_id: id },
+			)
+			.ok()
+			.map(|response| response.network);
+		self.networks.borrow_mut().insert(id, network.clone());
+		network
+	}
+
+	/// The networks of the gateway are listed page by page on the first lookup by name.
+	fn query_network_by_name(&self, name: &str) -> Option<NetworkInfo> {
+		let gateway = self.gateway.as_ref()?;
+		if !self.networks_listed.get() {
+			let mut start_after = None;
+			loop {
+				let NetworksResponse { networks } = self
+					.querier
+					.query_wasm_smart(
+						gateway,
+						&GatewayQueryMsg::Networks { start_after, limit: None },
+					)
+					.ok()?;
+				start_after = match networks.last() {
+					Some(network) => Some(network.id),
+					None => break,
+				};
+				self.networks
+					.borrow_mut()
+					.extend(networks.into_iter().map(|network| (network.id, Some(network))));
+			}
+			self.networks_listed.set(true);
+		}
+		self.networks
+			.borrow()
+			.values()
+			.flatten()
+			.find(|network| network.name == name)
+			.cloned()
+	}
+}
+
+impl<'a, R: Registry> Registry for ContractRegistry<'a, R> {
+	fn network_by_id(&self, id: NetworkId) -> Option<NetworkInfo> {
+		self.query_network(id).or_else(|| self.fallback.network_by_id(id))
+	}
+
+	fn network_by_name(&self, name: &str) -> Option<NetworkInfo> {
+		self.query_network_by_name(name).or_else(|| self.fallback.network_by_name(name))
+	}
+
+	fn asset_by_id(&self, id: AssetId) -> Option<AssetInfo> {
+		self.query_asset_info(&QueryMsg::AssetInfo { asset_id: id.into() })
+			.or_else(|| self.fallback.asset_by_id(id))
+	}
+
+	fn asset_by_name(&self, name: &str) -> Option<AssetInfo> {
+		self.query_asset_info(&QueryMsg::AssetInfoByName { name: name.into() })
+			.or_else(|| self.fallback.asset_by_name(name))
+	}
 }
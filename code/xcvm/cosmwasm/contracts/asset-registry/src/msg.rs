@@ -2,7 +2,7 @@ use cosmwasm_std::Addr;
 use cw_storage_plus::{CwIntKey, Key, KeyDeserialize, PrimaryKey};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-use xcvm_core::AssetId;
+use xcvm_core::{AssetId, AssetInfo, NetworkId};
 
 #[derive(Serialize, Deserialize, Copy, Clone, Debug, PartialEq, JsonSchema)]
 #[repr(transparent)]
@@ -26,6 +26,12 @@ impl From<AssetKey> for AssetId {
 	}
 }
 
+impl From<AssetKey> for u128 {
+	fn from(AssetKey(x): AssetKey) -> Self {
+		x.into()
+	}
+}
+
 impl<'a> PrimaryKey<'a> for AssetKey {
 	type Prefix = ();
 	type SubPrefix = ();
@@ -59,25 +65,140 @@ impl AssetReference {
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
-pub struct InstantiateMsg {}
+pub struct InstantiateMsg {
+	/// Administrator of the registry, defaults to the instantiator.
+	#[serde(default)]
+	pub admin: Option<String>,
+}
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum ExecuteMsg {
-	RegisterAsset { asset_id: AssetKey, reference: AssetReference },
-	UnregisterAsset { asset_id: AssetKey },
+	/// Register or update an asset. Registrars may only register new assets.
+	RegisterAsset {
+		asset_id: AssetKey,
+		reference: AssetReference,
+		/// Network the asset has been created on, if not the local one.
+		#[serde(default)]
+		origin_network: Option<NetworkId>,
+	},
+	/// Remove an asset along with its metadata and remote counterparts, admin only.
+	UnregisterAsset {
+		asset_id: AssetKey,
+	},
+	/// Attach metadata to a registered asset. Registrars can only set the metadata of assets
+	/// having none.
+	SetAssetInfo {
+		asset_id: AssetKey,
+		name: String,
+		decimals: u8,
+	},
+	/// Map a local asset to its identifier on a remote network, `None` removing the mapping.
+	/// Admin only.
+	SetRemoteAsset {
+		asset_id: AssetKey,
+		network_id: NetworkId,
+		remote_asset_id: Option<AssetKey>,
+	},
+	/// Allow `registrar` (e.g. the gateway wrapping remote assets) to register new assets.
+	AddRegistrar {
+		registrar: String,
+	},
+	RemoveRegistrar {
+		registrar: String,
+	},
+	/// First step of the ownership transfer, `admin` accepting it with
+	/// [`ExecuteMsg::AcceptOwnership`].
+	TransferOwnership {
+		admin: String,
+	},
+	AcceptOwnership {},
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
-pub struct MigrateMsg {}
+pub struct MigrateMsg {
+	/// Replace the administrator, required when migrating from a registry without access
+	/// control.
+	#[serde(default)]
+	pub admin: Option<String>,
+}
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum QueryMsg {
-	Lookup { asset_id: AssetKey },
+	Lookup {
+		asset_id: AssetKey,
+	},
+	AssetInfo {
+		asset_id: AssetKey,
+	},
+	/// Metadata of the asset registered under this name.
+	AssetInfoByName {
+		name: String,
+	},
+	/// Paginated listing of the asset metadata, by ascending asset id.
+	AssetInfos {
+		start_after: Option<AssetKey>,
+		limit: Option<u32>,
+	},
+	/// Paginated listing of the registered assets, by ascending asset id.
+	Assets {
+		start_after: Option<AssetKey>,
+		limit: Option<u32>,
+	},
+	/// Identifier of a local asset on a remote network, if it differs.
+	RemoteAsset {
+		asset_id: AssetKey,
+		network_id: NetworkId,
+	},
+	/// Paginated listing of the remote counterparts of a local asset, by ascending network id.
+	RemoteAssets {
+		asset_id: AssetKey,
+		start_after: Option<NetworkId>,
+		limit: Option<u32>,
+	},
+	Config {},
+	Registrars {},
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct LookupResponse {
 	pub reference: AssetReference,
 }
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct AssetInfoResponse {
+	pub info: AssetInfo,
+	pub origin_network: Option<NetworkId>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct AssetInfosResponse {
+	pub infos: Vec<AssetInfo>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct AssetsResponse {
+	pub assets: Vec<(AssetId, AssetReference)>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RemoteAssetResponse {
+	pub remote_asset_id: Option<AssetId>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RemoteAssetsResponse {
+	pub remote_assets: Vec<(NetworkId, AssetId)>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ConfigResponse {
+	pub admin: Addr,
+	pub pending_admin: Option<Addr>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RegistrarsResponse {
+	pub registrars: Vec<Addr>,
+}
@@ -1,4 +1,31 @@
 use crate::msg::{AssetKey, AssetReference};
-use cw_storage_plus::Map;
+use cosmwasm_std::Addr;
+use cw_storage_plus::{Item, Map};
+use serde::{Deserialize, Serialize};
+use xcvm_core::{AssetId, AssetInfo, NetworkId};
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Config {
+	pub admin: Addr,
+	/// Admin proposed by [`crate::msg::ExecuteMsg::TransferOwnership`], not yet accepted.
+	pub pending_admin: Option<Addr>,
+}
+
+pub const CONFIG: Item<Config> = Item::new("config");
+
+/// Addresses allowed to register new assets besides the admin.
+pub const REGISTRARS: Map<Addr, ()> = Map::new("registrars");
 
 pub const ASSETS: Map<AssetKey, AssetReference> = Map::new("assets");
+
+/// Name and decimals of the registered assets, see [`xcvm_core::Registry`].
+pub const ASSET_INFOS: Map<AssetKey, AssetInfo> = Map::new("asset_infos");
+
+/// Assets indexed by their name in [`ASSET_INFOS`], the names being unique.
+pub const ASSET_IDS_BY_NAME: Map<&str, AssetId> = Map::new("asset_ids_by_name");
+
+/// Network the registered assets have been created on, unset for the local ones.
+pub const ASSET_ORIGIN_NETWORKS: Map<AssetKey, NetworkId> = Map::new("asset_origin_networks");
+
+/// Identifier of a local asset on a remote network, indexed by local asset id then network id.
+pub const REMOTE_ASSETS: Map<(u128, u32), AssetId> = Map::new("remote_assets");
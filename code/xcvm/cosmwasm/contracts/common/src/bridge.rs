@@ -0,0 +1,20 @@
+use cosmwasm_std::Binary;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use xcvm_core::NetworkId;
+
+/// Messages the gateway sends to the OTP bridges, see [`crate::gateway::Bridge`].
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ExecuteMsg {
+	SendPacket {
+		network_id: NetworkId,
+		/// The protobuf encoded XCVM packet.
+		packet: Binary,
+		/// Unix timestamp, in seconds, after which the packet must no longer be delivered.
+		deadline: Option<u64>,
+		/// Sequence of the packet on this bridge, assigned by the gateway. The outcome of the
+		/// packet is reported for this sequence with [`crate::gateway::ExecuteMsg::OtpAck`].
+		sequence: u64,
+	},
+}
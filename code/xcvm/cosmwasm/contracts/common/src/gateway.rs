@@ -1,13 +1,206 @@
-use crate::shared::BridgeMsg;
-use cosmwasm_std::{Addr, CosmosMsg};
+use crate::{
+	router::FeeSchedule,
+	shared::{BridgeMsg, BridgeQueryMsg},
+};
+use cosmwasm_std::{Addr, Binary, CosmosMsg};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-use xcvm_core::NetworkId;
+use xcvm_core::{AssetId, BridgeId, BridgeSecurity, Displayed, Funds, NetworkId, NetworkInfo};
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum ExecuteMsg {
-	IbcSetNetworkChannel { network_id: NetworkId, channel_id: String },
-	Bridge { interpreter: Addr, msg: BridgeMsg },
-	Batch { msgs: Vec<CosmosMsg> },
+	IbcSetNetworkChannel {
+		network_id: NetworkId,
+		channel_id: String,
+	},
+	Bridge {
+		interpreter: Addr,
+		msg: BridgeMsg,
+		#[serde(default)]
+		fees: Option<RelayerFees>,
+	},
+	BridgeQuery {
+		interpreter: Addr,
+		msg: BridgeQueryMsg,
+	},
+	Batch {
+		msgs: Vec<CosmosMsg>,
+	},
+	RegisterNetwork {
+		network: NetworkInfo,
+	},
+	UnregisterNetwork {
+		network_id: NetworkId,
+	},
+	SetNetworkTimeout {
+		network_id: NetworkId,
+		timeout: Option<NetworkTimeout>,
+	},
+	RegisterBridge {
+		bridge_id: BridgeId,
+		bridge: Bridge,
+	},
+	UnregisterBridge {
+		bridge_id: BridgeId,
+	},
+	OtpReceive {
+		relayer: Addr,
+		packet: Binary,
+	},
+	OtpAck {
+		/// The relayer that delivered the packet, paid its fees on success.
+		relayer: Addr,
+		sequence: u64,
+		ack: Binary,
+	},
+	CreateWrappedAsset {
+		asset_id: AssetId,
+		recipient: Addr,
+		amount: Displayed<u128>,
+	},
+	RecordInflow {
+		path: RateLimitPath,
+		assets: Funds<Displayed<u128>>,
+	},
+	SetRateLimit {
+		path: RateLimitPath,
+		asset_id: AssetId,
+		limit: Option<RateLimit>,
+	},
+	SetRouterFeeSchedule {
+		fee_schedule: FeeSchedule,
+	},
+}
+
+/// Bridge following the OTP specs, registered by the admin through
+/// [`ExecuteMsg::RegisterBridge`].
+///
+/// Spawns requiring at most `security` are sent to the `address` contract with a
+/// [`crate::bridge::ExecuteMsg::SendPacket`]. The bridge delivers the inbound packets with
+/// [`ExecuteMsg::OtpReceive`] and reports the outcome of the outbound ones with
+/// [`ExecuteMsg::OtpAck`].
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Bridge {
+	pub security: BridgeSecurity,
+	pub address: Addr,
+	/// The network this bridge is connected to.
+	pub network_id: NetworkId,
+}
+
+/// Fees of a spawn, transferred to the gateway along with its assets by the router.
+///
+/// The fees are escrowed until the packet is acknowledged: paid on success to the relayer that
+/// delivered it, as reported by the IBC acknowledgement or [`ExecuteMsg::OtpAck`], refunded to the
+/// interpreter along with the assets otherwise.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RelayerFees {
+	pub fees: Funds<Displayed<u128>>,
+}
+
+/// Timeout of the IBC packets sent to a network, set with [`ExecuteMsg::SetNetworkTimeout`]
+/// (`None` restoring the [`DEFAULT_NETWORK_TIMEOUT`]).
+#[derive(Serialize, Deserialize, Copy, Clone, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum NetworkTimeout {
+	/// Number of seconds after the block time at which the packet has been sent.
+	Seconds(u64),
+	/// Number of blocks after the latest height of the counterparty network, as carried by the
+	/// last packet received from it. The default timeout applies until a packet is received, and
+	/// once the last one is too old.
+	Blocks { revision: u64, blocks: u64 },
+}
+
+/// Number of seconds of [`DEFAULT_NETWORK_TIMEOUT`].
+pub const DEFAULT_NETWORK_TIMEOUT_SECONDS: u64 = 600;
+
+/// Timeout of the networks without a configured one.
+pub const DEFAULT_NETWORK_TIMEOUT: NetworkTimeout =
+	NetworkTimeout::Seconds(DEFAULT_NETWORK_TIMEOUT_SECONDS);
+
+/// Route through which assets enter or leave the gateway.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum RateLimitPath {
+	Ibc { channel_id: String },
+	Bridge { bridge_id: BridgeId },
+}
+
+/// Maximum amounts of an asset transferred through a [`RateLimitPath`] per window, set with
+/// [`ExecuteMsg::SetRateLimit`] (`None` lifting the limit).
+///
+/// Bounds the assets at risk if a counterparty network or bridge is compromised. Outbound programs
+/// exceeding the limit are rejected while inbound packets are acknowledged as failed.
+#[derive(Serialize, Deserialize, Copy, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct RateLimit {
+	/// Duration of the window in seconds. A window starts with the first transfer following the
+	/// end of the previous one.
+	pub window: u64,
+	/// Maximum amount received from the counterparty per window.
+	pub max_inflow: Displayed<u128>,
+	/// Maximum amount sent to the counterparty per window.
+	pub max_outflow: Displayed<u128>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum QueryMsg {
+	/// Configuration of the gateway and address of its router.
+	Config {},
+	/// Lookup a network registered on this gateway.
+	Network { network_id: NetworkId },
+	/// List the networks registered on this gateway, ordered by identifier.
+	Networks { start_after: Option<NetworkId>, limit: Option<u32> },
+	/// Timeout of the packets sent to a network.
+	NetworkTimeout { network_id: NetworkId },
+	/// Lookup an OTP bridge registered on this gateway.
+	Bridge { bridge_id: BridgeId },
+	/// List the OTP bridges registered on this gateway, ordered by identifier.
+	Bridges { start_after: Option<BridgeId>, limit: Option<u32> },
+	/// IBC channel opened to a network.
+	NetworkChannel { network_id: NetworkId },
+	/// List the IBC channels assigned to a network, ordered by network.
+	NetworkChannels { start_after: Option<NetworkId>, limit: Option<u32> },
+	/// Information of an opened IBC channel and the network it is assigned to, if any.
+	ChannelInfo { channel_id: String },
+	/// Rate limit of an asset through a path, along with the amounts transferred in the current
+	/// window.
+	RateLimit { path: RateLimitPath, asset_id: AssetId },
+	/// List the rate limits configured on this gateway, ordered by path and asset.
+	RateLimits { start_after: Option<(RateLimitPath, AssetId)>, limit: Option<u32> },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct NetworkResponse {
+	pub network: NetworkInfo,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct NetworksResponse {
+	pub networks: Vec<NetworkInfo>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct NetworkTimeoutResponse {
+	pub timeout: NetworkTimeout,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct BridgeResponse {
+	pub bridge: Bridge,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct BridgesResponse {
+	pub bridges: Vec<(BridgeId, Bridge)>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct NetworkChannelResponse {
+	pub channel_id: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct NetworkChannelsResponse {
+	pub channels: Vec<(NetworkId, String)>,
 }
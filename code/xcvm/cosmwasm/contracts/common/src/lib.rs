@@ -1,3 +1,4 @@
+pub mod bridge;
 pub mod gateway;
 pub mod router;
 pub mod shared;
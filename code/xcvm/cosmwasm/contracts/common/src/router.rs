@@ -1,8 +1,8 @@
-use crate::shared::BridgeMsg;
+use crate::shared::{BridgeMsg, BridgeQueryMsg};
 use cw_xcvm_utils::DefaultXCVMProgram;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-use xcvm_core::{BridgeSecurity, CallOrigin, Displayed, Funds, InterpreterOrigin};
+use xcvm_core::{BridgeSecurity, CallOrigin, Displayed, Funds, InterpreterOrigin, NetworkId};
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
@@ -41,4 +41,37 @@ pub enum ExecuteMsg {
 		/// The message we want to forward to the bridge gateway.
 		msg: BridgeMsg,
 	},
+	BridgeForwardQuery {
+		/// The query we want to forward to the bridge gateway.
+		msg: BridgeQueryMsg,
+	},
+	/// Replace the fee schedule of the router.
+	/// This is only meant to be used by the gateway, on behalf of its admin.
+	SetFeeSchedule {
+		/// The new fee schedule.
+		fee_schedule: FeeSchedule,
+	},
+}
+
+/// Fees charged by the router on the programs spawned from this network.
+///
+/// The fee of the destination network is deducted from the spawned assets, then paid along with
+/// the tip of the spawn to the relayer that delivers it. Both are escrowed by the gateway until the
+/// spawn is delivered, and refunded to the interpreter if it fails, see
+/// [`crate::gateway::RelayerFees`].
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, JsonSchema)]
+pub struct FeeSchedule {
+	/// Fee per destination network, the networks without one are free.
+	pub network_fees: Vec<(NetworkId, Funds<Displayed<u128>>)>,
+}
+
+impl FeeSchedule {
+	/// Fee of a program spawned to `network_id`.
+	pub fn network_fee(&self, network_id: NetworkId) -> Funds<Displayed<u128>> {
+		self.network_fees
+			.iter()
+			.find(|(id, _)| *id == network_id)
+			.map(|(_, fee)| fee.clone())
+			.unwrap_or_default()
+	}
 }
@@ -12,6 +12,22 @@ pub struct BridgeMsg {
 	pub salt: Vec<u8>,
 	pub program: DefaultXCVMProgram,
 	pub assets: Funds<Displayed<u128>>,
+	/// Unix timestamp, in seconds, after which the program must no longer be delivered.
+	#[serde(default)]
+	pub deadline: Option<u64>,
+	/// Paid to the relayer in addition to the fee of the router.
+	#[serde(default)]
+	pub tip: Funds<Displayed<u128>>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct BridgeQueryMsg {
+	pub interpreter_origin: InterpreterOrigin,
+	pub network_id: NetworkId,
+	/// The salt of the remote interpreter to query.
+	pub salt: Vec<u8>,
+	/// Identifier of the query, delivered back with its result.
+	pub query_id: u64,
 }
 
 pub fn encode_base64<T: Serialize>(x: &T) -> StdResult<String> {
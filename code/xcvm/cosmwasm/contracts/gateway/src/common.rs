@@ -1,8 +1,10 @@
 use crate::{
 	error::ContractError,
-	state::{Config, CONFIG, ROUTER},
+	state::{Config, BRIDGES, CONFIG, ROUTER},
 };
-use cosmwasm_std::Deps;
+use cosmwasm_std::{Deps, Order};
+use cw_xcvm_common::gateway::Bridge;
+use xcvm_core::BridgeId;
 
 pub fn ensure_admin(deps: Deps, sender: &str) -> Result<(), ContractError> {
 	let Config { admin, .. } = CONFIG.load(deps.storage)?;
@@ -13,6 +15,16 @@ pub fn ensure_admin(deps: Deps, sender: &str) -> Result<(), ContractError> {
 	}
 }
 
+/// Ensure that the `sender` is a registered OTP bridge, returning it.
+pub fn ensure_bridge(deps: Deps, sender: &str) -> Result<(BridgeId, Bridge), ContractError> {
+	BRIDGES
+		.range(deps.storage, None, None, Order::Ascending)
+		.find(|entry| matches!(entry, Ok((_, bridge)) if bridge.address.as_ref() == sender))
+		.transpose()?
+		.map(|(bridge_id, bridge)| (bridge_id.into(), bridge))
+		.ok_or(ContractError::NotAuthorized)
+}
+
 pub fn ensure_router(deps: Deps, sender: &str) -> Result<(), ContractError> {
 	let router = ROUTER.load(deps.storage)?;
 	if router.as_ref() == sender {
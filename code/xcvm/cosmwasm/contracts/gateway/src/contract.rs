@@ -1,34 +1,57 @@
 extern crate alloc;
 
 use crate::{
-	common::{ensure_admin, ensure_router},
+	common::{ensure_admin, ensure_bridge, ensure_router},
 	error::ContractError,
-	msg::{InstantiateMsg, MigrateMsg, QueryMsg},
+	msg::{
+		BridgeResponse, BridgesResponse, ChannelInfoResponse, ConfigResponse, Cw20InstantiateMsg,
+		InstantiateMsg, MigrateMsg, NetworkChannelResponse, NetworkChannelsResponse,
+		NetworkResponse, NetworkTimeoutResponse, NetworksResponse, QueryMsg, RateLimitResponse,
+		RateLimitsResponse,
+	},
+	rate_limit::{path_key, record_flow, release_flow, set_rate_limit, Flow},
 	state::{
-		ChannelInfo, Config, CONFIG, IBC_CHANNEL_INFO, IBC_CHANNEL_NETWORK, IBC_NETWORK_CHANNEL,
-		ROUTER,
+		ChannelId, ChannelInfo, Config, InFlightPacket, NetworkHeight, BRIDGES, CONFIG,
+		DEFAULT_WRAPPED_ASSET_PREFIX, IBC_CHANNEL_INFO, IBC_CHANNEL_NETWORK, IBC_IN_FLIGHT_PACKETS,
+		IBC_NETWORK_CHANNEL, IBC_PENDING_PACKET, NETWORKS, NETWORK_HEIGHTS, NETWORK_TIMEOUTS,
+		OTP_IN_FLIGHT_PACKETS, OTP_NEXT_SEQUENCE, PENDING_WRAPPED_ASSET, RATE_LIMITS, ROUTER,
 	},
 };
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{
-	wasm_execute, wasm_instantiate, Addr, Binary, CosmosMsg, Deps, DepsMut, Env, Event,
-	Ibc3ChannelOpenResponse, IbcBasicResponse, IbcChannelCloseMsg, IbcChannelConnectMsg,
-	IbcChannelOpenMsg, IbcChannelOpenResponse, IbcMsg, IbcOrder, IbcPacketAckMsg,
-	IbcPacketReceiveMsg, IbcPacketTimeoutMsg, IbcReceiveResponse, IbcTimeout, IbcTimeoutBlock,
-	MessageInfo, Reply, Response, StdError, SubMsg, SubMsgResult,
+	to_binary, wasm_execute, wasm_instantiate, Addr, BankMsg, Binary, Coin, CosmosMsg, Deps,
+	DepsMut, Env, Event, Ibc3ChannelOpenResponse, IbcBasicResponse, IbcChannelCloseMsg,
+	IbcChannelConnectMsg, IbcChannelOpenMsg, IbcChannelOpenResponse, IbcMsg, IbcOrder,
+	IbcPacketAckMsg, IbcPacketReceiveMsg, IbcPacketTimeoutMsg, IbcReceiveResponse, IbcTimeout,
+	IbcTimeoutBlock, MessageInfo, Order, Reply, Response, StdError, StdResult, Storage, SubMsg,
+	SubMsgResponse, SubMsgResult, Timestamp,
 };
 use cw2::set_contract_version;
-use cw20::Cw20ExecuteMsg;
+use cw20::{BalanceResponse, Cw20Coin, Cw20ExecuteMsg, Cw20QueryMsg, MinterResponse};
+use cw_storage_plus::Bound;
 use cw_utils::ensure_from_older_version;
-use cw_xcvm_asset_registry::{contract::external_query_lookup_asset, msg::AssetReference};
-use cw_xcvm_common::{gateway::ExecuteMsg, shared::BridgeMsg};
+use cw_xcvm_asset_registry::{
+	contract::{external_query_assets, external_query_lookup_asset, external_query_remote_asset},
+	helpers::AssetRegistryContract,
+	msg::{AssetReference, RegistrarsResponse},
+	state::ASSETS,
+};
+use cw_xcvm_common::{
+	gateway::{
+		Bridge, ExecuteMsg, NetworkTimeout, RateLimitPath, RelayerFees, DEFAULT_NETWORK_TIMEOUT,
+		DEFAULT_NETWORK_TIMEOUT_SECONDS,
+	},
+	shared::{BridgeMsg, BridgeQueryMsg},
+};
+use cw_xcvm_router::state::INTERPRETERS;
 use cw_xcvm_utils::{DefaultXCVMPacket, DefaultXCVMProgram};
 use xcvm_core::{
-	BridgeProtocol, BridgeSecurity, CallOrigin, Displayed, Funds, InterpreterOrigin, NetworkId,
-	XCVMAck,
+	AssetId, AssetInfo, BridgeId, BridgeProtocol, BridgeSecurity, CallOrigin, Displayed, Funds,
+	InterpreterOrigin, MemoryRegistry, NetworkId, NetworkInfo, QueryPacket, QueryResult, Register,
+	Registry, XCVMAck,
 };
-use xcvm_proto::{decode_packet, Encodable};
+use xcvm_proto::{decode_packet, decode_query_packet, decode_query_result, Encodable};
 
 pub const CONTRACT_NAME: &str = "composable:xcvm-gateway";
 pub const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
@@ -39,6 +62,18 @@ pub const XCVM_GATEWAY_IBC_ORDERING: IbcOrder = IbcOrder::Unordered;
 
 pub const XCVM_GATEWAY_INSTANTIATE_ROUTER_REPLY_ID: u64 = 0;
 pub const XCVM_GATEWAY_BATCH_REPLY_ID: u64 = 1;
+pub const XCVM_GATEWAY_SEND_PACKET_REPLY_ID: u64 = 2;
+pub const XCVM_GATEWAY_INSTANTIATE_WRAPPED_ASSET_REPLY_ID: u64 = 3;
+pub const XCVM_GATEWAY_QUERY_RESULT_REPLY_ID: u64 = 4;
+
+const DEFAULT_QUERY_LIMIT: u32 = 10;
+const MAX_QUERY_LIMIT: u32 = 30;
+
+/// Number of seconds during which the height received from a network is used for the timeouts.
+const NETWORK_HEIGHT_VALIDITY_SECONDS: u64 = 3600;
+/// Upper bound of the block rate of a network, a received height further than the network could
+/// have produced since the last one being ignored.
+const MAX_BLOCKS_PER_SECOND: u64 = 1;
 
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
@@ -71,7 +106,7 @@ pub fn instantiate(
 
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn execute(
-	deps: DepsMut,
+	mut deps: DepsMut,
 	env: Env,
 	info: MessageInfo,
 	msg: ExecuteMsg,
@@ -82,7 +117,11 @@ pub fn execute(
 			let _ = IBC_CHANNEL_INFO
 				.load(deps.storage, channel_id.clone())
 				.map_err(|_| ContractError::UnknownChannel)?;
+			if let Some(previous) = IBC_NETWORK_CHANNEL.may_load(deps.storage, network_id)? {
+				IBC_CHANNEL_NETWORK.remove(deps.storage, previous);
+			}
 			IBC_NETWORK_CHANNEL.save(deps.storage, network_id, &channel_id)?;
+			IBC_CHANNEL_NETWORK.save(deps.storage, channel_id.clone(), &network_id)?;
 			Ok(Response::default().add_event(
 				Event::new(XCVM_GATEWAY_EVENT_PREFIX)
 					.add_attribute("action", "set_network_channel")
@@ -93,9 +132,21 @@ pub fn execute(
 
 		ExecuteMsg::Bridge {
 			interpreter,
-			msg: BridgeMsg { interpreter_origin, network_id, security, salt, program, assets },
+			msg:
+				BridgeMsg {
+					interpreter_origin,
+					network_id,
+					security,
+					salt,
+					program,
+					assets,
+					deadline,
+					..
+				},
+			fees,
 		} => handle_bridge(
 			deps,
+			env,
 			info,
 			interpreter,
 			interpreter_origin,
@@ -104,26 +155,278 @@ pub fn execute(
 			salt,
 			program,
 			assets,
+			deadline,
+			fees,
 		),
 
+		ExecuteMsg::BridgeQuery { interpreter, msg } =>
+			handle_bridge_query(deps, env, info, interpreter, msg),
+
 		ExecuteMsg::Batch { msgs } =>
 			if info.sender != env.contract.address {
 				Err(ContractError::NotAuthorized)
 			} else {
 				Ok(Response::default().add_messages(msgs))
 			},
+
+		ExecuteMsg::CreateWrappedAsset { asset_id, recipient, amount } =>
+			if info.sender != env.contract.address {
+				Err(ContractError::NotAuthorized)
+			} else {
+				handle_create_wrapped_asset(deps, env, asset_id, recipient, amount)
+			},
+
+		ExecuteMsg::RecordInflow { path, assets } =>
+			if info.sender != env.contract.address {
+				Err(ContractError::NotAuthorized)
+			} else {
+				record_flow(deps.storage, env.block.time.seconds(), &path, Flow::Inbound, &assets)?;
+				Ok(Response::default())
+			},
+
+		ExecuteMsg::RegisterNetwork { network } => {
+			ensure_admin(deps.as_ref(), info.sender.as_ref())?;
+			handle_register_network(deps, network)
+		},
+
+		ExecuteMsg::UnregisterNetwork { network_id } => {
+			ensure_admin(deps.as_ref(), info.sender.as_ref())?;
+			NETWORKS.remove(deps.storage, network_id);
+			Ok(Response::default().add_event(
+				Event::new(XCVM_GATEWAY_EVENT_PREFIX)
+					.add_attribute("action", "unregister_network")
+					.add_attribute("network_id", format!("{network_id}")),
+			))
+		},
+
+		ExecuteMsg::SetNetworkTimeout { network_id, timeout } => {
+			ensure_admin(deps.as_ref(), info.sender.as_ref())?;
+			match timeout {
+				Some(timeout) => NETWORK_TIMEOUTS.save(deps.storage, network_id, &timeout)?,
+				None => NETWORK_TIMEOUTS.remove(deps.storage, network_id),
+			}
+			Ok(Response::default().add_event(
+				Event::new(XCVM_GATEWAY_EVENT_PREFIX)
+					.add_attribute("action", "set_network_timeout")
+					.add_attribute("network_id", format!("{network_id}")),
+			))
+		},
+
+		ExecuteMsg::SetRateLimit { path, asset_id, limit } => {
+			ensure_admin(deps.as_ref(), info.sender.as_ref())?;
+			let key = path_key(&path);
+			set_rate_limit(deps.storage, path, asset_id, limit)?;
+			Ok(Response::default().add_event(
+				Event::new(XCVM_GATEWAY_EVENT_PREFIX)
+					.add_attribute("action", "set_rate_limit")
+					.add_attribute("path", key)
+					.add_attribute("asset_id", format!("{}", u128::from(asset_id))),
+			))
+		},
+
+		ExecuteMsg::SetRouterFeeSchedule { fee_schedule } => {
+			ensure_admin(deps.as_ref(), info.sender.as_ref())?;
+			let router_address = ROUTER.load(deps.storage)?;
+			Ok(Response::default()
+				.add_message(wasm_execute(
+					router_address,
+					&cw_xcvm_common::router::ExecuteMsg::SetFeeSchedule { fee_schedule },
+					Default::default(),
+				)?)
+				.add_event(
+					Event::new(XCVM_GATEWAY_EVENT_PREFIX)
+						.add_attribute("action", "set_router_fee_schedule"),
+				))
+		},
+
+		ExecuteMsg::RegisterBridge { bridge_id, bridge } => {
+			ensure_admin(deps.as_ref(), info.sender.as_ref())?;
+			handle_register_bridge(deps, bridge_id, bridge)
+		},
+
+		ExecuteMsg::UnregisterBridge { bridge_id } => {
+			ensure_admin(deps.as_ref(), info.sender.as_ref())?;
+			BRIDGES.remove(deps.storage, bridge_id.clone());
+			Ok(Response::default().add_event(
+				Event::new(XCVM_GATEWAY_EVENT_PREFIX)
+					.add_attribute("action", "unregister_bridge")
+					.add_attribute("bridge_id", format!("{}", Binary::from(bridge_id.as_ref()))),
+			))
+		},
+
+		ExecuteMsg::OtpReceive { relayer, packet } =>
+			handle_otp_receive(deps, env, info, relayer, packet),
+
+		ExecuteMsg::OtpAck { relayer, sequence, ack } => {
+			let (bridge_id, _) = ensure_bridge(deps.as_ref(), info.sender.as_ref())?;
+			let ack = XCVMAck::try_from(ack.as_slice()).map_err(|_| ContractError::InvalidAck)?;
+			// A packet is settled only once. Packets sent before the gateway recorded them have no
+			// record and can't be settled, see the README.
+			let packet = OTP_IN_FLIGHT_PACKETS
+				.may_load(deps.storage, (bridge_id.clone(), sequence))?
+				.ok_or(ContractError::UnknownPacket)?;
+			OTP_IN_FLIGHT_PACKETS.remove(deps.storage, (bridge_id.clone(), sequence));
+			let path = RateLimitPath::Bridge { bridge_id: bridge_id.clone() };
+			let (messages, event) = settle_packet(&mut deps, &path, packet, ack, relayer)?;
+			Ok(Response::default().add_messages(messages).add_event(
+				event
+					.add_attribute("bridge_id", format!("{}", Binary::from(bridge_id.as_ref())))
+					.add_attribute("sequence", format!("{sequence}")),
+			))
+		},
 	}
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
 	let _ = ensure_from_older_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+	// Absolute height timeouts are no longer supported, the default timeout applies instead.
+	let networks = NETWORK_TIMEOUTS
+		.keys(deps.storage, None, None, Order::Ascending)
+		.collect::<StdResult<Vec<_>>>()?;
+	for network_id in networks {
+		if NETWORK_TIMEOUTS.load(deps.storage, network_id.into()).is_err() {
+			NETWORK_TIMEOUTS.remove(deps.storage, network_id.into());
+		}
+	}
 	Ok(Response::default())
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn query(_deps: Deps, _env: Env, _msg: QueryMsg) -> Result<Binary, ContractError> {
-	Err(StdError::generic_err("not implemented").into())
+pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> Result<Binary, ContractError> {
+	match msg {
+		QueryMsg::Config {} => Ok(to_binary(&ConfigResponse {
+			config: CONFIG.load(deps.storage)?,
+			router: ROUTER.may_load(deps.storage)?,
+		})?),
+		QueryMsg::Network { network_id } =>
+			Ok(to_binary(&NetworkResponse { network: NETWORKS.load(deps.storage, network_id)? })?),
+		QueryMsg::Networks { start_after, limit } => Ok(to_binary(&NetworksResponse {
+			networks: NETWORKS
+				.range(deps.storage, start_after.map(Bound::exclusive), None, Order::Ascending)
+				.take(query_limit(limit))
+				.map(|entry| entry.map(|(_, network)| network))
+				.collect::<StdResult<Vec<_>>>()?,
+		})?),
+		QueryMsg::NetworkTimeout { network_id } => Ok(to_binary(&NetworkTimeoutResponse {
+			timeout: NETWORK_TIMEOUTS
+				.may_load(deps.storage, network_id)?
+				.unwrap_or(DEFAULT_NETWORK_TIMEOUT),
+		})?),
+		QueryMsg::Bridge { bridge_id } =>
+			Ok(to_binary(&BridgeResponse { bridge: BRIDGES.load(deps.storage, bridge_id)? })?),
+		QueryMsg::Bridges { start_after, limit } => Ok(to_binary(&BridgesResponse {
+			bridges: BRIDGES
+				.range(deps.storage, start_after.map(Bound::exclusive), None, Order::Ascending)
+				.take(query_limit(limit))
+				.map(|entry| entry.map(|(bridge_id, bridge)| (bridge_id.into(), bridge)))
+				.collect::<StdResult<Vec<_>>>()?,
+		})?),
+		QueryMsg::NetworkChannel { network_id } => Ok(to_binary(&NetworkChannelResponse {
+			channel_id: IBC_NETWORK_CHANNEL.load(deps.storage, network_id)?,
+		})?),
+		QueryMsg::NetworkChannels { start_after, limit } =>
+			Ok(to_binary(&NetworkChannelsResponse {
+				channels: IBC_NETWORK_CHANNEL
+					.range(deps.storage, start_after.map(Bound::exclusive), None, Order::Ascending)
+					.take(query_limit(limit))
+					.map(|entry| {
+						entry.map(|(network_id, channel_id)| (network_id.into(), channel_id))
+					})
+					.collect::<StdResult<Vec<_>>>()?,
+			})?),
+		QueryMsg::ChannelInfo { channel_id } => Ok(to_binary(&ChannelInfoResponse {
+			info: IBC_CHANNEL_INFO.load(deps.storage, channel_id.clone())?,
+			network_id: IBC_CHANNEL_NETWORK.may_load(deps.storage, channel_id)?,
+		})?),
+		QueryMsg::RateLimit { path, asset_id } => Ok(to_binary(&RateLimitResponse {
+			rate_limit: RATE_LIMITS.load(deps.storage, (path_key(&path), asset_id.into()))?,
+		})?),
+		QueryMsg::RateLimits { start_after, limit } => Ok(to_binary(&RateLimitsResponse {
+			rate_limits: RATE_LIMITS
+				.range(
+					deps.storage,
+					start_after.map(|(path, asset_id)| {
+						Bound::exclusive((path_key(&path), u128::from(asset_id)))
+					}),
+					None,
+					Order::Ascending,
+				)
+				.take(query_limit(limit))
+				.map(|entry| entry.map(|(_, rate_limit)| rate_limit))
+				.collect::<StdResult<Vec<_>>>()?,
+		})?),
+	}
+}
+
+/// Number of entries of a page, at most [`MAX_QUERY_LIMIT`].
+fn query_limit(limit: Option<u32>) -> usize {
+	limit.unwrap_or(DEFAULT_QUERY_LIMIT).min(MAX_QUERY_LIMIT) as usize
+}
+
+/// Register a network, making it addressable by its name in the [`xcvm_core::Registry`] built
+/// from this gateway. Both the identifier and the name must be unique.
+fn handle_register_network(deps: DepsMut, network: NetworkInfo) -> Result<Response, ContractError> {
+	let already_registered = NETWORKS.has(deps.storage, network.id) ||
+		NETWORKS.range(deps.storage, None, None, Order::Ascending).any(
+			|entry| matches!(entry, Ok((_, registered)) if registered.name == network.name),
+		);
+	if already_registered {
+		return Err(ContractError::NetworkAlreadyRegistered)
+	}
+	NETWORKS.save(deps.storage, network.id, &network)?;
+	Ok(Response::default().add_event(
+		Event::new(XCVM_GATEWAY_EVENT_PREFIX)
+			.add_attribute("action", "register_network")
+			.add_attribute("network_id", format!("{}", network.id))
+			.add_attribute("name", network.name),
+	))
+}
+
+/// Register an OTP bridge, see [`Bridge`]. Both the identifier and the address must be unique as
+/// the bridge is authenticated by its address.
+fn handle_register_bridge(
+	deps: DepsMut,
+	bridge_id: BridgeId,
+	bridge: Bridge,
+) -> Result<Response, ContractError> {
+	deps.api.addr_validate(bridge.address.as_str())?;
+	if BRIDGES.has(deps.storage, bridge_id.clone()) ||
+		ensure_bridge(deps.as_ref(), bridge.address.as_str()).is_ok()
+	{
+		return Err(ContractError::BridgeAlreadyRegistered)
+	}
+	BRIDGES.save(deps.storage, bridge_id.clone(), &bridge)?;
+	Ok(Response::default().add_event(
+		Event::new(XCVM_GATEWAY_EVENT_PREFIX)
+			.add_attribute("action", "register_bridge")
+			.add_attribute("bridge_id", format!("{}", Binary::from(bridge_id.as_ref())))
+			.add_attribute("network_id", format!("{}", bridge.network_id))
+			.add_attribute("security", format!("{}", bridge.security as u8))
+			.add_attribute("address", bridge.address),
+	))
+}
+
+/// Handle a packet delivered by an OTP bridge. The program is executed with the security of the
+/// bridge, which is then enforced by the router against the security expected by the user.
+fn handle_otp_receive(
+	deps: DepsMut,
+	env: Env,
+	info: MessageInfo,
+	relayer: Addr,
+	packet: Binary,
+) -> Result<Response, ContractError> {
+	let (bridge_id, Bridge { security, network_id, .. }) =
+		ensure_bridge(deps.as_ref(), info.sender.as_ref())?;
+	let event = Event::new(XCVM_GATEWAY_EVENT_PREFIX)
+		.add_attribute("action", "otp_receive")
+		.add_attribute("bridge_id", format!("{}", Binary::from(bridge_id.as_ref())));
+	let path = RateLimitPath::Bridge { bridge_id: bridge_id.clone() };
+	let protocol = BridgeProtocol::OTP { id: bridge_id, security };
+	match execute_packet(deps, &env, path, protocol, Some(network_id), relayer, packet.as_slice()) {
+		Ok(batch) => Ok(Response::default().add_event(event).add_submessage(batch)),
+		Err(_) => Ok(Response::default().add_event(event).set_data(XCVMAck::KO.into_vec())),
+	}
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
@@ -131,6 +434,10 @@ pub fn reply(deps: DepsMut, _env: Env, msg: Reply) -> Result<Response, ContractE
 	match msg.id {
 		XCVM_GATEWAY_INSTANTIATE_ROUTER_REPLY_ID => handle_instantiate_reply(deps, msg),
 		XCVM_GATEWAY_BATCH_REPLY_ID => handle_batch_reply(msg),
+		XCVM_GATEWAY_SEND_PACKET_REPLY_ID => handle_send_packet_reply(deps, msg),
+		XCVM_GATEWAY_INSTANTIATE_WRAPPED_ASSET_REPLY_ID =>
+			handle_instantiate_wrapped_asset_reply(deps, msg),
+		XCVM_GATEWAY_QUERY_RESULT_REPLY_ID => Ok(handle_query_result_reply(msg)),
 		_ => Err(ContractError::UnknownReply),
 	}
 }
@@ -179,11 +486,12 @@ pub fn ibc_channel_connect(
 
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn ibc_channel_close(
-	deps: DepsMut,
+	mut deps: DepsMut,
 	_env: Env,
 	msg: IbcChannelCloseMsg,
 ) -> Result<IbcBasicResponse, ContractError> {
 	let channel = msg.channel();
+	let channel_id = channel.endpoint.channel_id.clone();
 	match IBC_CHANNEL_NETWORK.load(deps.storage, channel.endpoint.channel_id.clone()) {
 		Ok(channel_network) => {
 			IBC_CHANNEL_NETWORK.remove(deps.storage, channel.endpoint.channel_id.clone());
@@ -193,13 +501,27 @@ pub fn ibc_channel_close(
 		Err(_) => {},
 	}
 	IBC_CHANNEL_INFO.remove(deps.storage, channel.endpoint.channel_id.clone());
-	// TODO: are all the in flight packets timed out in this case? if not, we need to unescrow
-	// assets
-	Ok(IbcBasicResponse::new().add_event(
+	// The in flight packets will never be acknowledged, refund them right away. A later timeout
+	// of these packets is a no-op as their records are removed.
+	let in_flight = IBC_IN_FLIGHT_PACKETS
+		.prefix(channel_id.clone())
+		.range(deps.storage, None, None, Order::Ascending)
+		.collect::<StdResult<Vec<_>>>()?;
+	let mut response = IbcBasicResponse::new().add_event(
 		Event::new(XCVM_GATEWAY_EVENT_PREFIX)
 			.add_attribute("action", "ibc_close")
-			.add_attribute("channel_id", channel.endpoint.channel_id.clone()),
-	))
+			.add_attribute("channel_id", channel_id.clone()),
+	);
+	let path = RateLimitPath::Ibc { channel_id: channel_id.clone() };
+	for (sequence, packet) in in_flight {
+		IBC_IN_FLIGHT_PACKETS.remove(deps.storage, (channel_id.clone(), sequence));
+		let (messages, event) = refund_packet(&mut deps, &path, packet, "channel_closed")?;
+		response =
+			response
+				.add_messages(messages)
+				.add_event(packet_event(event, &channel_id, sequence));
+	}
+	Ok(response)
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
@@ -208,40 +530,31 @@ pub fn ibc_packet_receive(
 	env: Env,
 	msg: IbcPacketReceiveMsg,
 ) -> Result<IbcReceiveResponse, ContractError> {
-	let batch = (|| -> Result<_, ContractError> {
-		let Config { registry_address, .. } = CONFIG.load(deps.storage)?;
-		let router_address = ROUTER.load(deps.storage)?;
-		let packet: DefaultXCVMPacket =
-			decode_packet(&msg.packet.data).map_err(ContractError::Protobuf)?;
-		// Execute both mints + execution in a single sub-transaction.
-		let mut msgs = mint_counterparty_assets(
-			&deps,
-			router_address.as_ref(),
-			registry_address.as_ref(),
-			packet.assets.clone(),
-		)?;
-		msgs.push(
-			wasm_execute(
-				router_address,
-				&cw_xcvm_common::router::ExecuteMsg::ExecuteProgramPrivileged {
-					call_origin: CallOrigin::Remote {
-						protocol: BridgeProtocol::IBC,
-						relayer: msg.relayer,
-						user_origin: packet.user_origin,
-					},
-					salt: packet.salt,
-					program: packet.program,
-					assets: packet.assets,
-				},
-				Default::default(),
-			)?
-			.into(),
-		);
-		Ok(SubMsg::reply_always(
-			wasm_execute(env.contract.address, &ExecuteMsg::Batch { msgs }, Default::default())?,
-			XCVM_GATEWAY_BATCH_REPLY_ID,
-		))
-	})();
+	// Queries are answered synchronously, the snapshot is carried by the acknowledgement.
+	if let Ok(query) = decode_query_packet(&msg.packet.data) {
+		let ack = query_interpreter(deps.as_ref(), query)
+			.map(Encodable::encode)
+			.unwrap_or_else(|_| XCVMAck::KO.into_vec());
+		return Ok(IbcReceiveResponse::default()
+			.set_ack(ack)
+			.add_event(Event::new(XCVM_GATEWAY_EVENT_PREFIX).add_attribute("action", "query")))
+	}
+	let channel_id = msg.packet.dest.channel_id;
+	let network_id = IBC_CHANNEL_NETWORK.may_load(deps.storage, channel_id.clone())?;
+	let packet: Result<DefaultXCVMPacket, _> = decode_packet(&msg.packet.data);
+	if let (Some(network_id), Ok(packet)) = (network_id, packet) {
+		record_network_height(deps.storage, &env, network_id, packet.height)?;
+	}
+	let path = RateLimitPath::Ibc { channel_id };
+	let batch = execute_packet(
+		deps,
+		&env,
+		path,
+		BridgeProtocol::IBC,
+		network_id,
+		msg.relayer,
+		&msg.packet.data,
+	);
 	match batch {
 		Ok(batch) => Ok(IbcReceiveResponse::default()
 			.set_ack(XCVMAck::OK.into_vec())
@@ -252,53 +565,349 @@ pub fn ibc_packet_receive(
 
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn ibc_packet_ack(
-	deps: DepsMut,
+	mut deps: DepsMut,
 	_env: Env,
 	msg: IbcPacketAckMsg,
 ) -> Result<IbcBasicResponse, ContractError> {
+	if let Ok(query) = decode_query_packet(&msg.original_packet.data) {
+		let result = match XCVMAck::try_from(msg.acknowledgement.data.as_slice()) {
+			Ok(XCVMAck::KO) => Err("The query failed on the remote network.".into()),
+			_ => decode_query_result(msg.acknowledgement.data.as_slice())
+				.map_err(|_| ContractError::InvalidAck)
+				.map(Ok)?,
+		};
+		return forward_query_result(query, result)
+	}
 	let ack = XCVMAck::try_from(msg.acknowledgement.data.as_slice())
 		.map_err(|_| ContractError::InvalidAck)?;
+	let channel_id = msg.original_packet.src.channel_id;
+	let sequence = msg.original_packet.sequence;
+	match IBC_IN_FLIGHT_PACKETS.may_load(deps.storage, (channel_id.clone(), sequence))? {
+		Some(packet) => {
+			IBC_IN_FLIGHT_PACKETS.remove(deps.storage, (channel_id.clone(), sequence));
+			let path = RateLimitPath::Ibc { channel_id: channel_id.clone() };
+			let (messages, event) = settle_packet(&mut deps, &path, packet, ack, msg.relayer)?;
+			Ok(IbcBasicResponse::default().add_messages(messages).add_event(packet_event(
+				event,
+				&channel_id,
+				sequence,
+			)))
+		},
+		// Already refunded when the channel has been closed.
+		None => Ok(IbcBasicResponse::default()),
+	}
+}
+
+/// Decode an inbound XCVM packet, then mint its assets and execute its program in a single
+/// sub-transaction, acknowledged by [`handle_batch_reply`]. The assets are accounted in the rate
+/// limits of the `path` the packet has been received through, and its user origin must be on the
+/// `network_id` it has been received from, when known.
+fn execute_packet(
+	deps: DepsMut,
+	env: &Env,
+	path: RateLimitPath,
+	protocol: BridgeProtocol,
+	network_id: Option<NetworkId>,
+	relayer: Addr,
+	data: &[u8],
+) -> Result<SubMsg, ContractError> {
 	let Config { registry_address, .. } = CONFIG.load(deps.storage)?;
-	let packet: DefaultXCVMPacket =
-		decode_packet(&msg.original_packet.data).map_err(ContractError::Protobuf)?;
-	let messages = match ack {
+	let router_address = ROUTER.load(deps.storage)?;
+	let packet: DefaultXCVMPacket = decode_packet(data).map_err(ContractError::Protobuf)?;
+	// A network can only speak for its own users.
+	if matches!(network_id, Some(network_id) if network_id != packet.user_origin.network_id) {
+		return Err(ContractError::InvalidUserOrigin)
+	}
+	// Execute the inflow accounting, mints and execution in a single sub-transaction, so that a
+	// failed packet isn't accounted.
+	let mut msgs = vec![wasm_execute(
+		env.contract.address.clone(),
+		&ExecuteMsg::RecordInflow { path, assets: packet.assets.clone() },
+		Default::default(),
+	)?
+	.into()];
+	msgs.extend(mint_counterparty_assets(
+		&deps,
+		&env.contract.address,
+		&router_address,
+		registry_address.as_ref(),
+		packet.assets.clone(),
+	)?);
+	msgs.push(
+		wasm_execute(
+			router_address,
+			&cw_xcvm_common::router::ExecuteMsg::ExecuteProgramPrivileged {
+				call_origin: CallOrigin::Remote {
+					protocol,
+					relayer,
+					user_origin: packet.user_origin,
+				},
+				salt: packet.salt,
+				program: packet.program,
+				assets: packet.assets,
+			},
+			Default::default(),
+		)?
+		.into(),
+	);
+	Ok(SubMsg::reply_always(
+		wasm_execute(
+			env.contract.address.clone(),
+			&ExecuteMsg::Batch { msgs },
+			Default::default(),
+		)?,
+		XCVM_GATEWAY_BATCH_REPLY_ID,
+	))
+}
+
+/// Burn the assets escrowed for an outbound XCVM packet sent through `path` and pay its fees to
+/// the `relayer` that delivered it once acknowledged, or refund both on failure.
+fn settle_packet(
+	deps: &mut DepsMut,
+	path: &RateLimitPath,
+	packet: InFlightPacket,
+	ack: XCVMAck,
+	relayer: Addr,
+) -> Result<(Vec<CosmosMsg>, Event), ContractError> {
+	match ack {
 		XCVMAck::OK => {
 			// We got the ACK
-			burn_escrowed_assets(deps, registry_address.as_str(), packet.assets)
-		},
-		XCVMAck::KO => {
-			// On failure, return the funds
-			unescrow_assets(
-				&deps,
-				// Safe as impossible to tamper.
-				String::from_utf8_lossy(&packet.interpreter).to_string(),
-				registry_address.as_str(),
-				packet.assets,
-			)
+			let Config { registry_address, .. } = CONFIG.load(deps.storage)?;
+			let event = Event::new(XCVM_GATEWAY_EVENT_PREFIX)
+				.add_attribute("action", "packet_delivered")
+				.add_attribute("interpreter", packet.interpreter.clone());
+			let mut messages =
+				burn_escrowed_assets(deps, registry_address.as_str(), packet.assets)?;
+			if let Some(RelayerFees { fees }) = packet.fees {
+				messages.extend(unescrow_assets(
+					deps,
+					relayer.into_string(),
+					registry_address.as_str(),
+					fees,
+				)?);
+			}
+			Ok((messages, event))
 		},
+		XCVMAck::KO => refund_packet(deps, path, packet, "ko_ack"),
 		_ => Err(ContractError::InvalidAck),
-	}?;
-	Ok(IbcBasicResponse::default().add_messages(messages))
+	}
+}
+
+/// Return the assets and fees escrowed for a failed outbound XCVM packet to the interpreter that
+/// spawned it, releasing the outflow of the assets through `path`. The `reason` is traced in the
+/// emitted event.
+fn refund_packet(
+	deps: &mut DepsMut,
+	path: &RateLimitPath,
+	packet: InFlightPacket,
+	reason: &str,
+) -> Result<(Vec<CosmosMsg>, Event), ContractError> {
+	let Config { registry_address, .. } = CONFIG.load(deps.storage)?;
+	release_flow(deps.storage, packet.sent_at, path, Flow::Outbound, &packet.assets)?;
+	let event = Event::new(XCVM_GATEWAY_EVENT_PREFIX)
+		.add_attribute("action", "refund")
+		.add_attribute("reason", reason)
+		.add_attribute("interpreter", packet.interpreter.clone())
+		.add_attribute("network_id", format!("{}", u32::from(packet.user_origin.network_id)))
+		.add_attribute("user_id", hex::encode(&packet.user_origin.user_id))
+		.add_attribute(
+			"assets",
+			serde_json_wasm::to_string(&packet.assets)
+				.map_err(|_| ContractError::FailedToSerialize)?,
+		);
+	let mut messages = unescrow_assets(
+		deps,
+		packet.interpreter.to_string(),
+		registry_address.as_str(),
+		packet.assets,
+	)?;
+	if let Some(RelayerFees { fees }) = packet.fees {
+		messages.extend(unescrow_assets(
+			deps,
+			packet.interpreter.into_string(),
+			registry_address.as_str(),
+			fees,
+		)?);
+	}
+	Ok((messages, event))
+}
+
+/// Identify the IBC packet an event relates to.
+fn packet_event(event: Event, channel_id: &str, sequence: u64) -> Event {
+	event
+		.add_attribute("channel_id", channel_id)
+		.add_attribute("sequence", format!("{sequence}"))
+}
+
+/// Record the packet sent by [`handle_bridge`] under the sequence assigned by the IBC module, see
+/// [`IBC_IN_FLIGHT_PACKETS`].
+fn handle_send_packet_reply(deps: DepsMut, msg: Reply) -> Result<Response, ContractError> {
+	let response = msg.result.into_result().map_err(StdError::generic_err)?;
+	let packet = IBC_PENDING_PACKET.load(deps.storage)?;
+	IBC_PENDING_PACKET.remove(deps.storage);
+	let send_packet_event = response
+		.events
+		.iter()
+		.find(|event| event.ty == "send_packet")
+		.ok_or(StdError::not_found("send_packet event not found"))?;
+	let attribute = |key: &str| {
+		send_packet_event
+			.attributes
+			.iter()
+			.find(|attr| attr.key == key)
+			.map(|attr| attr.value.clone())
+			.ok_or_else(|| StdError::not_found(format!("{key} attribute not found")))
+	};
+	let channel_id = attribute("packet_src_channel")?;
+	let sequence = attribute("packet_sequence")?
+		.parse::<u64>()
+		.map_err(|_| StdError::generic_err("invalid packet sequence"))?;
+	IBC_IN_FLIGHT_PACKETS.save(deps.storage, (channel_id.clone(), sequence), &packet)?;
+	Ok(Response::default().add_event(packet_event(
+		Event::new(XCVM_GATEWAY_EVENT_PREFIX)
+			.add_attribute("action", "packet_sent")
+			.add_attribute("interpreter", packet.interpreter),
+		&channel_id,
+		sequence,
+	)))
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn ibc_packet_timeout(
-	deps: DepsMut,
+	mut deps: DepsMut,
 	_env: Env,
 	msg: IbcPacketTimeoutMsg,
 ) -> Result<IbcBasicResponse, ContractError> {
+	if let Ok(query) = decode_query_packet(&msg.packet.data) {
+		return forward_query_result(query, Err("The query timed out.".into()))
+	}
+	let channel_id = msg.packet.src.channel_id;
+	let sequence = msg.packet.sequence;
+	match IBC_IN_FLIGHT_PACKETS.may_load(deps.storage, (channel_id.clone(), sequence))? {
+		Some(packet) => {
+			// On timeout, return the funds
+			IBC_IN_FLIGHT_PACKETS.remove(deps.storage, (channel_id.clone(), sequence));
+			let path = RateLimitPath::Ibc { channel_id: channel_id.clone() };
+			let (messages, event) = refund_packet(&mut deps, &path, packet, "timeout")?;
+			Ok(IbcBasicResponse::default().add_messages(messages).add_event(packet_event(
+				event,
+				&channel_id,
+				sequence,
+			)))
+		},
+		// Already refunded when the channel has been closed.
+		None => Ok(IbcBasicResponse::default()),
+	}
+}
+
+/// Deliver the outcome of a query to the interpreter that initiated it.
+/// The delivery is not allowed to fail the acknowledgement or the timeout of the packet, a failure
+/// being handled by [`handle_query_result_reply`].
+fn forward_query_result(
+	packet: QueryPacket,
+	result: Result<QueryResult, String>,
+) -> Result<IbcBasicResponse, ContractError> {
+	// Safe as impossible to tamper.
+	let interpreter = String::from_utf8_lossy(&packet.interpreter).to_string();
+	Ok(IbcBasicResponse::default()
+		.add_event(
+			Event::new(XCVM_GATEWAY_EVENT_PREFIX)
+				.add_attribute("action", "query_result")
+				.add_attribute("interpreter", interpreter.clone())
+				.add_attribute("query_id", format!("{}", packet.query_id))
+				.add_attribute("success", format!("{}", result.is_ok())),
+		)
+		.add_submessage(SubMsg::reply_on_error(
+			wasm_execute(
+				interpreter,
+				&cw_xcvm_interpreter::msg::ExecuteMsg::SetQueryResult {
+					query_id: packet.query_id,
+					result,
+				},
+				Default::default(),
+			)?,
+			XCVM_GATEWAY_QUERY_RESULT_REPLY_ID,
+		)))
+}
+
+/// The interpreter refused the outcome of a query, e.g. because it has been removed. The outcome
+/// is dropped.
+fn handle_query_result_reply(msg: Reply) -> Response {
+	let mut event =
+		Event::new(XCVM_GATEWAY_EVENT_PREFIX).add_attribute("action", "query_result_failed");
+	if let SubMsgResult::Err(e) = msg.result {
+		event = event.add_attribute("reason", e);
+	}
+	Response::default().add_event(event)
+}
+
+/// Take a snapshot of the registers and balances of the interpreter targeted by a query.
+fn query_interpreter(deps: Deps, packet: QueryPacket) -> Result<QueryResult, ContractError> {
+	let router_address = ROUTER.load(deps.storage)?;
+	let interpreter_address = INTERPRETERS
+		.query(
+			&deps.querier,
+			router_address,
+			InterpreterOrigin { user_origin: packet.user_origin, salt: packet.salt },
+		)?
+		.and_then(|interpreter| interpreter.address)
+		.ok_or(ContractError::UnknownInterpreter)?;
+	let query_register = |register| cw_xcvm_interpreter::msg::QueryMsg::Register(register);
+	let ip: u32 = deps
+		.querier
+		.query_wasm_smart(&interpreter_address, &query_register(Register::Ip))?;
+	// The result and relayer registers are only set once a program has been executed.
+	let result = deps
+		.querier
+		.query_wasm_smart::<Result<SubMsgResponse, String>>(
+			&interpreter_address,
+			&query_register(Register::Result),
+		)
+		.ok()
+		.map(|result| serde_json_wasm::to_vec(&result))
+		.transpose()
+		.map_err(|_| ContractError::FailedToSerialize)?
+		.unwrap_or_default();
+	let relayer = deps
+		.querier
+		.query_wasm_smart::<Addr>(&interpreter_address, &query_register(Register::Relayer))
+		.map(|relayer| relayer.as_bytes().to_vec())
+		.unwrap_or_default();
 	let Config { registry_address, .. } = CONFIG.load(deps.storage)?;
-	let packet: DefaultXCVMPacket =
-		decode_packet(&msg.packet.data).map_err(ContractError::Protobuf)?;
-	// On timeout, return the funds
-	let burns = unescrow_assets(
-		&deps,
-		// Safe as impossible to tamper.
-		String::from_utf8_lossy(&packet.interpreter).to_string(),
-		registry_address.as_str(),
-		packet.assets,
-	)?;
-	Ok(IbcBasicResponse::default().add_messages(burns))
+	let mut balances: Vec<_> = deps
+		.querier
+		.query_all_balances(&interpreter_address)?
+		.into_iter()
+		.map(|coin| (coin.denom, Displayed(coin.amount.into())))
+		.collect();
+	balances.extend(query_cw20_balances(deps, registry_address, &interpreter_address)?);
+	Ok(QueryResult { ip, result, relayer, this: interpreter_address.as_bytes().to_vec(), balances })
+}
+
+/// Non-zero balances of `address` in the cw20 assets of the registry, indexed by their `cw20:`
+/// denomination.
+/// Only the first [`MAX_QUERY_LIMIT`] assets of the registry are visited, so that answering a
+/// query has a bounded cost.
+fn query_cw20_balances(
+	deps: Deps,
+	registry_address: String,
+	address: &Addr,
+) -> Result<Vec<(String, Displayed<u128>)>, ContractError> {
+	let mut balances = Vec::new();
+	for (_, reference) in
+		external_query_assets(deps.querier, registry_address, None, Some(MAX_QUERY_LIMIT))?
+	{
+		if let AssetReference::Virtual { cw20_address } = &reference {
+			let BalanceResponse { balance } = deps.querier.query_wasm_smart(
+				cw20_address,
+				&Cw20QueryMsg::Balance { address: address.to_string() },
+			)?;
+			if !balance.is_zero() {
+				balances.push((reference.denom(), Displayed(balance.into())));
+			}
+		}
+	}
+	Ok(balances)
 }
 
 pub fn handle_batch_reply(msg: Reply) -> Result<Response, ContractError> {
@@ -310,55 +919,163 @@ pub fn handle_batch_reply(msg: Reply) -> Result<Response, ContractError> {
 
 fn mint_counterparty_assets(
 	deps: &DepsMut,
-	router_address: &str,
+	self_address: &Addr,
+	router_address: &Addr,
 	registry_address: &str,
 	assets: Funds<Displayed<u128>>,
 ) -> Result<Vec<CosmosMsg>, ContractError> {
 	assets
 		.into_iter()
 		.map(|(asset_id, Displayed(amount))| {
-			let reference =
-				external_query_lookup_asset(deps.querier, registry_address.to_string(), asset_id)?;
-			match &reference {
-				AssetReference::Native { .. } => Err(ContractError::UnsupportedAsset),
-				AssetReference::Virtual { cw20_address } => {
-					// Burn from the current contract.
-					Ok(wasm_execute(
-						cw20_address.to_string(),
-						&Cw20ExecuteMsg::Mint {
-							recipient: router_address.to_string(),
-							amount: amount.into(),
-						},
-						Default::default(),
-					)?
-					.into())
-				},
+			match ASSETS.query(&deps.querier, Addr::unchecked(registry_address), asset_id.into())? {
+				// The asset is coming back, unlock the escrowed denom.
+				Some(AssetReference::Native { denom }) => Ok(BankMsg::Send {
+					to_address: router_address.to_string(),
+					amount: vec![Coin { denom, amount: amount.into() }],
+				}
+				.into()),
+				Some(AssetReference::Virtual { cw20_address }) => Ok(wasm_execute(
+					cw20_address.to_string(),
+					&Cw20ExecuteMsg::Mint {
+						recipient: router_address.to_string(),
+						amount: amount.into(),
+					},
+					Default::default(),
+				)?
+				.into()),
+				// First transfer of the asset to this network, wrap it.
+				None => Ok(wasm_execute(
+					self_address,
+					&ExecuteMsg::CreateWrappedAsset {
+						asset_id,
+						recipient: router_address.clone(),
+						amount: Displayed(amount),
+					},
+					Default::default(),
+				)?
+				.into()),
 			}
 		})
 		.collect::<Result<Vec<_>, _>>()
 }
 
-fn burn_escrowed_assets(
+/// Instantiate a CW20 contract representing an asset of another network, minting `amount` to the
+/// `recipient`. The contract is registered in the asset-registry once instantiated, see
+/// [`handle_instantiate_wrapped_asset_reply`], so that the next transfers mint it directly.
+/// The name and decimals of the asset must be known, either by the asset-registry or as a
+/// built-in asset, the wrapped asset keeping its decimals and being named after it.
+fn handle_create_wrapped_asset(
+	deps: DepsMut,
+	env: Env,
+	asset_id: AssetId,
+	recipient: Addr,
+	Displayed(amount): Displayed<u128>,
+) -> Result<Response, ContractError> {
+	let Config { registry_address, wrapped_asset_code_id, wrapped_asset_prefix, .. } =
+		CONFIG.load(deps.storage)?;
+	let prefix = wrapped_asset_prefix.as_deref().unwrap_or(DEFAULT_WRAPPED_ASSET_PREFIX);
+	let code_id = wrapped_asset_code_id.ok_or(ContractError::UnsupportedAsset)?;
+	// The wrapped asset is registered by the gateway, fail before instantiating it.
+	let RegistrarsResponse { registrars } = deps.querier.query_wasm_smart(
+		&registry_address,
+		&cw_xcvm_asset_registry::msg::QueryMsg::Registrars {},
+	)?;
+	if !registrars.contains(&env.contract.address) {
+		return Err(ContractError::NotRegistrar)
+	}
+	let AssetInfo { name, decimals, .. } = AssetRegistryContract(Addr::unchecked(registry_address))
+		.registry(deps.querier, MemoryRegistry::default())
+		.asset_by_id(asset_id)
+		.ok_or(ContractError::UnknownAssetInfo)?;
+	let symbol = wrapped_asset_symbol(prefix, &name)?;
+	PENDING_WRAPPED_ASSET.save(deps.storage, &asset_id)?;
+	let asset_id = u128::from(asset_id);
+	let instantiate_msg = wasm_instantiate(
+		code_id,
+		&Cw20InstantiateMsg {
+			name: format!("XCVM wrapped asset {}", asset_id),
+			symbol,
+			decimals,
+			initial_balances: vec![Cw20Coin {
+				address: recipient.into_string(),
+				amount: amount.into(),
+			}],
+			mint: Some(MinterResponse { minter: env.contract.address.to_string(), cap: None }),
+		},
+		Default::default(),
+		format!("{}-wrapped-asset-{}", prefix, asset_id),
+	)?;
+	Ok(Response::default().add_submessage(SubMsg::reply_on_success(
+		instantiate_msg,
+		XCVM_GATEWAY_INSTANTIATE_WRAPPED_ASSET_REPLY_ID,
+	)))
+}
+
+/// Symbol of the asset named `name` once wrapped, e.g. `xcvmPICA`. As the names of the assets,
+/// the symbols of the wrapped assets are unique.
+fn wrapped_asset_symbol(prefix: &str, name: &str) -> Result<String, ContractError> {
+	let symbol = format!("{prefix}{name}");
+	// Symbols accepted by the CW20 contract.
+	if (3..=12).contains(&symbol.len()) &&
+		symbol.bytes().all(|byte| byte.is_ascii_alphabetic() || byte == b'-')
+	{
+		Ok(symbol)
+	} else {
+		Err(ContractError::InvalidWrappedAssetSymbol)
+	}
+}
+
+/// Register the freshly instantiated wrapped asset in the asset-registry.
+fn handle_instantiate_wrapped_asset_reply(
 	deps: DepsMut,
+	msg: Reply,
+) -> Result<Response, ContractError> {
+	let response = msg.result.into_result().map_err(StdError::generic_err)?;
+	let cw20_address = instantiated_contract_address(deps.as_ref(), &response)?;
+	let asset_id = PENDING_WRAPPED_ASSET.load(deps.storage)?;
+	PENDING_WRAPPED_ASSET.remove(deps.storage);
+	let Config { registry_address, .. } = CONFIG.load(deps.storage)?;
+	Ok(Response::default()
+		.add_event(
+			Event::new(XCVM_GATEWAY_EVENT_PREFIX)
+				.add_attribute("action", "wrapped_asset_created")
+				.add_attribute("asset_id", u128::from(asset_id).to_string())
+				.add_attribute("cw20_address", cw20_address.to_string()),
+		)
+		.add_message(wasm_execute(
+			registry_address,
+			&cw_xcvm_asset_registry::msg::ExecuteMsg::RegisterAsset {
+				asset_id: asset_id.into(),
+				reference: AssetReference::Virtual { cw20_address },
+				origin_network: None,
+			},
+			Default::default(),
+		)?))
+}
+
+fn burn_escrowed_assets(
+	deps: &DepsMut,
 	registry_address: &str,
 	assets: Funds<Displayed<u128>>,
 ) -> Result<Vec<CosmosMsg>, ContractError> {
 	assets
 		.into_iter()
-		.map(|(asset_id, Displayed(amount))| {
-			let reference =
-				external_query_lookup_asset(deps.querier, registry_address.to_string(), asset_id)?;
-			match &reference {
-				AssetReference::Native { .. } => Err(ContractError::UnsupportedAsset),
-				AssetReference::Virtual { cw20_address } => {
-					// Burn from the current contract.
-					Ok(wasm_execute(
+		.filter_map(|(asset_id, Displayed(amount))| {
+			match external_query_lookup_asset(deps.querier, registry_address.to_string(), asset_id)
+			{
+				// Native denoms stay locked in the gateway until they come back.
+				Ok(AssetReference::Native { .. }) => None,
+				// Burn from the current contract.
+				Ok(AssetReference::Virtual { cw20_address }) => Some(
+					wasm_execute(
 						cw20_address.to_string(),
 						&Cw20ExecuteMsg::Burn { amount: amount.into() },
 						Default::default(),
-					)?
-					.into())
-				},
+					)
+					.map(Into::into)
+					.map_err(Into::into),
+				),
+				Err(error) => Some(Err(error.into())),
 			}
 		})
 		.collect::<Result<Vec<_>, _>>()
@@ -375,10 +1092,14 @@ fn unescrow_assets(
 		.map(|(asset_id, Displayed(amount))| {
 			let reference =
 				external_query_lookup_asset(deps.querier, registry_address.to_string(), asset_id)?;
-			match &reference {
-				AssetReference::Native { .. } => Err(ContractError::UnsupportedAsset),
+			match reference {
+				AssetReference::Native { denom } => Ok(BankMsg::Send {
+					to_address: sender.clone(),
+					amount: vec![Coin { denom, amount: amount.into() }],
+				}
+				.into()),
 				AssetReference::Virtual { cw20_address } => {
-					// Transfer from the sender to the gateway
+					// Transfer from the gateway back to the sender
 					Ok(wasm_execute(
 						cw20_address.to_string(),
 						&Cw20ExecuteMsg::Transfer {
@@ -394,8 +1115,84 @@ fn unescrow_assets(
 		.collect::<Result<Vec<_>, _>>()
 }
 
+/// Record the `height` carried by a packet received from `network_id`.
+/// The height is ignored if it is lower than the recorded one, or if the network could not have
+/// produced that many blocks since the recorded one was received, see [`MAX_BLOCKS_PER_SECOND`].
+fn record_network_height(
+	storage: &mut dyn Storage,
+	env: &Env,
+	network_id: NetworkId,
+	height: u64,
+) -> StdResult<()> {
+	let plausible = match NETWORK_HEIGHTS.may_load(storage, network_id)? {
+		Some(last) if !network_height_expired(env, &last) => {
+			let elapsed = env.block.time.seconds().saturating_sub(last.received_at.seconds());
+			height > last.height &&
+				height - last.height <=
+					elapsed.saturating_add(1).saturating_mul(MAX_BLOCKS_PER_SECOND)
+		},
+		_ => true,
+	};
+	if plausible {
+		NETWORK_HEIGHTS.save(
+			storage,
+			network_id,
+			&NetworkHeight { height, received_at: env.block.time },
+		)?;
+	}
+	Ok(())
+}
+
+/// Whether the height of a network is too old to be relied upon, see
+/// [`NETWORK_HEIGHT_VALIDITY_SECONDS`].
+fn network_height_expired(env: &Env, network_height: &NetworkHeight) -> bool {
+	env.block.time.seconds().saturating_sub(network_height.received_at.seconds()) >
+		NETWORK_HEIGHT_VALIDITY_SECONDS
+}
+
+/// Compute the timeout of a packet sent to `network_id`, bounded by the optional `deadline` of
+/// the program.
+fn ibc_timeout(
+	deps: Deps,
+	env: &Env,
+	network_id: NetworkId,
+	deadline: Option<u64>,
+) -> Result<IbcTimeout, ContractError> {
+	let deadline = deadline.map(Timestamp::from_seconds);
+	if matches!(deadline, Some(deadline) if deadline <= env.block.time) {
+		return Err(ContractError::DeadlineExpired)
+	}
+	let timeout = NETWORK_TIMEOUTS
+		.may_load(deps.storage, network_id)?
+		.unwrap_or(DEFAULT_NETWORK_TIMEOUT);
+	let timestamp = |seconds| {
+		let timestamp = env.block.time.plus_seconds(seconds);
+		IbcTimeout::with_timestamp(deadline.map_or(timestamp, |deadline| deadline.min(timestamp)))
+	};
+	Ok(match timeout {
+		NetworkTimeout::Seconds(seconds) => timestamp(seconds),
+		NetworkTimeout::Blocks { revision, blocks } =>
+			match NETWORK_HEIGHTS.may_load(deps.storage, network_id)? {
+				Some(network_height) if !network_height_expired(env, &network_height) => {
+					let block = IbcTimeoutBlock {
+						revision,
+						height: network_height.height.saturating_add(blocks),
+					};
+					match deadline {
+						Some(deadline) => IbcTimeout::with_both(block, deadline),
+						None => IbcTimeout::with_block(block),
+					}
+				},
+				// The height of the network is unknown until a packet is received from it, and
+				// expires if no packet is received for a while.
+				_ => timestamp(DEFAULT_NETWORK_TIMEOUT_SECONDS),
+			},
+	})
+}
+
 pub fn handle_bridge(
 	deps: DepsMut,
+	env: Env,
 	info: MessageInfo,
 	interpreter: Addr,
 	interpreter_origin: InterpreterOrigin,
@@ -404,64 +1201,1213 @@ pub fn handle_bridge(
 	salt: Vec<u8>,
 	program: DefaultXCVMProgram,
 	assets: Funds<Displayed<u128>>,
+	deadline: Option<u64>,
+	fees: Option<RelayerFees>,
 ) -> Result<Response, ContractError> {
 	ensure_router(deps.as_ref(), info.sender.as_ref())?;
-	match security {
-		// Only allow deterministic over IBC here
-		BridgeSecurity::Deterministic => {
-			let channel_id = IBC_NETWORK_CHANNEL.load(deps.storage, network_id)?;
-			let packet = DefaultXCVMPacket {
-				interpreter: interpreter.as_bytes().to_vec(),
-				user_origin: interpreter_origin.user_origin,
-				salt,
-				program,
-				assets,
-			};
+	// The packet carries the identifiers of the assets on the counterparty network.
+	let Config { registry_address, .. } = CONFIG.load(deps.storage)?;
+	let remote_assets = assets
+		.0
+		.iter()
+		.map(|(asset_id, amount)| {
+			external_query_remote_asset(
+				deps.querier,
+				registry_address.to_string(),
+				*asset_id,
+				network_id,
+			)
+			.map(|remote_asset_id| (remote_asset_id, *amount))
+		})
+		.collect::<StdResult<Vec<_>>>()?;
+	let packet = DefaultXCVMPacket {
+		interpreter: interpreter.as_bytes().to_vec(),
+		user_origin: interpreter_origin.user_origin,
+		salt,
+		program,
+		assets: Funds(remote_assets),
+		height: env.block.height,
+	};
+	let event = Event::new(XCVM_GATEWAY_EVENT_PREFIX)
+		.add_attribute("action", "bridge")
+		.add_attribute("network_id", format!("{network_id}"))
+		.add_attribute("salt", format!("{}", Binary::from(packet.salt.clone())))
+		.add_attribute(
+			"program",
+			serde_json_wasm::to_string(&packet.program)
+				.map_err(|_| ContractError::FailedToSerialize)?,
+		)
+		.add_attribute(
+			"assets",
+			serde_json_wasm::to_string(&packet.assets)
+				.map_err(|_| ContractError::FailedToSerialize)?,
+		);
+	match select_bridge(deps.as_ref(), network_id, security)? {
+		Some(Route::Ibc(channel_id)) => {
+			let timeout = ibc_timeout(deps.as_ref(), &env, network_id, deadline)?;
+			record_flow(
+				deps.storage,
+				env.block.time.seconds(),
+				&RateLimitPath::Ibc { channel_id: channel_id.clone() },
+				Flow::Outbound,
+				&assets,
+			)?;
+			IBC_PENDING_PACKET.save(
+				deps.storage,
+				&InFlightPacket {
+					interpreter: interpreter.clone(),
+					user_origin: packet.user_origin.clone(),
+					assets,
+					sent_at: env.block.time.seconds(),
+					fees,
+				},
+			)?;
+			Ok(Response::default().add_event(event).add_submessage(SubMsg::reply_on_success(
+				IbcMsg::SendPacket { channel_id, data: Binary::from(packet.encode()), timeout },
+				XCVM_GATEWAY_SEND_PACKET_REPLY_ID,
+			)))
+		},
+		Some(Route::Otp(bridge_id, bridge)) => {
+			if matches!(deadline, Some(deadline) if deadline <= env.block.time.seconds()) {
+				return Err(ContractError::DeadlineExpired)
+			}
+			record_flow(
+				deps.storage,
+				env.block.time.seconds(),
+				&RateLimitPath::Bridge { bridge_id: bridge_id.clone() },
+				Flow::Outbound,
+				&assets,
+			)?;
+			let sequence =
+				OTP_NEXT_SEQUENCE.may_load(deps.storage, bridge_id.clone())?.unwrap_or(1);
+			OTP_NEXT_SEQUENCE.save(deps.storage, bridge_id.clone(), &(sequence + 1))?;
+			OTP_IN_FLIGHT_PACKETS.save(
+				deps.storage,
+				(bridge_id.clone(), sequence),
+				&InFlightPacket {
+					interpreter: interpreter.clone(),
+					user_origin: packet.user_origin.clone(),
+					assets,
+					sent_at: env.block.time.seconds(),
+					fees,
+				},
+			)?;
 			Ok(Response::default()
 				.add_event(
-					Event::new(XCVM_GATEWAY_EVENT_PREFIX)
-						.add_attribute("action", "bridge")
-						.add_attribute("network_id", format!("{network_id}"))
-						.add_attribute("salt", format!("{}", Binary::from(packet.salt.clone())))
-						.add_attribute(
-							"program",
-							serde_json_wasm::to_string(&packet.program)
-								.map_err(|_| ContractError::FailedToSerialize)?,
-						)
-						.add_attribute(
-							"assets",
-							serde_json_wasm::to_string(&packet.assets)
-								.map_err(|_| ContractError::FailedToSerialize)?,
-						),
+					event
+						.add_attribute("bridge_id", format!("{}", Binary::from(bridge_id.as_ref())))
+						.add_attribute("sequence", format!("{sequence}")),
 				)
-				.add_message(IbcMsg::SendPacket {
-					channel_id,
-					data: Binary::from(packet.encode()),
-					// TODO: should be a parameter or configuration
-					timeout: IbcTimeout::with_block(IbcTimeoutBlock { revision: 0, height: 10000 }),
-				}))
+				.add_message(wasm_execute(
+					bridge.address,
+					&cw_xcvm_common::bridge::ExecuteMsg::SendPacket {
+						network_id,
+						packet: Binary::from(packet.encode()),
+						deadline,
+						sequence,
+					},
+					Default::default(),
+				)?))
 		},
-		_ => Err(ContractError::UnsupportedBridgeSecurity),
+		None => Err(ContractError::UnsupportedBridgeSecurity),
 	}
 }
 
+/// Path through which a packet is sent to a network.
+#[derive(Debug, PartialEq)]
+enum Route {
+	/// The IBC channel of the network, considered deterministic.
+	Ibc(ChannelId),
+	Otp(BridgeId, Bridge),
+}
+
+/// Select the route to `network_id` providing at least the requested `security`.
+/// Deterministic packets are sent over IBC, falling back to a deterministic OTP bridge. Weaker
+/// requirements are fulfilled by the most secure OTP bridge, falling back to IBC.
+fn select_bridge(
+	deps: Deps,
+	network_id: NetworkId,
+	security: BridgeSecurity,
+) -> Result<Option<Route>, ContractError> {
+	let channel = IBC_NETWORK_CHANNEL.may_load(deps.storage, network_id)?.map(Route::Ibc);
+	if security == BridgeSecurity::Deterministic && channel.is_some() {
+		return Ok(channel)
+	}
+	let mut selected: Option<(BridgeId, Bridge)> = None;
+	for entry in BRIDGES.range(deps.storage, None, None, Order::Ascending) {
+		let (bridge_id, bridge) = entry?;
+		let more_secure = match &selected {
+			Some((_, selected)) => bridge.security > selected.security,
+			None => true,
+		};
+		if bridge.network_id == network_id && bridge.security >= security && more_secure {
+			selected = Some((bridge_id.into(), bridge));
+		}
+	}
+	Ok(selected.map(|(bridge_id, bridge)| Route::Otp(bridge_id, bridge)).or(channel))
+}
+
+pub fn handle_bridge_query(
+	deps: DepsMut,
+	env: Env,
+	info: MessageInfo,
+	interpreter: Addr,
+	BridgeQueryMsg { interpreter_origin, network_id, salt, query_id }: BridgeQueryMsg,
+) -> Result<Response, ContractError> {
+	ensure_router(deps.as_ref(), info.sender.as_ref())?;
+	let channel_id = IBC_NETWORK_CHANNEL.load(deps.storage, network_id)?;
+	let packet = QueryPacket {
+		interpreter: interpreter.as_bytes().to_vec(),
+		user_origin: interpreter_origin.user_origin,
+		salt,
+		query_id,
+	};
+	Ok(Response::default()
+		.add_event(
+			Event::new(XCVM_GATEWAY_EVENT_PREFIX)
+				.add_attribute("action", "bridge_query")
+				.add_attribute("network_id", format!("{network_id}"))
+				.add_attribute("salt", format!("{}", Binary::from(packet.salt.clone()))),
+		)
+		.add_message(IbcMsg::SendPacket {
+			channel_id,
+			data: Binary::from(packet.encode()),
+			timeout: ibc_timeout(deps.as_ref(), &env, network_id, None)?,
+		}))
+}
+
 fn handle_instantiate_reply(deps: DepsMut, msg: Reply) -> Result<Response, ContractError> {
 	let response = msg.result.into_result().map_err(StdError::generic_err)?;
-	let router_address = {
-		let instantiate_event = response
-			.events
-			.iter()
-			.find(|event| event.ty == "instantiate")
-			.ok_or(StdError::not_found("instantiate event not found"))?;
-		deps.api.addr_validate(
-			&instantiate_event
-				.attributes
-				.iter()
-				.find(|attr| &attr.key == "_contract_address")
-				.ok_or(StdError::not_found("_contract_address attribute not found"))?
-				.value,
-		)?
-	};
+	let router_address = instantiated_contract_address(deps.as_ref(), &response)?;
 	ROUTER.save(deps.storage, &router_address)?;
 	Ok(Response::default())
 }
+
+/// Extract the address of the contract instantiated by a sub-message.
+fn instantiated_contract_address(
+	deps: Deps,
+	response: &SubMsgResponse,
+) -> Result<Addr, ContractError> {
+	let instantiate_event = response
+		.events
+		.iter()
+		.find(|event| event.ty == "instantiate")
+		.ok_or(StdError::not_found("instantiate event not found"))?;
+	Ok(deps.api.addr_validate(
+		&instantiate_event
+			.attributes
+			.iter()
+			.find(|attr| &attr.key == "_contract_address")
+			.ok_or(StdError::not_found("_contract_address attribute not found"))?
+			.value,
+	)?)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use cosmwasm_std::{
+		from_binary,
+		testing::{
+			mock_dependencies, mock_env, mock_ibc_channel_close_init, mock_info, MockApi,
+			MockQuerier, MockStorage,
+		},
+		ContractResult, IbcAcknowledgement, IbcEndpoint, IbcPacket, OwnedDeps, SystemResult,
+		WasmQuery,
+	};
+	use cw_xcvm_asset_registry::msg::{
+		AssetsResponse, LookupResponse, QueryMsg as RegistryQueryMsg, RemoteAssetResponse,
+	};
+	use cw_xcvm_common::gateway::RateLimit;
+	use std::collections::VecDeque;
+	use xcvm_core::{Asset, UserOrigin, PICA};
+
+	const CHANNEL: &str = "channel-0";
+	const INTERPRETER: &str = "interpreter";
+	const CW20: &str = "cw20";
+
+	fn setup() -> OwnedDeps<MockStorage, MockApi, MockQuerier> {
+		let mut deps = mock_dependencies();
+		deps.querier.update_wasm(|query| {
+			let msg = match query {
+				WasmQuery::Smart { msg, .. } => from_binary(msg).unwrap(),
+				WasmQuery::Raw { .. } =>
+					return SystemResult::Ok(ContractResult::Ok(
+						to_binary(&AssetReference::Virtual { cw20_address: Addr::unchecked(CW20) })
+							.unwrap(),
+					)),
+				_ => panic!("unexpected query"),
+			};
+			SystemResult::Ok(match msg {
+				RegistryQueryMsg::Lookup { .. } => ContractResult::Ok(
+					to_binary(&LookupResponse {
+						reference: AssetReference::Virtual { cw20_address: Addr::unchecked(CW20) },
+					})
+					.unwrap(),
+				),
+				// PICA is known as asset 42 on network 2.
+				RegistryQueryMsg::RemoteAsset { asset_id, network_id } => ContractResult::Ok(
+					to_binary(&RemoteAssetResponse {
+						remote_asset_id: (AssetId::from(asset_id) == PICA::ID &&
+							network_id == 2.into())
+						.then(|| 42.into()),
+					})
+					.unwrap(),
+				),
+				RegistryQueryMsg::Registrars {} => ContractResult::Ok(
+					to_binary(&RegistrarsResponse {
+						registrars: vec![mock_env().contract.address],
+					})
+					.unwrap(),
+				),
+				_ => ContractResult::Err("unknown asset".into()),
+			})
+		});
+		let config = Config {
+			registry_address: "registry".into(),
+			router_code_id: 0,
+			interpreter_code_id: 0,
+			network_id: 1.into(),
+			admin: "admin".into(),
+			wrapped_asset_code_id: None,
+			wrapped_asset_prefix: None,
+		};
+		CONFIG.save(deps.as_mut().storage, &config).unwrap();
+		ROUTER.save(deps.as_mut().storage, &Addr::unchecked("router")).unwrap();
+		IBC_NETWORK_CHANNEL
+			.save(deps.as_mut().storage, 2.into(), &CHANNEL.into())
+			.unwrap();
+		deps
+	}
+
+	/// Bridge a program escrowing 100 PICA, the IBC module assigning it `sequence`.
+	fn send_packet(deps: DepsMut, sequence: u64) -> IbcPacket {
+		send_packet_with_fees(deps, sequence, None)
+	}
+
+	fn send_packet_with_fees(deps: DepsMut, sequence: u64, fees: Option<RelayerFees>) -> IbcPacket {
+		let msg = BridgeMsg {
+			interpreter_origin: InterpreterOrigin {
+				user_origin: UserOrigin { network_id: 1.into(), user_id: b"alice".to_vec().into() },
+				salt: Vec::new(),
+			},
+			network_id: 2.into(),
+			security: BridgeSecurity::Deterministic,
+			salt: Vec::new(),
+			program: DefaultXCVMProgram { tag: Vec::new(), instructions: VecDeque::new() },
+			assets: Funds::from([(PICA::ID, 100_u128)]),
+			deadline: None,
+			tip: Funds::empty(),
+		};
+		let interpreter = Addr::unchecked(INTERPRETER);
+		let response = execute(
+			deps,
+			mock_env(),
+			mock_info("router", &[]),
+			ExecuteMsg::Bridge { interpreter, msg, fees },
+		)
+		.unwrap();
+		let (data, timeout) = match &response.messages[0].msg {
+			CosmosMsg::Ibc(IbcMsg::SendPacket { data, timeout, .. }) =>
+				(data.clone(), timeout.clone()),
+			_ => panic!("expected an IBC packet"),
+		};
+		let endpoint = |channel_id: &str| IbcEndpoint {
+			port_id: "wasm.gateway".into(),
+			channel_id: channel_id.into(),
+		};
+		IbcPacket::new(data, endpoint(CHANNEL), endpoint("channel-1"), sequence, timeout)
+	}
+
+	fn reply_sequence(deps: DepsMut, sequence: u64) {
+		let event = Event::new("send_packet")
+			.add_attribute("packet_src_channel", CHANNEL)
+			.add_attribute("packet_sequence", format!("{sequence}"));
+		reply(
+			deps,
+			mock_env(),
+			Reply {
+				id: XCVM_GATEWAY_SEND_PACKET_REPLY_ID,
+				result: SubMsgResult::Ok(SubMsgResponse { events: vec![event], data: None }),
+			},
+		)
+		.unwrap();
+	}
+
+	fn refund() -> CosmosMsg {
+		wasm_execute(
+			CW20,
+			&Cw20ExecuteMsg::Transfer { recipient: INTERPRETER.into(), amount: 100_u128.into() },
+			Vec::new(),
+		)
+		.unwrap()
+		.into()
+	}
+
+	fn messages(response: &IbcBasicResponse) -> Vec<CosmosMsg> {
+		response.messages.iter().map(|message| message.msg.clone()).collect()
+	}
+
+	fn reason(response: &IbcBasicResponse) -> Option<String> {
+		response
+			.events
+			.iter()
+			.flat_map(|event| event.attributes.iter())
+			.find(|attribute| attribute.key == "reason")
+			.map(|attribute| attribute.value.clone())
+	}
+
+	#[test]
+	fn bridged_assets_use_their_remote_ids() {
+		let mut deps = setup();
+		let packet = send_packet(deps.as_mut(), 1);
+		let packet: DefaultXCVMPacket = decode_packet(packet.data.as_slice()).unwrap();
+		assert_eq!(packet.assets, Funds::from([(42_u128, 100_u128)]));
+		// The local asset is escrowed and refunded.
+		assert_eq!(
+			IBC_PENDING_PACKET.load(&deps.storage).unwrap().assets,
+			Funds::from([(PICA::ID, 100_u128)])
+		);
+	}
+
+	#[test]
+	fn ko_ack_refunds_the_interpreter() {
+		let mut deps = setup();
+		let packet = send_packet(deps.as_mut(), 1);
+		reply_sequence(deps.as_mut(), 1);
+		assert!(IBC_IN_FLIGHT_PACKETS.has(&deps.storage, (CHANNEL.into(), 1)));
+
+		let ack = |ack: XCVMAck| {
+			IbcPacketAckMsg::new(
+				IbcAcknowledgement::new(ack.into_vec()),
+				packet.clone(),
+				Addr::unchecked("relayer"),
+			)
+		};
+		let response = ibc_packet_ack(deps.as_mut(), mock_env(), ack(XCVMAck::KO)).unwrap();
+		assert_eq!(messages(&response), vec![refund()]);
+		assert_eq!(reason(&response), Some("ko_ack".into()));
+		assert!(!IBC_IN_FLIGHT_PACKETS.has(&deps.storage, (CHANNEL.into(), 1)));
+
+		// A packet is settled only once.
+		let response = ibc_packet_ack(deps.as_mut(), mock_env(), ack(XCVMAck::OK)).unwrap();
+		assert!(response.messages.is_empty());
+	}
+
+	#[test]
+	fn relayer_fees_are_paid_once_delivered() {
+		let mut deps = setup();
+		let fees = RelayerFees { fees: Funds::from([(PICA::ID, 5_u128)]) };
+		let transfer = |recipient: &str| -> CosmosMsg {
+			wasm_execute(
+				CW20,
+				&Cw20ExecuteMsg::Transfer { recipient: recipient.into(), amount: 5_u128.into() },
+				Vec::new(),
+			)
+			.unwrap()
+			.into()
+		};
+		let ack = |packet: &IbcPacket, ack: XCVMAck| {
+			IbcPacketAckMsg::new(
+				IbcAcknowledgement::new(ack.into_vec()),
+				packet.clone(),
+				Addr::unchecked("ack_relayer"),
+			)
+		};
+
+		// The fees go to the relayer that delivered the packet.
+		let packet = send_packet_with_fees(deps.as_mut(), 1, Some(fees.clone()));
+		reply_sequence(deps.as_mut(), 1);
+		let response =
+			ibc_packet_ack(deps.as_mut(), mock_env(), ack(&packet, XCVMAck::OK)).unwrap();
+		assert_eq!(messages(&response)[1..], [transfer("ack_relayer")]);
+
+		// The fees are refunded along with the assets.
+		let packet = send_packet_with_fees(deps.as_mut(), 2, Some(fees));
+		reply_sequence(deps.as_mut(), 2);
+		let response =
+			ibc_packet_ack(deps.as_mut(), mock_env(), ack(&packet, XCVMAck::KO)).unwrap();
+		assert_eq!(messages(&response), vec![refund(), transfer(INTERPRETER)]);
+	}
+
+	#[test]
+	fn failed_packets_are_not_rate_limited() {
+		let mut deps = setup();
+		let limit =
+			RateLimit { window: 100, max_inflow: Displayed(100), max_outflow: Displayed(100) };
+		let channel = |channel_id: &str| RateLimitPath::Ibc { channel_id: channel_id.into() };
+		for channel_id in [CHANNEL, "channel-1"] {
+			set_rate_limit(deps.as_mut().storage, channel(channel_id), PICA::ID, Some(limit))
+				.unwrap();
+		}
+		let flow = |deps: Deps, channel_id| {
+			RATE_LIMITS
+				.load(deps.storage, (path_key(&channel(channel_id)), PICA::ID.into()))
+				.unwrap()
+				.flow
+		};
+
+		// The outflow of a refunded packet is released.
+		let packet = send_packet(deps.as_mut(), 1);
+		reply_sequence(deps.as_mut(), 1);
+		assert_eq!(flow(deps.as_ref(), CHANNEL).outflow, Displayed(100));
+		let ack = IbcPacketAckMsg::new(
+			IbcAcknowledgement::new(XCVMAck::KO.into_vec()),
+			packet.clone(),
+			Addr::unchecked("relayer"),
+		);
+		ibc_packet_ack(deps.as_mut(), mock_env(), ack).unwrap();
+		assert_eq!(flow(deps.as_ref(), CHANNEL).outflow, Displayed(0));
+
+		// The inflow is accounted within the sub-transaction of the packet, reverted on failure.
+		let response = ibc_packet_receive(
+			deps.as_mut(),
+			mock_env(),
+			IbcPacketReceiveMsg::new(packet, Addr::unchecked("relayer")),
+		)
+		.unwrap();
+		assert_eq!(flow(deps.as_ref(), "channel-1").inflow, Displayed(0));
+		let msgs = match &response.messages[0].msg {
+			CosmosMsg::Wasm(cosmwasm_std::WasmMsg::Execute { msg, .. }) =>
+				match from_binary(msg).unwrap() {
+					ExecuteMsg::Batch { msgs } => msgs,
+					_ => panic!("expected a batch"),
+				},
+			_ => panic!("expected a batch"),
+		};
+		assert_eq!(
+			msgs[0],
+			wasm_execute(
+				mock_env().contract.address,
+				&ExecuteMsg::RecordInflow {
+					path: channel("channel-1"),
+					assets: Funds::from([(42_u128, 100_u128)]),
+				},
+				Vec::new(),
+			)
+			.unwrap()
+			.into()
+		);
+	}
+
+	#[test]
+	fn rate_limits_are_paginated() {
+		let mut deps = setup();
+		let limit = RateLimit { window: 100, max_inflow: Displayed(1), max_outflow: Displayed(1) };
+		for asset_id in 1..=3_u128 {
+			let path = RateLimitPath::Ibc { channel_id: CHANNEL.into() };
+			set_rate_limit(deps.as_mut().storage, path, asset_id.into(), Some(limit)).unwrap();
+		}
+		let rate_limits = |start_after, limit| -> Vec<AssetId> {
+			let response: RateLimitsResponse = from_binary(
+				&query(deps.as_ref(), mock_env(), QueryMsg::RateLimits { start_after, limit })
+					.unwrap(),
+			)
+			.unwrap();
+			response.rate_limits.into_iter().map(|rate_limit| rate_limit.asset_id).collect()
+		};
+		let path = RateLimitPath::Ibc { channel_id: CHANNEL.into() };
+		assert_eq!(rate_limits(None, Some(2)), vec![1.into(), 2.into()]);
+		assert_eq!(rate_limits(Some((path, 2.into())), Some(2)), vec![3.into()]);
+	}
+
+	#[test]
+	fn timeout_refunds_the_interpreter() {
+		let mut deps = setup();
+		let packet = send_packet(deps.as_mut(), 7);
+		reply_sequence(deps.as_mut(), 7);
+		let response = ibc_packet_timeout(
+			deps.as_mut(),
+			mock_env(),
+			IbcPacketTimeoutMsg::new(packet, Addr::unchecked("relayer")),
+		)
+		.unwrap();
+		assert_eq!(messages(&response), vec![refund()]);
+		assert_eq!(reason(&response), Some("timeout".into()));
+	}
+
+	#[test]
+	fn height_timeouts_are_relative_to_the_counterparty() {
+		let mut deps = setup();
+		let env = mock_env();
+		let in_seconds = |seconds| IbcTimeout::with_timestamp(env.block.time.plus_seconds(seconds));
+		assert_eq!(
+			ibc_timeout(deps.as_ref(), &env, 2.into(), None).unwrap(),
+			in_seconds(DEFAULT_NETWORK_TIMEOUT_SECONDS)
+		);
+		let deadline = env.block.time.plus_seconds(10).seconds();
+		assert_eq!(
+			ibc_timeout(deps.as_ref(), &env, 2.into(), Some(deadline)).unwrap(),
+			IbcTimeout::with_timestamp(Timestamp::from_seconds(deadline))
+		);
+		assert!(matches!(
+			ibc_timeout(deps.as_ref(), &env, 2.into(), Some(env.block.time.seconds())),
+			Err(ContractError::DeadlineExpired)
+		));
+
+		let _ = execute(
+			deps.as_mut(),
+			mock_env(),
+			mock_info("admin", &[]),
+			ExecuteMsg::SetNetworkTimeout {
+				network_id: 2.into(),
+				timeout: Some(NetworkTimeout::Blocks { revision: 1, blocks: 100 }),
+			},
+		)
+		.unwrap();
+		// The height of the counterparty is not known yet.
+		assert_eq!(
+			ibc_timeout(deps.as_ref(), &env, 2.into(), None).unwrap(),
+			in_seconds(DEFAULT_NETWORK_TIMEOUT_SECONDS)
+		);
+
+		// Receive a packet sent by the counterparty at its current height.
+		IBC_CHANNEL_NETWORK
+			.save(deps.as_mut().storage, "channel-1".into(), &2.into())
+			.unwrap();
+		let packet = send_packet(deps.as_mut(), 1);
+		let _ = ibc_packet_receive(
+			deps.as_mut(),
+			mock_env(),
+			IbcPacketReceiveMsg::new(packet, Addr::unchecked("relayer")),
+		)
+		.unwrap();
+		let block = IbcTimeoutBlock { revision: 1, height: env.block.height + 100 };
+		assert_eq!(
+			ibc_timeout(deps.as_ref(), &env, 2.into(), None).unwrap(),
+			IbcTimeout::with_block(block)
+		);
+		assert_eq!(
+			ibc_timeout(deps.as_ref(), &env, 2.into(), Some(deadline)).unwrap(),
+			IbcTimeout::with_both(block, Timestamp::from_seconds(deadline))
+		);
+
+		// A height further than the counterparty could have produced is ignored.
+		let mut later = env.clone();
+		later.block.time = env.block.time.plus_seconds(10);
+		record_network_height(deps.as_mut().storage, &later, 2.into(), env.block.height + 1000)
+			.unwrap();
+		assert_eq!(
+			ibc_timeout(deps.as_ref(), &later, 2.into(), None).unwrap(),
+			IbcTimeout::with_block(block)
+		);
+		record_network_height(deps.as_mut().storage, &later, 2.into(), env.block.height + 10)
+			.unwrap();
+		assert_eq!(
+			ibc_timeout(deps.as_ref(), &later, 2.into(), None).unwrap(),
+			IbcTimeout::with_block(IbcTimeoutBlock { revision: 1, height: env.block.height + 110 })
+		);
+
+		// The height expires if no packet is received for a while.
+		later.block.time = later.block.time.plus_seconds(NETWORK_HEIGHT_VALIDITY_SECONDS + 1);
+		assert_eq!(
+			ibc_timeout(deps.as_ref(), &later, 2.into(), None).unwrap(),
+			IbcTimeout::with_timestamp(
+				later.block.time.plus_seconds(DEFAULT_NETWORK_TIMEOUT_SECONDS)
+			)
+		);
+	}
+
+	#[test]
+	fn otp_packets_must_originate_from_the_bridge_network() {
+		let mut deps = setup();
+		BRIDGES
+			.save(
+				deps.as_mut().storage,
+				BridgeId::from(b"otp".to_vec()),
+				&Bridge {
+					security: BridgeSecurity::Probabilistic,
+					address: Addr::unchecked("bridge"),
+					network_id: 2.into(),
+				},
+			)
+			.unwrap();
+		let packet = |network_id: u32| {
+			Binary::from(
+				DefaultXCVMPacket {
+					interpreter: b"remote".to_vec(),
+					user_origin: UserOrigin {
+						network_id: network_id.into(),
+						user_id: b"alice".to_vec().into(),
+					},
+					salt: Vec::new(),
+					program: DefaultXCVMProgram { tag: Vec::new(), instructions: VecDeque::new() },
+					assets: Funds::empty(),
+					height: 1,
+				}
+				.encode(),
+			)
+		};
+		let receive = |deps: DepsMut, network_id| {
+			execute(
+				deps,
+				mock_env(),
+				mock_info("bridge", &[]),
+				ExecuteMsg::OtpReceive {
+					relayer: Addr::unchecked("relayer"),
+					packet: packet(network_id),
+				},
+			)
+			.unwrap()
+		};
+
+		let response = receive(deps.as_mut(), 3);
+		assert_eq!(response.data, Some(XCVMAck::KO.into_vec().into()));
+		assert!(response.messages.is_empty());
+
+		let response = receive(deps.as_mut(), 2);
+		assert_eq!(response.data, None);
+		assert_eq!(response.messages.len(), 1);
+	}
+
+	#[test]
+	fn otp_ko_ack_refunds_the_interpreter_once() {
+		let mut deps = setup();
+		let bridge_id = BridgeId::from(b"otp".to_vec());
+		let bridge = Bridge {
+			security: BridgeSecurity::Probabilistic,
+			address: Addr::unchecked("bridge"),
+			network_id: 2.into(),
+		};
+		BRIDGES.save(deps.as_mut().storage, bridge_id.clone(), &bridge).unwrap();
+		let msg = BridgeMsg {
+			interpreter_origin: InterpreterOrigin {
+				user_origin: UserOrigin { network_id: 1.into(), user_id: b"alice".to_vec().into() },
+				salt: Vec::new(),
+			},
+			network_id: 2.into(),
+			security: BridgeSecurity::Probabilistic,
+			salt: Vec::new(),
+			program: DefaultXCVMProgram { tag: Vec::new(), instructions: VecDeque::new() },
+			assets: Funds::from([(PICA::ID, 100_u128)]),
+			deadline: None,
+			tip: Funds::empty(),
+		};
+		let interpreter = Addr::unchecked(INTERPRETER);
+		let response = execute(
+			deps.as_mut(),
+			mock_env(),
+			mock_info("router", &[]),
+			ExecuteMsg::Bridge { interpreter, msg, fees: None },
+		)
+		.unwrap();
+		let send_packet = wasm_execute(
+			"bridge",
+			&cw_xcvm_common::bridge::ExecuteMsg::SendPacket {
+				network_id: 2.into(),
+				packet: match &response.messages[0].msg {
+					CosmosMsg::Wasm(cosmwasm_std::WasmMsg::Execute { msg, .. }) =>
+						match from_binary(msg).unwrap() {
+							cw_xcvm_common::bridge::ExecuteMsg::SendPacket { packet, .. } => packet,
+						},
+					_ => panic!("expected a bridge message"),
+				},
+				deadline: None,
+				sequence: 1,
+			},
+			Vec::new(),
+		)
+		.unwrap();
+		assert_eq!(response.messages[0].msg, send_packet.into());
+		assert!(OTP_IN_FLIGHT_PACKETS.has(&deps.storage, (bridge_id.clone(), 1)));
+
+		let ack = |deps: DepsMut, ack: XCVMAck| {
+			execute(
+				deps,
+				mock_env(),
+				mock_info("bridge", &[]),
+				ExecuteMsg::OtpAck {
+					relayer: Addr::unchecked("relayer"),
+					sequence: 1,
+					ack: Binary::from(ack.into_vec()),
+				},
+			)
+		};
+		let response = ack(deps.as_mut(), XCVMAck::KO).unwrap();
+		let messages =
+			response.messages.iter().map(|message| message.msg.clone()).collect::<Vec<_>>();
+		assert_eq!(messages, vec![refund()]);
+		assert!(!OTP_IN_FLIGHT_PACKETS.has(&deps.storage, (bridge_id, 1)));
+
+		// A packet is settled only once.
+		assert!(matches!(ack(deps.as_mut(), XCVMAck::KO), Err(ContractError::UnknownPacket)));
+	}
+
+	#[test]
+	fn otp_acks_settle_the_local_assets() {
+		let mut deps = setup();
+		// Only the local identifier of PICA is registered.
+		deps.querier.update_wasm(|query| {
+			let msg = match query {
+				WasmQuery::Smart { msg, .. } => from_binary(msg).unwrap(),
+				_ => panic!("unexpected query"),
+			};
+			SystemResult::Ok(match msg {
+				RegistryQueryMsg::Lookup { asset_id } if AssetId::from(asset_id) == PICA::ID =>
+					ContractResult::Ok(
+						to_binary(&LookupResponse {
+							reference: AssetReference::Virtual {
+								cw20_address: Addr::unchecked(CW20),
+							},
+						})
+						.unwrap(),
+					),
+				RegistryQueryMsg::RemoteAsset { asset_id, network_id } => ContractResult::Ok(
+					to_binary(&RemoteAssetResponse {
+						remote_asset_id: (AssetId::from(asset_id) == PICA::ID &&
+							network_id == 2.into())
+						.then(|| 42.into()),
+					})
+					.unwrap(),
+				),
+				_ => ContractResult::Err("unknown asset".into()),
+			})
+		});
+		let bridge = Bridge {
+			security: BridgeSecurity::Probabilistic,
+			address: Addr::unchecked("bridge"),
+			network_id: 2.into(),
+		};
+		BRIDGES.save(deps.as_mut().storage, b"otp".to_vec().into(), &bridge).unwrap();
+		let bridge_program = |deps: DepsMut| {
+			let msg = BridgeMsg {
+				interpreter_origin: InterpreterOrigin {
+					user_origin: UserOrigin {
+						network_id: 1.into(),
+						user_id: b"alice".to_vec().into(),
+					},
+					salt: Vec::new(),
+				},
+				network_id: 2.into(),
+				security: BridgeSecurity::Probabilistic,
+				salt: Vec::new(),
+				program: DefaultXCVMProgram { tag: Vec::new(), instructions: VecDeque::new() },
+				assets: Funds::from([(PICA::ID, 100_u128)]),
+				deadline: None,
+				tip: Funds::empty(),
+			};
+			let response = execute(
+				deps,
+				mock_env(),
+				mock_info("router", &[]),
+				ExecuteMsg::Bridge { interpreter: Addr::unchecked(INTERPRETER), msg, fees: None },
+			)
+			.unwrap();
+			match &response.messages[0].msg {
+				CosmosMsg::Wasm(cosmwasm_std::WasmMsg::Execute { msg, .. }) =>
+					match from_binary(msg).unwrap() {
+						cw_xcvm_common::bridge::ExecuteMsg::SendPacket { packet, .. } => packet,
+					},
+				_ => panic!("expected a bridge message"),
+			}
+		};
+		// The counterparty receives its own identifier of PICA.
+		let packet: DefaultXCVMPacket = decode_packet(&bridge_program(deps.as_mut())).unwrap();
+		assert_eq!(packet.assets, Funds::from([(AssetId::from(42_u128), 100_u128)]));
+		let _ = bridge_program(deps.as_mut());
+
+		let ack = |deps: DepsMut, sequence: u64, ack: XCVMAck| {
+			let response = execute(
+				deps,
+				mock_env(),
+				mock_info("bridge", &[]),
+				ExecuteMsg::OtpAck {
+					relayer: Addr::unchecked("relayer"),
+					sequence,
+					ack: Binary::from(ack.into_vec()),
+				},
+			)
+			.unwrap();
+			response.messages.into_iter().map(|message| message.msg).collect::<Vec<_>>()
+		};
+		// Escrowed PICA is burnt once delivered and refunded otherwise.
+		let burn =
+			wasm_execute(CW20, &Cw20ExecuteMsg::Burn { amount: 100_u128.into() }, Vec::new())
+				.unwrap()
+				.into();
+		assert_eq!(ack(deps.as_mut(), 1, XCVMAck::OK), vec![burn]);
+		assert_eq!(ack(deps.as_mut(), 2, XCVMAck::KO), vec![refund()]);
+	}
+
+	#[test]
+	fn bridges_networks_and_channels_are_paginated() {
+		let mut deps = setup();
+		let bridge_id = |id: u8| BridgeId::from(vec![id]);
+		for id in 1..=3 {
+			let bridge = Bridge {
+				security: BridgeSecurity::Probabilistic,
+				address: Addr::unchecked(format!("bridge{id}")),
+				network_id: (id as u32).into(),
+			};
+			BRIDGES.save(deps.as_mut().storage, bridge_id(id), &bridge).unwrap();
+			IBC_NETWORK_CHANNEL
+				.save(deps.as_mut().storage, (id as u32).into(), &format!("channel-{id}"))
+				.unwrap();
+		}
+		let bridges = |start_after, limit| -> Vec<BridgeId> {
+			let response: BridgesResponse = from_binary(
+				&query(deps.as_ref(), mock_env(), QueryMsg::Bridges { start_after, limit })
+					.unwrap(),
+			)
+			.unwrap();
+			response.bridges.into_iter().map(|(bridge_id, _)| bridge_id).collect()
+		};
+		assert_eq!(bridges(None, Some(2)), vec![bridge_id(1), bridge_id(2)]);
+		assert_eq!(bridges(Some(bridge_id(2)), Some(2)), vec![bridge_id(3)]);
+		assert_eq!(bridges(None, None).len(), 3);
+
+		for id in 1..=3 {
+			let network = NetworkInfo { id: id.into(), name: format!("network{id}") };
+			NETWORKS.save(deps.as_mut().storage, id.into(), &network).unwrap();
+		}
+		let networks = |start_after, limit| -> Vec<NetworkId> {
+			let response: NetworksResponse = from_binary(
+				&query(deps.as_ref(), mock_env(), QueryMsg::Networks { start_after, limit })
+					.unwrap(),
+			)
+			.unwrap();
+			response.networks.into_iter().map(|network| network.id).collect()
+		};
+		assert_eq!(networks(None, Some(2)), vec![1.into(), 2.into()]);
+		assert_eq!(networks(Some(2.into()), None), vec![3.into()]);
+
+		let channels = |start_after, limit| -> Vec<String> {
+			let response: NetworkChannelsResponse = from_binary(
+				&query(deps.as_ref(), mock_env(), QueryMsg::NetworkChannels { start_after, limit })
+					.unwrap(),
+			)
+			.unwrap();
+			response.channels.into_iter().map(|(_, channel_id)| channel_id).collect()
+		};
+		assert_eq!(channels(None, Some(1)), vec!["channel-1".to_string()]);
+		assert_eq!(
+			channels(Some(1.into()), None),
+			vec!["channel-2".to_string(), "channel-3".to_string()]
+		);
+	}
+
+	#[test]
+	fn the_most_secure_route_is_selected() {
+		let mut deps = setup();
+		let select = |deps: Deps, security| select_bridge(deps, 2.into(), security).unwrap();
+		let ibc = Some(Route::Ibc(CHANNEL.into()));
+		// Without OTP bridge, IBC fulfills every requirement.
+		assert_eq!(select(deps.as_ref(), BridgeSecurity::Insecure), ibc);
+
+		let bridge = |id: u8, security, network_id: u32| {
+			let bridge = Bridge {
+				security,
+				address: Addr::unchecked(format!("bridge{id}")),
+				network_id: network_id.into(),
+			};
+			(BridgeId::from(vec![id]), bridge)
+		};
+		for (bridge_id, bridge) in [
+			bridge(1, BridgeSecurity::Optimistic, 2),
+			bridge(2, BridgeSecurity::Probabilistic, 2),
+			bridge(3, BridgeSecurity::Deterministic, 3),
+		] {
+			BRIDGES.save(deps.as_mut().storage, bridge_id, &bridge).unwrap();
+		}
+		let (bridge_id, probabilistic) = bridge(2, BridgeSecurity::Probabilistic, 2);
+		assert_eq!(
+			select(deps.as_ref(), BridgeSecurity::Insecure),
+			Some(Route::Otp(bridge_id, probabilistic))
+		);
+		assert_eq!(select(deps.as_ref(), BridgeSecurity::Deterministic), ibc);
+
+		// Deterministic OTP bridges are used in the absence of an IBC channel.
+		IBC_NETWORK_CHANNEL.remove(deps.as_mut().storage, 2.into());
+		assert_eq!(select(deps.as_ref(), BridgeSecurity::Deterministic), None);
+		let (bridge_id, deterministic) = bridge(4, BridgeSecurity::Deterministic, 2);
+		BRIDGES.save(deps.as_mut().storage, bridge_id.clone(), &deterministic).unwrap();
+		assert_eq!(
+			select(deps.as_ref(), BridgeSecurity::Deterministic),
+			Some(Route::Otp(bridge_id, deterministic))
+		);
+	}
+
+	#[test]
+	fn channel_close_refunds_in_flight_packets() {
+		let mut deps = setup();
+		let packet = send_packet(deps.as_mut(), 1);
+		reply_sequence(deps.as_mut(), 1);
+		send_packet(deps.as_mut(), 2);
+		reply_sequence(deps.as_mut(), 2);
+
+		let close = mock_ibc_channel_close_init(CHANNEL, XCVM_GATEWAY_IBC_ORDERING, "");
+		let response = ibc_channel_close(deps.as_mut(), mock_env(), close).unwrap();
+		assert_eq!(messages(&response), vec![refund(), refund()]);
+		assert_eq!(reason(&response), Some("channel_closed".into()));
+
+		// The packets are timed out on close, they must not be refunded twice.
+		let response = ibc_packet_timeout(
+			deps.as_mut(),
+			mock_env(),
+			IbcPacketTimeoutMsg::new(packet, Addr::unchecked("relayer")),
+		)
+		.unwrap();
+		assert!(response.messages.is_empty());
+	}
+
+	#[test]
+	fn only_unregistered_assets_are_wrapped() {
+		let mut deps = setup();
+		let router = Addr::unchecked("router");
+		let gateway = mock_env().contract.address;
+		let assets = || Funds::from([(PICA::ID, 100_u128)]);
+		deps.querier.update_wasm(|query| match query {
+			WasmQuery::Raw { .. } => SystemResult::Ok(ContractResult::Ok(Binary::default())),
+			_ => panic!("unexpected query"),
+		});
+		let msgs =
+			mint_counterparty_assets(&deps.as_mut(), &gateway, &router, "registry", assets())
+				.unwrap();
+		assert_eq!(
+			msgs,
+			vec![wasm_execute(
+				&gateway,
+				&ExecuteMsg::CreateWrappedAsset {
+					asset_id: PICA::ID,
+					recipient: router.clone(),
+					amount: Displayed(100),
+				},
+				Vec::new(),
+			)
+			.unwrap()
+			.into()]
+		);
+
+		// A failing registry must not be mistaken for an unknown asset.
+		deps.querier
+			.update_wasm(|_| SystemResult::Ok(ContractResult::Err("out of gas".into())));
+		assert!(mint_counterparty_assets(&deps.as_mut(), &gateway, &router, "registry", assets())
+			.is_err());
+	}
+
+	#[test]
+	fn wrapped_asset_is_registered_once_instantiated() {
+		let mut deps = setup();
+		let gateway = mock_env().contract.address;
+		let create = ExecuteMsg::CreateWrappedAsset {
+			asset_id: PICA::ID,
+			recipient: Addr::unchecked("router"),
+			amount: Displayed(100),
+		};
+		assert!(matches!(
+			execute(deps.as_mut(), mock_env(), mock_info("router", &[]), create.clone()),
+			Err(ContractError::NotAuthorized)
+		));
+		assert!(matches!(
+			execute(deps.as_mut(), mock_env(), mock_info(gateway.as_str(), &[]), create.clone()),
+			Err(ContractError::UnsupportedAsset)
+		));
+
+		CONFIG
+			.update(deps.as_mut().storage, |config| -> StdResult<_> {
+				Ok(Config {
+					wrapped_asset_code_id: Some(42),
+					wrapped_asset_prefix: Some("wrapped".into()),
+					..config
+				})
+			})
+			.unwrap();
+		let unknown = ExecuteMsg::CreateWrappedAsset {
+			asset_id: 1234.into(),
+			recipient: Addr::unchecked("router"),
+			amount: Displayed(100),
+		};
+		assert!(matches!(
+			execute(deps.as_mut(), mock_env(), mock_info(gateway.as_str(), &[]), unknown),
+			Err(ContractError::UnknownAssetInfo)
+		));
+		let response =
+			execute(deps.as_mut(), mock_env(), mock_info(gateway.as_str(), &[]), create.clone())
+				.unwrap();
+		let (msg, label) = match &response.messages[0].msg {
+			CosmosMsg::Wasm(cosmwasm_std::WasmMsg::Instantiate {
+				code_id: 42, msg, label, ..
+			}) => (from_binary::<Cw20InstantiateMsg>(msg).unwrap(), label),
+			_ => panic!("expected an instantiation"),
+		};
+		assert_eq!(*label, format!("wrapped-wrapped-asset-{}", u128::from(PICA::ID)));
+		assert_eq!(msg.symbol, "wrappedPICA");
+		assert_eq!(msg.decimals, 12);
+
+		let event = Event::new("instantiate").add_attribute("_contract_address", "wrapped");
+		let response = reply(
+			deps.as_mut(),
+			mock_env(),
+			Reply {
+				id: XCVM_GATEWAY_INSTANTIATE_WRAPPED_ASSET_REPLY_ID,
+				result: SubMsgResult::Ok(SubMsgResponse { events: vec![event], data: None }),
+			},
+		)
+		.unwrap();
+		assert_eq!(
+			response.messages[0].msg,
+			wasm_execute(
+				"registry",
+				&cw_xcvm_asset_registry::msg::ExecuteMsg::RegisterAsset {
+					asset_id: PICA::ID.into(),
+					reference: AssetReference::Virtual { cw20_address: Addr::unchecked("wrapped") },
+					origin_network: None,
+				},
+				Vec::new(),
+			)
+			.unwrap()
+			.into()
+		);
+		assert!(PENDING_WRAPPED_ASSET.may_load(&deps.storage).unwrap().is_none());
+
+		// The gateway must be able to register the wrapped asset.
+		deps.querier.update_wasm(|_| {
+			SystemResult::Ok(ContractResult::Ok(
+				to_binary(&RegistrarsResponse { registrars: Vec::new() }).unwrap(),
+			))
+		});
+		assert!(matches!(
+			execute(deps.as_mut(), mock_env(), mock_info(gateway.as_str(), &[]), create),
+			Err(ContractError::NotRegistrar)
+		));
+	}
+
+	#[test]
+	fn query_snapshot_includes_cw20_balances() {
+		let mut deps = setup();
+		deps.querier.update_balance(INTERPRETER, vec![Coin::new(5, "uatom")]);
+		deps.querier.update_wasm(|query| {
+			SystemResult::Ok(match query {
+				WasmQuery::Raw { contract_addr, .. } if contract_addr == "router" =>
+					ContractResult::Ok(
+						to_binary(&cw_xcvm_router::state::Interpreter {
+							address: Some(Addr::unchecked(INTERPRETER)),
+							security: BridgeSecurity::Deterministic,
+						})
+						.unwrap(),
+					),
+				WasmQuery::Smart { contract_addr, msg } if contract_addr == INTERPRETER =>
+					match from_binary(msg).unwrap() {
+						cw_xcvm_interpreter::msg::QueryMsg::Register(Register::Ip) =>
+							ContractResult::Ok(to_binary(&3_u32).unwrap()),
+						_ => ContractResult::Err("register not set".into()),
+					},
+				WasmQuery::Smart { contract_addr, msg } if contract_addr == "registry" =>
+					match from_binary(msg).unwrap() {
+						RegistryQueryMsg::Assets {
+							start_after: None,
+							limit: Some(MAX_QUERY_LIMIT),
+						} => ContractResult::Ok(
+							to_binary(&AssetsResponse {
+								assets: vec![
+									(1.into(), AssetReference::Native { denom: "uatom".into() }),
+									(
+										2.into(),
+										AssetReference::Virtual {
+											cw20_address: Addr::unchecked(CW20),
+										},
+									),
+									(
+										3.into(),
+										AssetReference::Virtual {
+											cw20_address: Addr::unchecked("empty"),
+										},
+									),
+								],
+							})
+							.unwrap(),
+						),
+						_ => ContractResult::Ok(
+							to_binary(&AssetsResponse { assets: Vec::new() }).unwrap(),
+						),
+					},
+				WasmQuery::Smart { contract_addr, .. } => ContractResult::Ok(
+					to_binary(&BalanceResponse {
+						balance: if contract_addr == CW20 { 7_u128 } else { 0 }.into(),
+					})
+					.unwrap(),
+				),
+				_ => panic!("unexpected query"),
+			})
+		});
+		let result = query_interpreter(
+			deps.as_ref(),
+			QueryPacket {
+				interpreter: b"querier".to_vec(),
+				user_origin: UserOrigin { network_id: 2.into(), user_id: b"alice".to_vec().into() },
+				salt: Vec::new(),
+				query_id: 0,
+			},
+		)
+		.unwrap();
+		assert_eq!(result.ip, 3);
+		assert_eq!(
+			result.balances,
+			vec![("uatom".into(), Displayed(5)), (format!("cw20:{CW20}"), Displayed(7))]
+		);
+	}
+
+	#[test]
+	fn query_results_are_delivered_without_failing_the_packet() {
+		let mut deps = setup();
+		let msg = BridgeQueryMsg {
+			interpreter_origin: InterpreterOrigin {
+				user_origin: UserOrigin { network_id: 1.into(), user_id: b"alice".to_vec().into() },
+				salt: Vec::new(),
+			},
+			network_id: 2.into(),
+			salt: Vec::new(),
+			query_id: 5,
+		};
+		let response = execute(
+			deps.as_mut(),
+			mock_env(),
+			mock_info("router", &[]),
+			ExecuteMsg::BridgeQuery { interpreter: Addr::unchecked(INTERPRETER), msg },
+		)
+		.unwrap();
+		let (data, timeout) = match &response.messages[0].msg {
+			CosmosMsg::Ibc(IbcMsg::SendPacket { data, timeout, .. }) =>
+				(data.clone(), timeout.clone()),
+			_ => panic!("expected an IBC packet"),
+		};
+		let endpoint = |channel_id: &str| IbcEndpoint {
+			port_id: "wasm.gateway".into(),
+			channel_id: channel_id.into(),
+		};
+		let packet = IbcPacket::new(data, endpoint(CHANNEL), endpoint("channel-1"), 1, timeout);
+		let response = ibc_packet_timeout(
+			deps.as_mut(),
+			mock_env(),
+			IbcPacketTimeoutMsg::new(packet, Addr::unchecked("relayer")),
+		)
+		.unwrap();
+		assert_eq!(
+			response.messages,
+			vec![SubMsg::reply_on_error(
+				wasm_execute(
+					INTERPRETER,
+					&cw_xcvm_interpreter::msg::ExecuteMsg::SetQueryResult {
+						query_id: 5,
+						result: Err("The query timed out.".into()),
+					},
+					Vec::new(),
+				)
+				.unwrap(),
+				XCVM_GATEWAY_QUERY_RESULT_REPLY_ID,
+			)]
+		);
+
+		// The result is dropped if the interpreter refuses it.
+		let response = reply(
+			deps.as_mut(),
+			mock_env(),
+			Reply {
+				id: XCVM_GATEWAY_QUERY_RESULT_REPLY_ID,
+				result: SubMsgResult::Err("unauthorized".into()),
+			},
+		)
+		.unwrap();
+		assert!(response.messages.is_empty());
+	}
+}
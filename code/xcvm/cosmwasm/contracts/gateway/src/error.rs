@@ -38,4 +38,24 @@ pub enum ContractError {
 	UnknownReply,
 	#[error("The provided channel has not been previously opened.")]
 	UnknownChannel,
+	#[error("No interpreter has been instantiated for the queried origin.")]
+	UnknownInterpreter,
+	#[error("The network identifier or name is already registered.")]
+	NetworkAlreadyRegistered,
+	#[error("The deadline of the program has already passed.")]
+	DeadlineExpired,
+	#[error("The bridge identifier or address is already registered.")]
+	BridgeAlreadyRegistered,
+	#[error("The rate limit of the asset through this channel or bridge has been reached.")]
+	RateLimitExceeded,
+	#[error("The user origin of the packet is not on the network it has been received from.")]
+	InvalidUserOrigin,
+	#[error("No packet is in flight through the bridge with this sequence.")]
+	UnknownPacket,
+	#[error("The gateway is not a registrar of the asset-registry.")]
+	NotRegistrar,
+	#[error("The name and decimals of the asset are unknown.")]
+	UnknownAssetInfo,
+	#[error("The symbol of the wrapped asset is not a valid CW20 symbol.")]
+	InvalidWrappedAssetSymbol,
 }
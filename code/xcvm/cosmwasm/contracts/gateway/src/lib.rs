@@ -2,4 +2,5 @@ pub mod common;
 pub mod contract;
 pub mod error;
 pub mod msg;
+pub mod rate_limit;
 pub mod state;
@@ -1,6 +1,14 @@
-use crate::state::Config;
+use crate::state::{ChannelInfo, Config, RateLimitState};
+use cosmwasm_std::Addr;
+use cw20::{Cw20Coin, MinterResponse};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use xcvm_core::NetworkId;
+
+pub use cw_xcvm_common::gateway::{
+	BridgeResponse, BridgesResponse, NetworkChannelResponse, NetworkChannelsResponse,
+	NetworkResponse, NetworkTimeoutResponse, NetworksResponse, QueryMsg,
+};
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct InstantiateMsg {
@@ -11,4 +19,34 @@ pub struct InstantiateMsg {
 pub struct MigrateMsg {}
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
-pub enum QueryMsg {}
+pub struct ConfigResponse {
+	pub config: Config,
+	/// Set once the router instantiated by the gateway replied.
+	pub router: Option<Addr>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ChannelInfoResponse {
+	pub info: ChannelInfo,
+	pub network_id: Option<NetworkId>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RateLimitResponse {
+	pub rate_limit: RateLimitState,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RateLimitsResponse {
+	pub rate_limits: Vec<RateLimitState>,
+}
+
+/// Instantiate message of the `cw20-base` contract, used for the wrapped assets.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Cw20InstantiateMsg {
+	pub name: String,
+	pub symbol: String,
+	pub decimals: u8,
+	pub initial_balances: Vec<Cw20Coin>,
+	pub mint: Option<MinterResponse>,
+}
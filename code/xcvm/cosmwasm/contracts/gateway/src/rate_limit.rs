@@ -0,0 +1,187 @@
+use crate::{
+	error::ContractError,
+	state::{RateLimitFlow, RateLimitState, RATE_LIMITS},
+};
+use cosmwasm_std::{Binary, StdResult, Storage};
+use cw_xcvm_common::gateway::{RateLimit, RateLimitPath};
+use xcvm_core::{AssetId, Displayed, Funds};
+
+/// Direction of a transfer through a [`RateLimitPath`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Flow {
+	Inbound,
+	Outbound,
+}
+
+/// Storage key of a path, unique across the IBC channels and the OTP bridges.
+pub fn path_key(path: &RateLimitPath) -> String {
+	match path {
+		RateLimitPath::Ibc { channel_id } => format!("ibc/{channel_id}"),
+		RateLimitPath::Bridge { bridge_id } =>
+			format!("bridge/{}", Binary::from(bridge_id.as_ref())),
+	}
+}
+
+/// Set or lift (`None`) the rate limit of an asset through a path. The amounts already
+/// transferred in the current window are kept when the limit is updated.
+pub fn set_rate_limit(
+	storage: &mut dyn Storage,
+	path: RateLimitPath,
+	asset_id: AssetId,
+	limit: Option<RateLimit>,
+) -> StdResult<()> {
+	let key = (path_key(&path), u128::from(asset_id));
+	match limit {
+		Some(limit) => {
+			let flow =
+				RATE_LIMITS.may_load(storage, key.clone())?.map(|state| state.flow).unwrap_or(
+					RateLimitFlow { window_start: 0, inflow: Displayed(0), outflow: Displayed(0) },
+				);
+			RATE_LIMITS.save(storage, key, &RateLimitState { path, asset_id, limit, flow })
+		},
+		None => {
+			RATE_LIMITS.remove(storage, key);
+			Ok(())
+		},
+	}
+}
+
+/// Account the `assets` transferred through a path at block time `now` (in seconds), failing if
+/// the limit of any of them would be exceeded within the current window. Assets without a limit
+/// are not accounted. Nothing is accounted on failure.
+pub fn record_flow(
+	storage: &mut dyn Storage,
+	now: u64,
+	path: &RateLimitPath,
+	flow: Flow,
+	assets: &Funds<Displayed<u128>>,
+) -> Result<(), ContractError> {
+	let path_key = path_key(path);
+	let mut states = Vec::with_capacity(assets.0.len());
+	for (asset_id, Displayed(amount)) in assets.0.iter() {
+		let key = (path_key.clone(), u128::from(*asset_id));
+		let mut state = match RATE_LIMITS.may_load(storage, key.clone())? {
+			Some(state) => state,
+			None => continue,
+		};
+		if now >= state.flow.window_start.saturating_add(state.limit.window) {
+			state.flow =
+				RateLimitFlow { window_start: now, inflow: Displayed(0), outflow: Displayed(0) };
+		}
+		let (Displayed(current), Displayed(max)) = match flow {
+			Flow::Inbound => (&mut state.flow.inflow, state.limit.max_inflow),
+			Flow::Outbound => (&mut state.flow.outflow, state.limit.max_outflow),
+		};
+		let total = current.checked_add(*amount).ok_or(ContractError::ArithmeticOverflow)?;
+		if total > max {
+			return Err(ContractError::RateLimitExceeded)
+		}
+		*current = total;
+		states.push((key, state));
+	}
+	for (key, state) in states {
+		RATE_LIMITS.save(storage, key, &state)?;
+	}
+	Ok(())
+}
+
+/// Release the `assets` accounted by [`record_flow`] at block time `recorded_at` (in seconds),
+/// once their transfer failed. Assets accounted in a previous window are already released.
+pub fn release_flow(
+	storage: &mut dyn Storage,
+	recorded_at: u64,
+	path: &RateLimitPath,
+	flow: Flow,
+	assets: &Funds<Displayed<u128>>,
+) -> StdResult<()> {
+	let path_key = path_key(path);
+	for (asset_id, Displayed(amount)) in assets.0.iter() {
+		let key = (path_key.clone(), u128::from(*asset_id));
+		let mut state = match RATE_LIMITS.may_load(storage, key.clone())? {
+			Some(state) if recorded_at >= state.flow.window_start => state,
+			_ => continue,
+		};
+		let Displayed(current) = match flow {
+			Flow::Inbound => &mut state.flow.inflow,
+			Flow::Outbound => &mut state.flow.outflow,
+		};
+		*current = current.saturating_sub(*amount);
+		RATE_LIMITS.save(storage, key, &state)?;
+	}
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use cosmwasm_std::testing::MockStorage;
+	use xcvm_core::{Asset, PICA, USDC};
+
+	fn channel() -> RateLimitPath {
+		RateLimitPath::Ibc { channel_id: "channel-0".into() }
+	}
+
+	#[test]
+	fn limits_the_flow_per_window() {
+		let mut storage = MockStorage::new();
+		let limit =
+			RateLimit { window: 100, max_inflow: Displayed(50), max_outflow: Displayed(1000) };
+		set_rate_limit(&mut storage, channel(), PICA::ID, Some(limit)).unwrap();
+		let pica = |amount: u128| Funds::from([(PICA::ID, amount)]);
+
+		record_flow(&mut storage, 10, &channel(), Flow::Inbound, &pica(30)).unwrap();
+		assert!(matches!(
+			record_flow(&mut storage, 20, &channel(), Flow::Inbound, &pica(30)),
+			Err(ContractError::RateLimitExceeded)
+		));
+		// Both directions are limited independently.
+		record_flow(&mut storage, 20, &channel(), Flow::Outbound, &pica(1000)).unwrap();
+		// Other paths and assets are not limited.
+		let bridge = RateLimitPath::Bridge { bridge_id: vec![1].into() };
+		record_flow(&mut storage, 20, &bridge, Flow::Inbound, &pica(30)).unwrap();
+		record_flow(&mut storage, 20, &channel(), Flow::Inbound, &Funds::from([(USDC::ID, 99)]))
+			.unwrap();
+		// The window started with the first transfer.
+		record_flow(&mut storage, 110, &channel(), Flow::Inbound, &pica(50)).unwrap();
+
+		set_rate_limit(&mut storage, channel(), PICA::ID, None).unwrap();
+		record_flow(&mut storage, 110, &channel(), Flow::Inbound, &pica(1000)).unwrap();
+	}
+
+	#[test]
+	fn exceeding_any_asset_accounts_none() {
+		let mut storage = MockStorage::new();
+		let limit =
+			RateLimit { window: 100, max_inflow: Displayed(50), max_outflow: Displayed(50) };
+		set_rate_limit(&mut storage, channel(), PICA::ID, Some(limit)).unwrap();
+		set_rate_limit(&mut storage, channel(), USDC::ID, Some(limit)).unwrap();
+		let assets = Funds::from([(PICA::ID, 30_u128), (USDC::ID, 60_u128)]);
+		assert!(matches!(
+			record_flow(&mut storage, 10, &channel(), Flow::Inbound, &assets),
+			Err(ContractError::RateLimitExceeded)
+		));
+		let key = (path_key(&channel()), u128::from(PICA::ID));
+		assert_eq!(RATE_LIMITS.load(&storage, key).unwrap().flow.inflow, Displayed(0));
+	}
+
+	#[test]
+	fn released_flow_is_available_again() {
+		let mut storage = MockStorage::new();
+		let limit =
+			RateLimit { window: 100, max_inflow: Displayed(50), max_outflow: Displayed(50) };
+		set_rate_limit(&mut storage, channel(), PICA::ID, Some(limit)).unwrap();
+		let pica = |amount: u128| Funds::from([(PICA::ID, amount)]);
+
+		record_flow(&mut storage, 10, &channel(), Flow::Outbound, &pica(50)).unwrap();
+		release_flow(&mut storage, 10, &channel(), Flow::Outbound, &pica(50)).unwrap();
+		record_flow(&mut storage, 20, &channel(), Flow::Outbound, &pica(50)).unwrap();
+
+		// Releasing a transfer of a previous window doesn't affect the current one.
+		record_flow(&mut storage, 110, &channel(), Flow::Outbound, &pica(50)).unwrap();
+		release_flow(&mut storage, 20, &channel(), Flow::Outbound, &pica(50)).unwrap();
+		assert!(matches!(
+			record_flow(&mut storage, 120, &channel(), Flow::Outbound, &pica(1)),
+			Err(ContractError::RateLimitExceeded)
+		));
+	}
+}
@@ -1,8 +1,9 @@
-use cosmwasm_std::{Addr, IbcEndpoint};
+use cosmwasm_std::{Addr, IbcEndpoint, Timestamp};
 use cw_storage_plus::{Item, Map};
+use cw_xcvm_common::gateway::{Bridge, NetworkTimeout, RateLimit, RateLimitPath, RelayerFees};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-use xcvm_core::{BridgeId, BridgeSecurity, NetworkId};
+use xcvm_core::{AssetId, BridgeId, Displayed, Funds, NetworkId, NetworkInfo, UserOrigin};
 
 pub type ChannelId = String;
 
@@ -18,14 +19,17 @@ pub struct Config {
 	pub network_id: NetworkId,
 	/// The admin which is allowed to update the bridge list.
 	pub admin: String,
+	/// Code of the `cw20-base` contracts instantiated to represent the assets of other networks.
+	/// Assets without a local reference can't be received if unset.
+	#[serde(default)]
+	pub wrapped_asset_code_id: Option<u64>,
+	/// Symbol of the wrapped assets, prefixing the label of their contracts. Defaults to
+	/// [`DEFAULT_WRAPPED_ASSET_PREFIX`].
+	#[serde(default)]
+	pub wrapped_asset_prefix: Option<String>,
 }
 
-/// Bridge following the OTP specs.
-#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
-pub struct Bridge {
-	pub security: BridgeSecurity,
-	pub address: Addr,
-}
+pub const DEFAULT_WRAPPED_ASSET_PREFIX: &str = "xcvm";
 
 /// Information associated with an IBC channel.
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -40,10 +44,83 @@ pub struct ChannelInfo {
 
 pub const ROUTER: Item<Addr> = Item::new("router");
 pub const CONFIG: Item<Config> = Item::new("config");
+/// OTP bridges registered by the admin.
 pub const BRIDGES: Map<BridgeId, Bridge> = Map::new("bridges");
 
+/// Networks registered at runtime, see [`xcvm_core::Registry`].
+pub const NETWORKS: Map<NetworkId, NetworkInfo> = Map::new("networks");
+
+/// Timeouts configured by the admin, see [`cw_xcvm_common::gateway::DEFAULT_NETWORK_TIMEOUT`].
+pub const NETWORK_TIMEOUTS: Map<NetworkId, NetworkTimeout> = Map::new("network_timeouts");
+
+/// Latest block height of a network, carried by the packets received from it.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct NetworkHeight {
+	pub height: u64,
+	/// Block time at which the height was received.
+	pub received_at: Timestamp,
+}
+
+pub const NETWORK_HEIGHTS: Map<NetworkId, NetworkHeight> = Map::new("network_heights");
+
+/// Outbound XCVM packet awaiting its acknowledgement.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct InFlightPacket {
+	/// The interpreter that spawned the program, refunded if the packet fails.
+	pub interpreter: Addr,
+	pub user_origin: UserOrigin,
+	/// The assets escrowed by the gateway.
+	pub assets: Funds<Displayed<u128>>,
+	/// Block time, in seconds, at which the packet has been sent. Its outflow is released from
+	/// the rate limits if it's refunded within the same window.
+	#[serde(default)]
+	pub sent_at: u64,
+	/// The fees escrowed by the gateway, paid to the relayer once the packet is delivered.
+	#[serde(default)]
+	pub fees: Option<RelayerFees>,
+}
+
+/// Amounts of an asset transferred through a path during the current window of its
+/// [`RateLimit`].
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RateLimitFlow {
+	/// Block time, in seconds, at which the window started.
+	pub window_start: u64,
+	pub inflow: Displayed<u128>,
+	pub outflow: Displayed<u128>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RateLimitState {
+	pub path: RateLimitPath,
+	pub asset_id: AssetId,
+	pub limit: RateLimit,
+	pub flow: RateLimitFlow,
+}
+
+/// Rate limits configured by the admin, by path key (see [`crate::rate_limit::path_key`]) and
+/// asset.
+pub const RATE_LIMITS: Map<(String, u128), RateLimitState> = Map::new("rate_limits");
+
 pub const IBC_CHANNEL_INFO: Map<ChannelId, ChannelInfo> = Map::new("ibc_channel_info");
 
+/// Asset being wrapped, registered in the asset-registry once its contract is instantiated.
+pub const PENDING_WRAPPED_ASSET: Item<AssetId> = Item::new("pending_wrapped_asset");
+
+/// Packet being sent, recorded in [`IBC_IN_FLIGHT_PACKETS`] once its sequence is known.
+pub const IBC_PENDING_PACKET: Item<InFlightPacket> = Item::new("ibc_pending_packet");
+
+/// Packets sent but not yet acknowledged nor timed out, by source channel and sequence.
+pub const IBC_IN_FLIGHT_PACKETS: Map<(ChannelId, u64), InFlightPacket> =
+	Map::new("ibc_in_flight_packets");
+
+/// Next sequence of the packets sent through the OTP bridges.
+pub const OTP_NEXT_SEQUENCE: Map<BridgeId, u64> = Map::new("otp_next_sequence");
+
+/// Packets sent through the OTP bridges but not yet acknowledged, by bridge and sequence.
+pub const OTP_IN_FLIGHT_PACKETS: Map<(BridgeId, u64), InFlightPacket> =
+	Map::new("otp_in_flight_packets");
+
 /// According to XCVM protocol, it's always a 1:1 mapping between [`NetworkId`] and [`ChannelId`]
 pub const IBC_NETWORK_CHANNEL: Map<NetworkId, ChannelId> = Map::new("ibc_network_channel");
 pub const IBC_CHANNEL_NETWORK: Map<ChannelId, NetworkId> = Map::new("ibc_channel_network");
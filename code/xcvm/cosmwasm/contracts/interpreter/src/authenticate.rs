@@ -1,4 +1,7 @@
-use crate::{error::ContractError, state::OWNERS};
+use crate::{
+	error::ContractError,
+	state::{CONFIG, OWNERS},
+};
 use cosmwasm_std::{Addr, Deps};
 
 /// Authenticated token, MUST be private and kept in this module.
@@ -19,3 +22,14 @@ pub fn ensure_owner(
 		Err(ContractError::NotAuthorized)
 	}
 }
+
+/// Ensure that the caller is the gateway of the interpreter.
+/// Only used for the operations the gateway dispatches on its own, such as delivering query
+/// results.
+pub fn ensure_gateway(deps: Deps, sender: Addr) -> Result<Authenticated, ContractError> {
+	if CONFIG.load(deps.storage)?.gateway_address == sender {
+		Ok(Authenticated(()))
+	} else {
+		Err(ContractError::NotAuthorized)
+	}
+}
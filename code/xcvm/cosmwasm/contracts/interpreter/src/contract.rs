@@ -1,28 +1,39 @@
 extern crate alloc;
 
 use crate::{
-	authenticate::{ensure_owner, Authenticated},
+	authenticate::{ensure_gateway, ensure_owner, Authenticated},
 	error::ContractError,
 	msg::{ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg},
-	state::{Config, CONFIG, IP_REGISTER, OWNERS, RELAYER_REGISTER, RESULT_REGISTER},
+	state::{
+		CatchBlock, Config, LegacyConfig, TryFrame, CONFIG, IP_REGISTER, LEGACY_CONFIG, OWNERS,
+		PENDING_QUERY, QUERY_COUNT, RELAYER_REGISTER, RESULT_REGISTER, TRY_FRAMES,
+	},
+	trace::{
+		query_trace_steps, query_traces, start_trace, trace_error, trace_instruction,
+		trace_movement, trace_result,
+	},
 };
 use alloc::{borrow::Cow, collections::VecDeque};
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{
 	to_binary, wasm_execute, Addr, BankMsg, Binary, CanonicalAddr, Coin, CosmosMsg, Deps, DepsMut,
-	Env, Event, MessageInfo, QueryRequest, Reply, Response, StdError, StdResult, SubMsg, WasmQuery,
+	Env, Event, MessageInfo, QueryRequest, Reply, Response, StdError, StdResult, SubMsg,
+	SubMsgResponse, WasmQuery,
 };
 use cw2::set_contract_version;
 use cw20::{BalanceResponse, Cw20Contract, Cw20ExecuteMsg, Cw20QueryMsg};
 use cw_utils::ensure_from_older_version;
-use cw_xcvm_asset_registry::{contract::external_query_lookup_asset, msg::AssetReference};
-use cw_xcvm_common::shared::{encode_base64, BridgeMsg};
+use cw_xcvm_asset_registry::{
+	contract::{external_query_assets, external_query_lookup_asset},
+	msg::AssetReference,
+};
+use cw_xcvm_common::shared::{encode_base64, BridgeMsg, BridgeQueryMsg};
 use cw_xcvm_utils::DefaultXCVMProgram;
 use num::Zero;
 use xcvm_core::{
-	apply_bindings, cosmwasm::*, Amount, BindingValue, BridgeSecurity, Destination, Displayed,
-	Funds, NetworkId, Register,
+	apply_bindings, cosmwasm::*, Amount, BindingValue, BridgeSecurity, Condition, Destination,
+	Displayed, Funds, NetworkId, QueryResult, Register, UserOrigin, MAX_PARTS,
 };
 
 type XCVMInstruction = xcvm_core::Instruction<NetworkId, Vec<u8>, CanonicalAddr, Funds>;
@@ -32,6 +43,8 @@ const CONTRACT_NAME: &str = "composable:xcvm-interpreter";
 const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 const CALL_ID: u64 = 1;
 const SELF_CALL_ID: u64 = 2;
+const TRY_ID: u64 = 3;
+const CATCH_ID: u64 = 4;
 pub const XCVM_INTERPRETER_EVENT_PREFIX: &str = "xcvm.interpreter";
 pub const XCVM_INTERPRETER_EVENT_DATA_ORIGIN: &str = "data";
 
@@ -52,6 +65,7 @@ pub fn instantiate(
 		router_address,
 		gateway_address,
 		interpreter_origin: msg.interpreter_origin,
+		network_id: msg.network_id,
 	};
 	CONFIG.save(deps.storage, &config)?;
 	// Save the caller as owner, in most cases, it is the `XCVM router`
@@ -70,8 +84,12 @@ pub fn execute(
 	info: MessageInfo,
 	msg: ExecuteMsg,
 ) -> Result<Response, ContractError> {
-	// Only owners can execute entrypoints of the interpreter
-	let token = ensure_owner(deps.as_ref(), &env.contract.address, info.sender.clone())?;
+	let token = match msg {
+		// Query results are delivered by the gateway, which is not an owner of the interpreter
+		ExecuteMsg::SetQueryResult { .. } => ensure_gateway(deps.as_ref(), info.sender.clone())?,
+		// Only owners can execute entrypoints of the interpreter
+		_ => ensure_owner(deps.as_ref(), &env.contract.address, info.sender.clone())?,
+	};
 	match msg {
 		ExecuteMsg::Execute { relayer, program } =>
 			initiate_execution(token, deps, env, relayer, program),
@@ -88,6 +106,9 @@ pub fn execute(
 		ExecuteMsg::AddOwners { owners } => add_owners(token, deps, owners),
 
 		ExecuteMsg::RemoveOwners { owners } => Ok(remove_owners(token, deps, owners)),
+
+		ExecuteMsg::SetQueryResult { query_id, result } =>
+			set_query_result(token, deps, query_id, result),
 	}
 }
 
@@ -95,6 +116,30 @@ pub fn execute(
 pub fn migrate(deps: DepsMut, env: Env, msg: MigrateMsg) -> Result<Response, ContractError> {
 	let _ = ensure_from_older_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
 
+	// Interpreters instantiated before their network was recorded must be given one.
+	match (msg.network_id, CONFIG.load(deps.storage)) {
+		(Some(network_id), _) => {
+			let LegacyConfig {
+				gateway_address,
+				registry_address,
+				router_address,
+				interpreter_origin,
+			} = LEGACY_CONFIG.load(deps.storage)?;
+			CONFIG.save(
+				deps.storage,
+				&Config {
+					gateway_address,
+					registry_address,
+					router_address,
+					interpreter_origin,
+					network_id,
+				},
+			)?;
+		},
+		(None, Ok(_)) => {},
+		(None, Err(_)) => return Err(ContractError::MissingNetworkId),
+	}
+
 	// Already only callable by the admin of the contract, so no need to `ensure_owner`
 	let token = ensure_owner(deps.as_ref(), &env.contract.address, env.contract.address.clone())?;
 	let _ = add_owners(token, deps, msg.owners)?;
@@ -117,10 +162,23 @@ fn initiate_execution(
 	// Reset instruction pointer to zero.
 	IP_REGISTER.save(deps.storage, &0)?;
 
+	// The results of the queries of the previous programs must not overwrite the registers of this
+	// one.
+	PENDING_QUERY.remove(deps.storage);
+
 	// Set the new relayer, note that the relayer that is in the register is always the last relayer
 	// that executed a program.
 	RELAYER_REGISTER.save(deps.storage, &relayer)?;
 
+	let Config { interpreter_origin, .. } = CONFIG.load(deps.storage)?;
+	start_trace(
+		deps.storage,
+		program.tag.clone(),
+		interpreter_origin.salt,
+		relayer.clone(),
+		env.block.height,
+	)?;
+
 	Ok(Response::default().add_submessage(SubMsg::reply_on_error(
 		wasm_execute(
 			env.contract.address,
@@ -158,11 +216,42 @@ fn remove_owners(_: Authenticated, deps: DepsMut, owners: Vec<Addr>) -> Response
 	Response::default().add_event(event)
 }
 
+/// Store the outcome of a `Query` instruction in the [`RESULT_REGISTER`].
+/// A successful query is stored as the JSON encoded [`QueryResult`] in the response data.
+/// The outcome of a query is dropped if it is not the last query of the last executed program.
+fn set_query_result(
+	_: Authenticated,
+	deps: DepsMut,
+	query_id: u64,
+	result: Result<QueryResult, String>,
+) -> Result<Response, ContractError> {
+	let pending = PENDING_QUERY.may_load(deps.storage)? == Some(query_id);
+	let event = Event::new(XCVM_INTERPRETER_EVENT_PREFIX)
+		.add_attribute("action", "query.result")
+		.add_attribute("query_id", format!("{query_id}"))
+		.add_attribute("success", format!("{}", result.is_ok()))
+		.add_attribute("stale", format!("{}", !pending));
+	if !pending {
+		return Ok(Response::default().add_event(event))
+	}
+	PENDING_QUERY.remove(deps.storage);
+	let result = match result {
+		Ok(query_result) =>
+			Ok(SubMsgResponse { events: Vec::new(), data: Some(to_binary(&query_result)?) }),
+		Err(e) => Err(e),
+	};
+	RESULT_REGISTER.save(deps.storage, &result)?;
+	Ok(Response::default().add_event(event))
+}
+
 /// Execute a [`XCVMProgram`].
 /// The function will execute the program instructions one by one.
 /// If the program contains a [`XCVMInstruction::Call`], the execution is suspended and resumed
-/// after having executed the call.
-/// The [`IP_REGISTER`] is updated accordingly.
+/// after having executed the call. The body of a [`XCVMInstruction::Try`] is executed the same way,
+/// in a sub-message reverted on failure.
+/// The [`IP_REGISTER`] is updated accordingly. The instructions of the body and catch block of a
+/// `Try` are numbered from the position following the `Try`, which is restored once they are
+/// executed.
 /// A final `executed` event is yield whenever a program come to completion (all it's instructions
 /// has been executed).
 pub fn handle_execute_step(
@@ -173,13 +262,13 @@ pub fn handle_execute_step(
 	program: XCVMProgram,
 ) -> Result<Response, ContractError> {
 	let mut response = Response::new();
-	let instruction_len = program.instructions.len();
-	let mut instruction_iter = program.instructions.into_iter().enumerate();
+	let mut instructions = program.instructions;
 	let mut ip = IP_REGISTER.load(deps.storage)?;
-	while let Some((index, instruction)) = instruction_iter.next() {
+	while let Some(instruction) = instructions.pop_front() {
+		trace_instruction(deps.storage, ip, &instruction)?;
 		response = match instruction {
 			XCVMInstruction::Call { bindings, encoded } => {
-				if index >= instruction_len - 1 {
+				if instructions.is_empty() {
 					// If the call is the final instruction, do not yield execution
 					interpret_call(deps.as_ref(), &env, bindings, encoded, ip as usize, response)?
 				} else {
@@ -190,10 +279,14 @@ pub fn handle_execute_step(
 					//    rest of the instructions as XCVM program. This will make sure that
 					//    previous call instruction will run first, then the rest of the program
 					//    will run.
-					let response =
-						interpret_call(deps.as_ref(), &env, bindings, encoded, index, response)?;
-					let instructions: VecDeque<XCVMInstruction> =
-						instruction_iter.map(|(_, instr)| instr).collect();
+					let response = interpret_call(
+						deps.as_ref(),
+						&env,
+						bindings,
+						encoded,
+						ip as usize,
+						response,
+					)?;
 					let program = XCVMProgram { tag: program.tag, instructions };
 					IP_REGISTER.save(deps.storage, &ip)?;
 					return Ok(response.add_message(wasm_execute(
@@ -203,20 +296,57 @@ pub fn handle_execute_step(
 					)?))
 				}
 			},
-			XCVMInstruction::Spawn { network, bridge_security, salt, assets, program } =>
-				interpret_spawn(
-					&deps,
-					&env,
-					network,
-					bridge_security,
-					salt,
-					assets,
-					program,
-					response,
-				)?,
+			XCVMInstruction::Spawn {
+				network,
+				bridge_security,
+				salt,
+				assets,
+				program,
+				deadline,
+				tip,
+			} => interpret_spawn(
+				&mut deps,
+				&env,
+				network,
+				bridge_security,
+				salt,
+				assets,
+				program,
+				deadline,
+				tip,
+				response,
+			)?,
 			XCVMInstruction::Transfer { to, assets } =>
 				interpret_transfer(&mut deps, &env, relayer.clone(), to, assets, response)?,
-			instr => return Err(ContractError::InstructionNotSupported(format!("{:?}", instr))),
+			XCVMInstruction::Query { network, salt } =>
+				interpret_query(&mut deps, network, salt, response)?,
+			XCVMInstruction::Assert { condition } =>
+				interpret_assert(deps.as_ref(), &env, condition, response)?,
+			XCVMInstruction::If { condition, then, otherwise } => {
+				let (branch, response) =
+					interpret_if(deps.as_ref(), &env, condition, then, otherwise, response)?;
+				// The selected branch is executed in place, before the remaining instructions.
+				for instruction in branch.into_iter().rev() {
+					instructions.push_front(instruction);
+				}
+				response
+			},
+			XCVMInstruction::Try { body, catch } => {
+				let response =
+					interpret_try(&mut deps, &env, relayer.clone(), ip + 1, body, catch, response)?;
+				if !instructions.is_empty() {
+					// Yield the execution, the remaining instructions running after the body and
+					// the catch block if the body failed.
+					let program = XCVMProgram { tag: program.tag, instructions };
+					IP_REGISTER.save(deps.storage, &(ip + 1))?;
+					return Ok(response.add_message(wasm_execute(
+						env.contract.address,
+						&ExecuteMsg::ExecuteStep { relayer: relayer.clone(), program },
+						vec![],
+					)?))
+				}
+				response
+			},
 		};
 		ip += 1;
 	}
@@ -297,19 +427,85 @@ pub fn interpret_call(
 }
 
 pub fn interpret_spawn(
-	deps: &DepsMut,
+	deps: &mut DepsMut,
 	env: &Env,
 	network: NetworkId,
 	bridge_security: BridgeSecurity,
 	salt: Vec<u8>,
 	assets: Funds,
 	program: XCVMProgram,
-	mut response: Response,
+	deadline: Option<u64>,
+	tip: Option<Funds>,
+	response: Response,
 ) -> Result<Response, ContractError> {
 	let Config { interpreter_origin, registry_address, router_address, .. } =
 		CONFIG.load(deps.storage)?;
 
-	let registry_address = registry_address.into_string();
+	// The tip is taken first, the assets are then resolved against the remaining balances.
+	let (normalized_tip, response) = transfer_to_router(
+		deps,
+		env,
+		&registry_address,
+		&router_address,
+		tip.unwrap_or_default(),
+		&Funds::empty(),
+		response,
+	)?;
+	let (normalized_funds, response) = transfer_to_router(
+		deps,
+		env,
+		&registry_address,
+		&router_address,
+		assets,
+		&normalized_tip,
+		response,
+	)?;
+
+	Ok(response
+		.add_message(wasm_execute(
+			router_address,
+			&cw_xcvm_common::router::ExecuteMsg::BridgeForward {
+				msg: BridgeMsg {
+					interpreter_origin: interpreter_origin.clone(),
+					network_id: network,
+					security: bridge_security,
+					salt,
+					program,
+					assets: normalized_funds,
+					deadline,
+					tip: normalized_tip,
+				},
+			},
+			Default::default(),
+		)?)
+		.add_event(
+			Event::new(XCVM_INTERPRETER_EVENT_PREFIX)
+				.add_attribute("instruction", "spawn")
+				.add_attribute(
+					"origin_network_id",
+					serde_json_wasm::to_string(&interpreter_origin.user_origin.network_id)
+						.map_err(|_| ContractError::DataSerializationError)?,
+				)
+				.add_attribute(
+					"origin_user_id",
+					serde_json_wasm::to_string(&interpreter_origin.user_origin.user_id)
+						.map_err(|_| ContractError::DataSerializationError)?,
+				),
+		))
+}
+
+/// Transfer the `assets` from the interpreter to the router, resolving their amounts against the
+/// current balances minus the `reserved` amounts, already transferred by the response.
+fn transfer_to_router(
+	deps: &mut DepsMut,
+	env: &Env,
+	registry_address: &Addr,
+	router_address: &Addr,
+	assets: Funds,
+	reserved: &Funds<Displayed<u128>>,
+	mut response: Response,
+) -> Result<(Funds<Displayed<u128>>, Response), ContractError> {
+	let registry_address = registry_address.to_string();
 	let mut normalized_funds: Funds<Displayed<u128>> = Funds::empty();
 
 	for (asset_id, amount) in assets.0 {
@@ -339,13 +535,26 @@ pub fn interpret_spawn(
 					rsp.balance
 				},
 			};
-			Amount::absolute(amount.apply(transfer_amount.into()).into())
+			let reserved = reserved
+				.0
+				.iter()
+				.find(|(id, _)| *id == asset_id)
+				.map(|(_, Displayed(amount))| *amount)
+				.unwrap_or_default();
+			let transfer_amount = transfer_amount.u128().saturating_sub(reserved);
+			Amount::absolute(amount.apply(transfer_amount))
 		};
 
 		if !amount.is_zero() {
 			let asset_id: u128 = asset_id.into();
 			let transfer_amount = amount.intercept.0;
 			normalized_funds.0.push((asset_id.into(), transfer_amount.into()));
+			trace_movement(
+				deps.storage,
+				asset_id.into(),
+				transfer_amount,
+				router_address.to_string(),
+			)?;
 			response = match reference {
 				AssetReference::Native { denom } => response.add_message(BankMsg::Send {
 					to_address: router_address.clone().into(),
@@ -361,34 +570,35 @@ pub fn interpret_spawn(
 		}
 	}
 
+	Ok((normalized_funds, response))
+}
+
+/// Interpret the `Query` instruction
+/// The query is forwarded to the gateway through the router. The result is asynchronously stored
+/// in the [`RESULT_REGISTER`] once the remote network answered, unless another query or program
+/// has been executed in the meantime.
+pub fn interpret_query(
+	deps: &mut DepsMut,
+	network: NetworkId,
+	salt: Vec<u8>,
+	response: Response,
+) -> Result<Response, ContractError> {
+	let Config { interpreter_origin, router_address, .. } = CONFIG.load(deps.storage)?;
+	let query_id = QUERY_COUNT.may_load(deps.storage)?.unwrap_or_default();
+	QUERY_COUNT.save(deps.storage, &(query_id + 1))?;
+	PENDING_QUERY.save(deps.storage, &query_id)?;
 	Ok(response
 		.add_message(wasm_execute(
 			router_address,
-			&cw_xcvm_common::router::ExecuteMsg::BridgeForward {
-				msg: BridgeMsg {
-					interpreter_origin: interpreter_origin.clone(),
-					network_id: network,
-					security: bridge_security,
-					salt,
-					program,
-					assets: normalized_funds,
-				},
+			&cw_xcvm_common::router::ExecuteMsg::BridgeForwardQuery {
+				msg: BridgeQueryMsg { interpreter_origin, network_id: network, salt, query_id },
 			},
 			Default::default(),
 		)?)
 		.add_event(
 			Event::new(XCVM_INTERPRETER_EVENT_PREFIX)
-				.add_attribute("instruction", "spawn")
-				.add_attribute(
-					"origin_network_id",
-					serde_json_wasm::to_string(&interpreter_origin.user_origin.network_id)
-						.map_err(|_| ContractError::DataSerializationError)?,
-				)
-				.add_attribute(
-					"origin_user_id",
-					serde_json_wasm::to_string(&interpreter_origin.user_origin.user_id)
-						.map_err(|_| ContractError::DataSerializationError)?,
-				),
+				.add_attribute("instruction", "query")
+				.add_attribute("network_id", format!("{network}")),
 		))
 }
 
@@ -418,6 +628,7 @@ pub fn interpret_transfer(
 			AssetReference::Native { denom } => {
 				let mut coin = deps.querier.query_balance(env.contract.address.clone(), denom)?;
 				coin.amount = amount.apply(coin.amount.into()).into();
+				trace_movement(deps.storage, asset_id, coin.amount.into(), recipient.clone())?;
 				response.add_message(BankMsg::Send {
 					to_address: recipient.clone(),
 					amount: vec![coin],
@@ -434,6 +645,7 @@ pub fn interpret_transfer(
 					},
 				))?;
 				let transfer_amount = amount.apply(rsp.balance.into());
+				trace_movement(deps.storage, asset_id, transfer_amount, recipient.clone())?;
 				response.add_message(contract.call(Cw20ExecuteMsg::Transfer {
 					recipient: recipient.clone(),
 					amount: transfer_amount.into(),
@@ -447,6 +659,182 @@ pub fn interpret_transfer(
 	))
 }
 
+/// Interpret the `Assert` instruction
+///
+/// Abort the whole program if the condition does not hold.
+pub fn interpret_assert(
+	deps: Deps,
+	env: &Env,
+	condition: Condition,
+	response: Response,
+) -> Result<Response, ContractError> {
+	if !evaluate_condition(deps, env, &condition)? {
+		return Err(ContractError::AssertionFailed)
+	}
+
+	Ok(response.add_event(
+		Event::new(XCVM_INTERPRETER_EVENT_PREFIX).add_attribute("instruction", "assert"),
+	))
+}
+
+/// Interpret the `If` instruction
+///
+/// Returns the branch that must be executed next, depending on the condition.
+pub fn interpret_if(
+	deps: Deps,
+	env: &Env,
+	condition: Condition,
+	then: VecDeque<XCVMInstruction>,
+	otherwise: VecDeque<XCVMInstruction>,
+	response: Response,
+) -> Result<(VecDeque<XCVMInstruction>, Response), ContractError> {
+	let holds = evaluate_condition(deps, env, &condition)?;
+	let branch = if holds { then } else { otherwise };
+
+	Ok((
+		branch,
+		response.add_event(
+			Event::new(XCVM_INTERPRETER_EVENT_PREFIX)
+				.add_attribute("instruction", "if")
+				.add_attribute("branch", if holds { "then" } else { "otherwise" }),
+		),
+	))
+}
+
+/// Interpret the `Try` instruction
+///
+/// The `body` is executed by the interpreter itself in a sub-message, all its effects being
+/// reverted if it fails. The failure is then handled by [`handle_try_result`], executing the
+/// `catch` block. The [`IP_REGISTER`] is set back to `ip` once done, see [`TryFrame`].
+pub fn interpret_try(
+	deps: &mut DepsMut,
+	env: &Env,
+	relayer: Addr,
+	ip: u32,
+	body: VecDeque<XCVMInstruction>,
+	catch: Option<VecDeque<XCVMInstruction>>,
+	response: Response,
+) -> Result<Response, ContractError> {
+	let mut frames = TRY_FRAMES.may_load(deps.storage)?.unwrap_or_default();
+	frames.push(TryFrame {
+		ip,
+		catch: Some(match catch {
+			Some(instructions) =>
+				CatchBlock::Program(XCVMProgram { tag: Vec::new(), instructions }),
+			None => CatchBlock::ReturnFunds,
+		}),
+	});
+	TRY_FRAMES.save(deps.storage, &frames)?;
+	let program = XCVMProgram { tag: Vec::new(), instructions: body };
+	Ok(response
+		.add_event(Event::new(XCVM_INTERPRETER_EVENT_PREFIX).add_attribute("instruction", "try"))
+		.add_submessage(SubMsg::reply_always(
+			wasm_execute(
+				env.contract.address.clone(),
+				&ExecuteMsg::ExecuteStep { relayer, program },
+				vec![],
+			)?,
+			TRY_ID,
+		)))
+}
+
+/// Program returning every balance of the interpreter, in the assets of the registry, to the
+/// user origin of the interpreter, bridging them back if the user is on another network.
+fn return_funds_program(deps: Deps, env: &Env) -> Result<XCVMProgram, ContractError> {
+	let Config { registry_address, interpreter_origin, network_id, .. } =
+		CONFIG.load(deps.storage)?;
+	let mut funds = Funds::empty();
+	let mut start_after = None;
+	loop {
+		let assets =
+			external_query_assets(deps.querier, registry_address.to_string(), start_after, None)?;
+		match assets.last() {
+			Some((last, _)) => start_after = Some(*last),
+			None => break,
+		}
+		for (asset_id, reference) in assets {
+			// Transferring a zero amount fails.
+			if query_balance(deps, env, reference)? > 0 {
+				funds.0.push((asset_id, Amount::ratio(MAX_PARTS)));
+			}
+		}
+	}
+	let UserOrigin { network_id: user_network_id, user_id } = interpreter_origin.user_origin;
+	// The user id is the address of the user, canonicalized the same way on every network.
+	let user = core::str::from_utf8(&user_id.0).map_err(|_| ContractError::InvalidAddress)?;
+	let user = deps.api.addr_canonicalize(user)?;
+	let instructions = if funds.0.is_empty() {
+		VecDeque::new()
+	} else if user_network_id == network_id {
+		VecDeque::from([XCVMInstruction::Transfer {
+			to: Destination::Account(user),
+			assets: funds,
+		}])
+	} else {
+		VecDeque::from([XCVMInstruction::Spawn {
+			network: user_network_id,
+			bridge_security: BridgeSecurity::Deterministic,
+			salt: interpreter_origin.salt,
+			assets: funds.clone(),
+			program: XCVMProgram {
+				tag: Vec::new(),
+				instructions: VecDeque::from([XCVMInstruction::Transfer {
+					to: Destination::Account(user),
+					assets: funds,
+				}]),
+			},
+			deadline: None,
+			tip: None,
+		}])
+	};
+	Ok(XCVMProgram { tag: Vec::new(), instructions })
+}
+
+/// Balance of the interpreter in the referenced asset.
+fn query_balance(deps: Deps, env: &Env, reference: AssetReference) -> StdResult<u128> {
+	Ok(match reference {
+		AssetReference::Native { denom } =>
+			deps.querier.query_balance(env.contract.address.clone(), denom)?.amount.into(),
+		AssetReference::Virtual { cw20_address } => deps
+			.querier
+			.query::<BalanceResponse>(&QueryRequest::Wasm(WasmQuery::Smart {
+				contract_addr: cw20_address.into(),
+				msg: to_binary(&Cw20QueryMsg::Balance {
+					address: env.contract.address.clone().into_string(),
+				})?,
+			}))?
+			.balance
+			.into(),
+	})
+}
+
+/// Evaluate a `Condition` against the current state of the interpreter.
+pub fn evaluate_condition(
+	deps: Deps,
+	env: &Env,
+	condition: &Condition,
+) -> Result<bool, ContractError> {
+	match condition {
+		Condition::ResultEquals(expected) => {
+			// Same encoding as the `Result` late-binding.
+			match RESULT_REGISTER.may_load(deps.storage)? {
+				Some(result) => Ok(&serde_json_wasm::to_vec(&result)
+					.map_err(|_| ContractError::DataSerializationError)? ==
+					expected),
+				None => Ok(false),
+			}
+		},
+		Condition::BalanceAtLeast { asset, amount } => {
+			let registry_addr = CONFIG.load(deps.storage)?.registry_address.into_string();
+			let reference = external_query_lookup_asset(deps.querier, registry_addr, *asset)?;
+			Ok(query_balance(deps, env, reference)? >= amount.0)
+		},
+		Condition::CallSucceeded =>
+			Ok(matches!(RESULT_REGISTER.may_load(deps.storage)?, Some(Ok(_)))),
+		Condition::Not(condition) => Ok(!evaluate_condition(deps, env, condition)?),
+	}
+}
+
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
 	match msg {
@@ -456,14 +844,20 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
 		QueryMsg::Register(Register::This) => Ok(to_binary(&env.contract.address)?),
 		QueryMsg::Register(Register::Relayer) =>
 			Ok(to_binary(&RELAYER_REGISTER.load(deps.storage)?)?),
+		QueryMsg::Traces { start_before, limit } =>
+			to_binary(&query_traces(deps, start_before, limit)?),
+		QueryMsg::TraceSteps { trace_id, start_after, limit } =>
+			to_binary(&query_trace_steps(deps, trace_id, start_after, limit)?),
 	}
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn reply(deps: DepsMut, _env: Env, msg: Reply) -> StdResult<Response> {
+pub fn reply(deps: DepsMut, env: Env, msg: Reply) -> StdResult<Response> {
 	match msg.id {
 		CALL_ID => handle_call_result(deps, msg),
 		SELF_CALL_ID => handle_self_call_result(deps, msg),
+		TRY_ID => handle_try_result(deps, env, msg),
+		CATCH_ID => handle_catch_result(deps),
 		id => Err(StdError::generic_err(format!("Unknown reply id: {}", id))),
 	}
 }
@@ -476,6 +870,7 @@ fn handle_self_call_result(deps: DepsMut, msg: Reply) -> StdResult<Response> {
 			// Save the result that is returned from the sub-interpreter
 			// this way, only the `RESULT_REGISTER` is persisted. All
 			// other state changes are reverted.
+			trace_error(deps.storage, e.clone())?;
 			RESULT_REGISTER.save(deps.storage, &Err(e))?;
 			// Ip register should be incremented by one
 			let ip_register = IP_REGISTER.load(deps.storage)?;
@@ -485,8 +880,363 @@ fn handle_self_call_result(deps: DepsMut, msg: Reply) -> StdResult<Response> {
 	}
 }
 
+/// Handle the outcome of the body of a `Try`. On failure, the error is stored in the
+/// [`RESULT_REGISTER`] and the catch block is executed in a sub-message, returning every balance to
+/// the user origin if there is none. The [`IP_REGISTER`] is restored either way.
+fn handle_try_result(deps: DepsMut, env: Env, msg: Reply) -> StdResult<Response> {
+	let mut frames = TRY_FRAMES.load(deps.storage)?;
+	let frame = frames
+		.last_mut()
+		.ok_or_else(|| StdError::generic_err("No frame for the executed try"))?;
+	IP_REGISTER.save(deps.storage, &frame.ip)?;
+	match msg.result.into_result() {
+		Ok(_) => {
+			frames.pop();
+			TRY_FRAMES.save(deps.storage, &frames)?;
+			Ok(Response::default())
+		},
+		Err(e) => {
+			let catch = frame
+				.catch
+				.take()
+				.ok_or_else(|| StdError::generic_err("The catch block already executed"))?;
+			TRY_FRAMES.save(deps.storage, &frames)?;
+			trace_result(deps.storage, Err(e.clone()))?;
+			RESULT_REGISTER.save(deps.storage, &Err(e))?;
+			let program = match catch {
+				CatchBlock::Program(program) => program,
+				CatchBlock::ReturnFunds => return_funds_program(deps.as_ref(), &env)
+					.map_err(|e| StdError::generic_err(e.to_string()))?,
+			};
+			Ok(Response::default()
+				.add_submessage(SubMsg::reply_on_success(
+					wasm_execute(
+						env.contract.address,
+						&ExecuteMsg::ExecuteStep {
+							relayer: RELAYER_REGISTER.load(deps.storage)?,
+							program,
+						},
+						vec![],
+					)?,
+					CATCH_ID,
+				))
+				.add_event(
+					Event::new(XCVM_INTERPRETER_EVENT_PREFIX).add_attribute("action", "try.caught"),
+				))
+		},
+	}
+}
+
+/// Leave the `Try` whose catch block has been executed, restoring the [`IP_REGISTER`].
+fn handle_catch_result(deps: DepsMut) -> StdResult<Response> {
+	let mut frames = TRY_FRAMES.load(deps.storage)?;
+	let frame = frames
+		.pop()
+		.ok_or_else(|| StdError::generic_err("No frame for the executed catch"))?;
+	TRY_FRAMES.save(deps.storage, &frames)?;
+	IP_REGISTER.save(deps.storage, &frame.ip)?;
+	Ok(Response::default())
+}
+
 fn handle_call_result(deps: DepsMut, msg: Reply) -> StdResult<Response> {
 	let response = msg.result.into_result().map_err(StdError::generic_err)?;
+	trace_result(deps.storage, Ok(response.data.clone()))?;
 	RESULT_REGISTER.save(deps.storage, &Ok(response.clone()))?;
 	Ok(Response::default())
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use cosmwasm_std::{
+		from_binary,
+		testing::{mock_dependencies, mock_env, mock_info, MockApi, MockQuerier, MockStorage},
+		Api, ContractResult, OwnedDeps, SubMsgResult, SystemResult, WasmMsg,
+	};
+	use cw_xcvm_asset_registry::msg::{
+		AssetsResponse, LookupResponse, QueryMsg as RegistryQueryMsg,
+	};
+	use xcvm_core::{Asset, InterpreterOrigin, PICA, USDT};
+
+	const DENOM: &str = "upica";
+
+	fn setup() -> OwnedDeps<MockStorage, MockApi, MockQuerier> {
+		let mut deps = mock_dependencies();
+		deps.querier.update_wasm(|query| {
+			let msg = match query {
+				WasmQuery::Smart { msg, .. } => from_binary(msg).unwrap(),
+				_ => panic!("unexpected query"),
+			};
+			SystemResult::Ok(match msg {
+				RegistryQueryMsg::Lookup { .. } => ContractResult::Ok(
+					to_binary(&LookupResponse {
+						reference: AssetReference::Native { denom: DENOM.into() },
+					})
+					.unwrap(),
+				),
+				// A single page of assets, the interpreter holding no USDT.
+				RegistryQueryMsg::Assets { start_after, .. } => ContractResult::Ok(
+					to_binary(&AssetsResponse {
+						assets: match start_after {
+							None => vec![
+								(PICA::ID, AssetReference::Native { denom: DENOM.into() }),
+								(USDT::ID, AssetReference::Native { denom: "uusdt".into() }),
+							],
+							Some(_) => Vec::new(),
+						},
+					})
+					.unwrap(),
+				),
+				_ => ContractResult::Err("unknown asset".into()),
+			})
+		});
+		deps.querier
+			.update_balance(mock_env().contract.address, vec![Coin::new(1000, DENOM)]);
+		instantiate(
+			deps.as_mut(),
+			mock_env(),
+			mock_info("router", &[]),
+			InstantiateMsg {
+				gateway_address: "gateway".into(),
+				registry_address: "registry".into(),
+				router_address: "router".into(),
+				interpreter_origin: InterpreterOrigin {
+					user_origin: UserOrigin {
+						network_id: 1.into(),
+						user_id: b"alice".to_vec().into(),
+					},
+					salt: Vec::new(),
+				},
+				network_id: 1.into(),
+			},
+		)
+		.unwrap();
+		deps
+	}
+
+	#[test]
+	fn spawn_takes_the_tip_before_the_assets() {
+		let mut deps = setup();
+		let response = interpret_spawn(
+			&mut deps.as_mut(),
+			&mock_env(),
+			2.into(),
+			BridgeSecurity::Deterministic,
+			Vec::new(),
+			Funds(vec![(PICA::ID, Amount::ratio(MAX_PARTS))]),
+			XCVMProgram { tag: Vec::new(), instructions: VecDeque::new() },
+			None,
+			Some(Funds(vec![(PICA::ID, Amount::ratio(MAX_PARTS / 10))])),
+			Response::default(),
+		)
+		.unwrap();
+		let transferred = response
+			.messages
+			.iter()
+			.filter_map(|message| match &message.msg {
+				CosmosMsg::Bank(BankMsg::Send { amount, .. }) => Some(amount[0].amount.u128()),
+				_ => None,
+			})
+			.collect::<Vec<_>>();
+		// The tip is taken first, then the whole remaining balance is spawned.
+		let tip = Amount::ratio(MAX_PARTS / 10).apply(1000);
+		assert_eq!(transferred, vec![tip, 1000 - tip]);
+	}
+
+	#[test]
+	fn failed_try_bodies_run_their_catch_block() {
+		let mut deps = setup();
+		RELAYER_REGISTER
+			.save(deps.as_mut().storage, &Addr::unchecked("relayer"))
+			.unwrap();
+		let transfer = |to: &str| XCVMInstruction::Transfer {
+			to: Destination::Account(MockApi::default().addr_canonicalize(to).unwrap()),
+			assets: Funds(vec![(PICA::ID, Amount::ratio(MAX_PARTS))]),
+		};
+		let (body, catch) =
+			(VecDeque::from([transfer("bob")]), VecDeque::from([transfer("carol")]));
+		let try_result = |deps: DepsMut, result: Result<(), &str>| {
+			reply(
+				deps,
+				mock_env(),
+				Reply {
+					id: TRY_ID,
+					result: match result {
+						Ok(()) =>
+							SubMsgResult::Ok(SubMsgResponse { events: Vec::new(), data: None }),
+						Err(e) => SubMsgResult::Err(e.into()),
+					},
+				},
+			)
+			.unwrap()
+		};
+		let execute_step = |response: &Response| match &response.messages[0].msg {
+			CosmosMsg::Wasm(WasmMsg::Execute { msg, .. }) => match from_binary(msg).unwrap() {
+				ExecuteMsg::ExecuteStep { program, .. } => program.instructions,
+				_ => panic!("expected an execution step"),
+			},
+			_ => panic!("expected an execution step"),
+		};
+
+		let catch_result = |deps: DepsMut| {
+			reply(
+				deps,
+				mock_env(),
+				Reply {
+					id: CATCH_ID,
+					result: SubMsgResult::Ok(SubMsgResponse { events: Vec::new(), data: None }),
+				},
+			)
+			.unwrap()
+		};
+
+		// Nested tries are caught innermost first.
+		for (ip, catch) in [(1, Some(catch.clone())), (2, None)] {
+			interpret_try(
+				&mut deps.as_mut(),
+				&mock_env(),
+				Addr::unchecked("relayer"),
+				ip,
+				body.clone(),
+				catch,
+				Response::default(),
+			)
+			.unwrap();
+		}
+		assert_eq!(TRY_FRAMES.load(&deps.storage).unwrap().len(), 2);
+
+		// Without a catch block, every balance is returned to the user, whatever the body uses.
+		IP_REGISTER.save(deps.as_mut().storage, &7).unwrap();
+		let response = try_result(deps.as_mut(), Err("failed"));
+		assert_eq!(response.messages[0].id, CATCH_ID);
+		assert_eq!(execute_step(&response), VecDeque::from([transfer("alice")]));
+		assert_eq!(RESULT_REGISTER.load(&deps.storage).unwrap(), Err("failed".into()));
+		// The position following the try is restored once the catch block is executed.
+		assert_eq!(IP_REGISTER.load(&deps.storage).unwrap(), 2);
+		IP_REGISTER.save(deps.as_mut().storage, &9).unwrap();
+		catch_result(deps.as_mut());
+		assert_eq!(IP_REGISTER.load(&deps.storage).unwrap(), 2);
+
+		// A successful body skips the catch block.
+		let response = try_result(deps.as_mut(), Ok(()));
+		assert!(response.messages.is_empty());
+		assert!(TRY_FRAMES.load(&deps.storage).unwrap().is_empty());
+		assert_eq!(IP_REGISTER.load(&deps.storage).unwrap(), 1);
+
+		interpret_try(
+			&mut deps.as_mut(),
+			&mock_env(),
+			Addr::unchecked("relayer"),
+			1,
+			body,
+			Some(catch.clone()),
+			Response::default(),
+		)
+		.unwrap();
+		let response = try_result(deps.as_mut(), Err("failed"));
+		assert_eq!(execute_step(&response), catch);
+	}
+
+	#[test]
+	fn funds_are_returned_to_remote_users_through_a_spawn() {
+		let mut deps = setup();
+		let mut config = CONFIG.load(&deps.storage).unwrap();
+		config.interpreter_origin.user_origin.network_id = 2.into();
+		CONFIG.save(deps.as_mut().storage, &config).unwrap();
+		let funds = Funds(vec![(PICA::ID, Amount::ratio(MAX_PARTS))]);
+		let program = return_funds_program(deps.as_ref(), &mock_env()).unwrap();
+		assert_eq!(
+			program.instructions,
+			VecDeque::from([XCVMInstruction::Spawn {
+				network: 2.into(),
+				bridge_security: BridgeSecurity::Deterministic,
+				salt: Vec::new(),
+				assets: funds.clone(),
+				program: XCVMProgram {
+					tag: Vec::new(),
+					// The user is encoded as in the local transfers.
+					instructions: VecDeque::from([XCVMInstruction::Transfer {
+						to: Destination::Account(
+							MockApi::default().addr_canonicalize("alice").unwrap()
+						),
+						assets: funds,
+					}]),
+				},
+				deadline: None,
+				tip: None,
+			}])
+		);
+	}
+
+	#[test]
+	fn only_the_last_pending_query_result_is_stored() {
+		let mut deps = setup();
+		let query = |deps: &mut DepsMut| {
+			interpret_query(deps, 2.into(), Vec::new(), Response::default()).unwrap();
+		};
+		let set_result = |deps: DepsMut, query_id: u64| {
+			execute(
+				deps,
+				mock_env(),
+				mock_info("gateway", &[]),
+				ExecuteMsg::SetQueryResult { query_id, result: Err("failed".into()) },
+			)
+			.unwrap();
+		};
+
+		// Only the last query of a program is awaited.
+		query(&mut deps.as_mut());
+		query(&mut deps.as_mut());
+		set_result(deps.as_mut(), 0);
+		assert!(RESULT_REGISTER.may_load(&deps.storage).unwrap().is_none());
+		set_result(deps.as_mut(), 1);
+		assert_eq!(RESULT_REGISTER.load(&deps.storage).unwrap(), Err("failed".into()));
+
+		// A result does not overwrite the registers of a later program.
+		query(&mut deps.as_mut());
+		execute(
+			deps.as_mut(),
+			mock_env(),
+			mock_info("router", &[]),
+			ExecuteMsg::Execute {
+				relayer: Addr::unchecked("relayer"),
+				program: XCVMProgram { tag: Vec::new(), instructions: VecDeque::new() },
+			},
+		)
+		.unwrap();
+		RESULT_REGISTER.save(deps.as_mut().storage, &Err("later".into())).unwrap();
+		set_result(deps.as_mut(), 2);
+		assert_eq!(RESULT_REGISTER.load(&deps.storage).unwrap(), Err("later".into()));
+	}
+
+	#[test]
+	fn migration_sets_the_network_of_legacy_interpreters() {
+		let mut deps = setup();
+		let Config {
+			gateway_address, registry_address, router_address, interpreter_origin, ..
+		} = CONFIG.load(&deps.storage).unwrap();
+		LEGACY_CONFIG
+			.save(
+				deps.as_mut().storage,
+				&LegacyConfig {
+					gateway_address,
+					registry_address,
+					router_address,
+					interpreter_origin,
+				},
+			)
+			.unwrap();
+		let msg = |network_id: Option<u32>| MigrateMsg {
+			owners: Vec::new(),
+			network_id: network_id.map(Into::into),
+		};
+		assert!(matches!(
+			migrate(deps.as_mut(), mock_env(), msg(None)),
+			Err(ContractError::MissingNetworkId)
+		));
+		migrate(deps.as_mut(), mock_env(), msg(Some(1))).unwrap();
+		assert_eq!(CONFIG.load(&deps.storage).unwrap().network_id, 1.into());
+		// Up to date interpreters keep their network.
+		migrate(deps.as_mut(), mock_env(), msg(None)).unwrap();
+	}
+}
@@ -49,9 +49,15 @@ pub enum ContractError {
 	#[error("Instruction {0} is not supported")]
 	InstructionNotSupported(String),
 
+	#[error("Assertion failed")]
+	AssertionFailed,
+
 	#[error("Address is invalid")]
 	InvalidAddress,
 
 	#[error("Unsupported")]
 	Unsupported,
+
+	#[error("The network of the interpreter must be provided")]
+	MissingNetworkId,
 }
@@ -1,11 +1,12 @@
 extern crate alloc;
 
+use crate::state::{ExecutionTrace, TraceStep};
 use alloc::{string::String, vec::Vec};
 use cosmwasm_std::Addr;
 use cw_xcvm_utils::DefaultXCVMProgram;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-use xcvm_core::{InterpreterOrigin, Register};
+use xcvm_core::{InterpreterOrigin, NetworkId, QueryResult, Register};
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct InstantiateMsg {
@@ -17,6 +18,8 @@ pub struct InstantiateMsg {
 	pub router_address: String,
 	/// The interpreter origin.
 	pub interpreter_origin: InterpreterOrigin,
+	/// Network of the interpreter.
+	pub network_id: NetworkId,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -33,6 +36,9 @@ pub enum ExecuteMsg {
 	AddOwners { owners: Vec<Addr> },
 	/// Remove owners from the contract
 	RemoveOwners { owners: Vec<Addr> },
+	/// This is only meant to be used by the gateway, otherwise it will return an error
+	/// Store the outcome of a previously executed `Query` instruction in the `RESULT_REGISTER`.
+	SetQueryResult { query_id: u64, result: Result<QueryResult, String> },
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -40,10 +46,27 @@ pub struct MigrateMsg {
 	/// Owners to be added to the list of owners which acts more like a recovery in case all of the
 	/// owners are erased accidentally
 	pub owners: Vec<Addr>,
+	/// Network of the interpreter, required when migrating from an interpreter without one.
+	#[serde(default)]
+	pub network_id: Option<NetworkId>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub enum QueryMsg {
 	/// Get a specific register
 	Register(Register),
+	/// List the last executed programs, most recent first.
+	Traces { start_before: Option<u64>, limit: Option<u32> },
+	/// List the steps of an executed program, in execution order.
+	TraceSteps { trace_id: u64, start_after: Option<u32>, limit: Option<u32> },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct TracesResponse {
+	pub traces: Vec<(u64, ExecutionTrace)>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct TraceStepsResponse {
+	pub steps: Vec<(u32, TraceStep)>,
 }
@@ -1,11 +1,12 @@
 extern crate alloc;
 
-use alloc::string::String;
-use cosmwasm_std::{Addr, SubMsgResponse};
+use alloc::{string::String, vec::Vec};
+use cosmwasm_std::{Addr, Binary, SubMsgResponse};
 use cw_storage_plus::{Item, Map};
+use cw_xcvm_utils::DefaultXCVMProgram;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-use xcvm_core::InterpreterOrigin;
+use xcvm_core::{AssetId, Displayed, InterpreterOrigin, NetworkId};
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct Config {
@@ -13,10 +14,93 @@ pub struct Config {
 	pub registry_address: Addr,
 	pub router_address: Addr,
 	pub interpreter_origin: InterpreterOrigin,
+	pub network_id: NetworkId,
 }
 
 pub const CONFIG: Item<Config> = Item::new("config");
+
+/// [`Config`] of the interpreters instantiated before their network was recorded.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct LegacyConfig {
+	pub gateway_address: Addr,
+	pub registry_address: Addr,
+	pub router_address: Addr,
+	pub interpreter_origin: InterpreterOrigin,
+}
+
+pub const LEGACY_CONFIG: Item<LegacyConfig> = Item::new("config");
 pub const OWNERS: Map<Addr, ()> = Map::new("owners");
+/// Handler of the failure of the body of a `Try`.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum CatchBlock {
+	/// Program executed instead of the body.
+	Program(DefaultXCVMProgram),
+	/// Return every balance of the interpreter to the user origin.
+	ReturnFunds,
+}
+
+/// `Try` instruction being executed.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct TryFrame {
+	/// Value of the [`IP_REGISTER`] restored once the body, and the catch block if the body
+	/// failed, are executed: the position of the instruction following the `Try`.
+	pub ip: u32,
+	/// Handler of the failure of the body, taken when the body fails.
+	pub catch: Option<CatchBlock>,
+}
+
+/// `Try` instructions being executed, the innermost last.
+pub const TRY_FRAMES: Item<Vec<TryFrame>> = Item::new("try_frames");
+
+/// Funds sent by the interpreter while executing an instruction.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct AssetMovement {
+	pub asset_id: AssetId,
+	pub amount: Displayed<u128>,
+	pub recipient: String,
+}
+
+/// Instruction executed by the interpreter.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct TraceStep {
+	/// Value of the [`IP_REGISTER`] before the instruction.
+	pub ip: u32,
+	/// Name of the instruction, e.g. `call`.
+	pub instruction: String,
+	/// Outcome of a `Call`, or the failure of the body of a `Try`.
+	pub result: Option<Result<Option<Binary>, String>>,
+	pub movements: Vec<AssetMovement>,
+}
+
+/// Program executed by the interpreter. The steps of a failed program are reverted, only the
+/// error being recorded.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ExecutionTrace {
+	pub tag: Vec<u8>,
+	/// Salt of the interpreter origin.
+	pub salt: Vec<u8>,
+	pub relayer: Addr,
+	pub block_height: u64,
+	/// Number of steps, kept in [`TRACE_STEPS`].
+	pub step_count: u32,
+	pub error: Option<String>,
+}
+
+/// Executed programs, indexed by execution order. Only the last [`MAX_TRACES`] are kept.
+pub const TRACES: Map<u64, ExecutionTrace> = Map::new("traces");
+/// Steps of the executed programs, indexed by trace and step. Stored apart from the trace so
+/// that tracing an instruction only writes a single step.
+pub const TRACE_STEPS: Map<(u64, u32), TraceStep> = Map::new("trace_steps");
+/// Number of programs executed by the interpreter.
+pub const TRACE_COUNT: Item<u64> = Item::new("trace_count");
+pub const MAX_TRACES: u64 = 50;
+
+/// Number of queries sent by the interpreter, used as their identifier.
+pub const QUERY_COUNT: Item<u64> = Item::new("query_count");
+/// Identifier of the last query sent by the current program. Its result is only stored if no
+/// other program was executed in the meantime.
+pub const PENDING_QUERY: Item<u64> = Item::new("pending_query");
 
 // Registers
 pub const IP_REGISTER: Item<u32> = Item::new("ip_register");
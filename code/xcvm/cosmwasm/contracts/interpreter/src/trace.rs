@@ -0,0 +1,205 @@
+extern crate alloc;
+
+use crate::{
+	msg::{TraceStepsResponse, TracesResponse},
+	state::{
+		AssetMovement, ExecutionTrace, TraceStep, MAX_TRACES, TRACES, TRACE_COUNT, TRACE_STEPS,
+	},
+};
+use alloc::{string::String, vec::Vec};
+use cosmwasm_std::{Addr, Binary, Deps, Order, StdError, StdResult, Storage};
+use cw_storage_plus::Bound;
+use cw_xcvm_utils::DefaultXCVMInstruction;
+use xcvm_core::{AssetId, Displayed, Instruction};
+
+const DEFAULT_QUERY_LIMIT: u32 = 10;
+const MAX_QUERY_LIMIT: u32 = 30;
+
+/// Start the trace of a program, dropping the oldest one if [`MAX_TRACES`] are already kept.
+pub fn start_trace(
+	storage: &mut dyn Storage,
+	tag: Vec<u8>,
+	salt: Vec<u8>,
+	relayer: Addr,
+	block_height: u64,
+) -> StdResult<()> {
+	let id = TRACE_COUNT.may_load(storage)?.unwrap_or_default();
+	TRACES.save(
+		storage,
+		id,
+		&ExecutionTrace { tag, salt, relayer, block_height, step_count: 0, error: None },
+	)?;
+	if id >= MAX_TRACES {
+		let dropped = id - MAX_TRACES;
+		if let Some(trace) = TRACES.may_load(storage, dropped)? {
+			for step in 0..trace.step_count {
+				TRACE_STEPS.remove(storage, (dropped, step));
+			}
+			TRACES.remove(storage, dropped);
+		}
+	}
+	TRACE_COUNT.save(storage, &(id + 1))
+}
+
+/// Id and trace of the program being executed, if any.
+fn current_trace(storage: &dyn Storage) -> StdResult<Option<(u64, ExecutionTrace)>> {
+	let id = match TRACE_COUNT.may_load(storage)? {
+		Some(count) if count > 0 => count - 1,
+		_ => return Ok(None),
+	};
+	Ok(TRACES.may_load(storage, id)?.map(|trace| (id, trace)))
+}
+
+/// Update the trace of the program being executed, if any.
+fn update_current(
+	storage: &mut dyn Storage,
+	update: impl FnOnce(&mut ExecutionTrace),
+) -> StdResult<()> {
+	if let Some((id, mut trace)) = current_trace(storage)? {
+		update(&mut trace);
+		TRACES.save(storage, id, &trace)?;
+	}
+	Ok(())
+}
+
+/// Update the last step of the program being executed, if any.
+fn update_last_step(
+	storage: &mut dyn Storage,
+	update: impl FnOnce(&mut TraceStep),
+) -> StdResult<()> {
+	if let Some((id, trace)) = current_trace(storage)? {
+		if let Some(step) = trace.step_count.checked_sub(1) {
+			TRACE_STEPS.update(storage, (id, step), |last| {
+				let mut last = last.ok_or_else(|| StdError::not_found("TraceStep"))?;
+				update(&mut last);
+				StdResult::Ok(last)
+			})?;
+		}
+	}
+	Ok(())
+}
+
+pub fn trace_instruction(
+	storage: &mut dyn Storage,
+	ip: u32,
+	instruction: &DefaultXCVMInstruction,
+) -> StdResult<()> {
+	let instruction = match instruction {
+		Instruction::Transfer { .. } => "transfer",
+		Instruction::Call { .. } => "call",
+		Instruction::Spawn { .. } => "spawn",
+		Instruction::Query { .. } => "query",
+		Instruction::Assert { .. } => "assert",
+		Instruction::If { .. } => "if",
+		Instruction::Try { .. } => "try",
+	};
+	if let Some((id, mut trace)) = current_trace(storage)? {
+		TRACE_STEPS.save(
+			storage,
+			(id, trace.step_count),
+			&TraceStep { ip, instruction: instruction.into(), result: None, movements: Vec::new() },
+		)?;
+		trace.step_count += 1;
+		TRACES.save(storage, id, &trace)?;
+	}
+	Ok(())
+}
+
+/// Record funds sent by the last traced instruction.
+pub fn trace_movement(
+	storage: &mut dyn Storage,
+	asset_id: AssetId,
+	amount: u128,
+	recipient: String,
+) -> StdResult<()> {
+	update_last_step(storage, |step| {
+		step.movements
+			.push(AssetMovement { asset_id, amount: Displayed(amount), recipient })
+	})
+}
+
+/// Record the outcome of the last traced instruction.
+pub fn trace_result(
+	storage: &mut dyn Storage,
+	result: Result<Option<Binary>, String>,
+) -> StdResult<()> {
+	update_last_step(storage, |step| step.result = Some(result))
+}
+
+/// Record the failure of the program, its steps having been reverted.
+pub fn trace_error(storage: &mut dyn Storage, error: String) -> StdResult<()> {
+	update_current(storage, |trace| trace.error = Some(error))
+}
+
+/// Paginated listing of the traced programs, most recent first, at most [`MAX_QUERY_LIMIT`] per
+/// page.
+pub fn query_traces(
+	deps: Deps,
+	start_before: Option<u64>,
+	limit: Option<u32>,
+) -> StdResult<TracesResponse> {
+	let limit = limit.unwrap_or(DEFAULT_QUERY_LIMIT).min(MAX_QUERY_LIMIT) as usize;
+	let traces = TRACES
+		.range(deps.storage, None, start_before.map(Bound::exclusive), Order::Descending)
+		.take(limit)
+		.collect::<StdResult<Vec<_>>>()?;
+	Ok(TracesResponse { traces })
+}
+
+/// Paginated listing of the steps of a traced program, at most [`MAX_QUERY_LIMIT`] per page.
+pub fn query_trace_steps(
+	deps: Deps,
+	trace_id: u64,
+	start_after: Option<u32>,
+	limit: Option<u32>,
+) -> StdResult<TraceStepsResponse> {
+	let limit = limit.unwrap_or(DEFAULT_QUERY_LIMIT).min(MAX_QUERY_LIMIT) as usize;
+	let steps = TRACE_STEPS
+		.prefix(trace_id)
+		.range(deps.storage, start_after.map(Bound::exclusive), None, Order::Ascending)
+		.take(limit)
+		.collect::<StdResult<Vec<_>>>()?;
+	Ok(TraceStepsResponse { steps })
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use cosmwasm_std::testing::mock_dependencies;
+	use xcvm_core::{Asset, Destination, Funds, PICA};
+
+	#[test]
+	fn keeps_the_last_traces() {
+		let mut deps = mock_dependencies();
+		let transfer = DefaultXCVMInstruction::Transfer {
+			to: Destination::Relayer,
+			assets: Funds::from([(PICA::ID, 1_u128)]),
+		};
+		for height in 0..MAX_TRACES + 2 {
+			let storage = deps.as_mut().storage;
+			start_trace(storage, b"tag".to_vec(), Vec::new(), Addr::unchecked("relayer"), height)
+				.unwrap();
+			trace_instruction(storage, 0, &transfer).unwrap();
+			trace_movement(storage, PICA::ID, 1, "relayer".into()).unwrap();
+		}
+		trace_error(deps.as_mut().storage, "failed".into()).unwrap();
+
+		let TracesResponse { traces } = query_traces(deps.as_ref(), None, Some(2)).unwrap();
+		assert_eq!(
+			traces.iter().map(|(id, _)| *id).collect::<Vec<_>>(),
+			[MAX_TRACES + 1, MAX_TRACES]
+		);
+		assert_eq!(traces[0].1.error, Some("failed".into()));
+		assert_eq!(traces[1].1.error, None);
+		assert_eq!(traces[1].1.step_count, 1);
+		let TraceStepsResponse { steps } =
+			query_trace_steps(deps.as_ref(), MAX_TRACES, None, None).unwrap();
+		assert_eq!(steps[0].1.movements[0].amount, Displayed(1));
+		// The steps of dropped traces are removed with them.
+		assert!(query_trace_steps(deps.as_ref(), 1, None, None).unwrap().steps.is_empty());
+
+		let TracesResponse { traces } =
+			query_traces(deps.as_ref(), Some(3), Some(MAX_QUERY_LIMIT)).unwrap();
+		assert_eq!(traces.iter().map(|(id, _)| *id).collect::<Vec<_>>(), [2]);
+	}
+}
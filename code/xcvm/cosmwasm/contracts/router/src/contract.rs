@@ -2,34 +2,48 @@ extern crate alloc;
 
 use crate::{
 	error::ContractError,
-	msg::{InstantiateMsg, MigrateMsg, QueryMsg},
+	msg::{
+		ConfigResponse, EstimateFeesResponse, InstantiateMsg, InterpreterResponse,
+		InterpretersResponse, MigrateMsg, QueryMsg,
+	},
 	state::{Config, Interpreter, CONFIG, INTERPRETERS},
 };
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{
 	to_binary, wasm_execute, Addr, BankMsg, Binary, Coin, CosmosMsg, Deps, DepsMut, Env, Event,
-	MessageInfo, Reply, Response, StdError, StdResult, SubMsg, WasmMsg,
+	MessageInfo, Order, Reply, Response, StdError, StdResult, SubMsg, WasmMsg,
 };
 use cw2::set_contract_version;
 use cw20::{Cw20Contract, Cw20ExecuteMsg};
+use cw_storage_plus::Bound;
 use cw_utils::ensure_from_older_version;
-use cw_xcvm_asset_registry::{contract::external_query_lookup_asset, msg::AssetReference};
+use cw_xcvm_asset_registry::{
+	contract::external_query_lookup_asset, helpers::AssetRegistryContract, msg::AssetReference,
+};
 use cw_xcvm_common::{
-	router::ExecuteMsg,
-	shared::{decode_base64, BridgeMsg},
+	gateway::RelayerFees,
+	router::{ExecuteMsg, FeeSchedule},
+	shared::{decode_base64, BridgeMsg, BridgeQueryMsg},
 };
 use cw_xcvm_interpreter::contract::{
 	XCVM_INTERPRETER_EVENT_DATA_ORIGIN, XCVM_INTERPRETER_EVENT_PREFIX,
 };
-use cw_xcvm_utils::DefaultXCVMProgram;
-use xcvm_core::{BridgeSecurity, CallOrigin, Displayed, Funds, InterpreterOrigin};
+use cw_xcvm_utils::{DefaultXCVMInstruction, DefaultXCVMProgram};
+use std::collections::VecDeque;
+use xcvm_core::{
+	AssetId, BridgeSecurity, CallOrigin, Diagnostic, Displayed, Funds, Instruction,
+	InterpreterOrigin, MemoryRegistry, NetworkId, UserOrigin, Validator,
+};
 
 const CONTRACT_NAME: &str = "composable:xcvm-router";
 const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 const INSTANTIATE_REPLY_ID: u64 = 1;
 pub const XCVM_ROUTER_EVENT_PREFIX: &str = "xcvm.router";
 
+const DEFAULT_QUERY_LIMIT: u32 = 10;
+const MAX_QUERY_LIMIT: u32 = 30;
+
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
 	deps: DepsMut,
@@ -47,6 +61,7 @@ pub fn instantiate(
 			registry_address,
 			interpreter_code_id: msg.interpreter_code_id,
 			network_id: msg.network_id,
+			fee_schedule: FeeSchedule::default(),
 		},
 	)?;
 	Ok(Response::default()
@@ -64,6 +79,7 @@ pub fn execute(
 		ExecuteMsg::ExecuteProgram { salt, program, assets } => {
 			let self_address = env.contract.address;
 			let call_origin = CallOrigin::Local { user: info.sender.clone() };
+			validate_program(deps.as_ref(), &program, &assets)?;
 			let transfers =
 				transfer_from_user(&deps, self_address.clone(), info.sender, info.funds, &assets)?;
 			Ok(Response::default().add_messages(transfers).add_message(wasm_execute(
@@ -82,6 +98,20 @@ pub fn execute(
 			handle_set_interpreter_security(deps, info, interpreter_origin, bridge_security),
 
 		ExecuteMsg::BridgeForward { msg } => handle_bridge_forward(deps, info, msg),
+
+		ExecuteMsg::BridgeForwardQuery { msg } => handle_bridge_forward_query(deps, info, msg),
+
+		ExecuteMsg::SetFeeSchedule { fee_schedule } => {
+			let mut config = CONFIG.load(deps.storage)?;
+			if config.gateway_address != info.sender {
+				return Err(ContractError::NotAuthorized)
+			}
+			config.fee_schedule = fee_schedule;
+			CONFIG.save(deps.storage, &config)?;
+			Ok(Response::default().add_event(
+				Event::new(XCVM_ROUTER_EVENT_PREFIX).add_attribute("action", "set_fee_schedule"),
+			))
+		},
 	}
 }
 
@@ -155,40 +185,150 @@ fn transfer_from_user(
 
 /// Handle a request to forward a message to the bridge gateway.
 /// The call must originate from an interpreter.
+/// The fee of the destination network is deducted from the bridged assets, then escrowed by the
+/// gateway along with the tip for the relayer that delivers the spawn, see [`FeeSchedule`].
 fn handle_bridge_forward(
 	deps: DepsMut,
 	info: MessageInfo,
-	msg: BridgeMsg,
+	mut msg: BridgeMsg,
 ) -> Result<Response, ContractError> {
 	ensure_interpreter(&deps, &info.sender, msg.interpreter_origin.clone())?;
 	let config = CONFIG.load(deps.storage)?;
-	let transfers = msg
-		.assets
-		.0
-		.iter()
-		.map(|(asset, Displayed(amount))| {
-			let reference = external_query_lookup_asset(
-				deps.querier,
-				config.registry_address.to_string(),
-				*asset,
-			)?;
-			match reference {
-				AssetReference::Native { denom } => Ok(BankMsg::Send {
-					to_address: config.gateway_address.clone().into(),
-					amount: vec![Coin { denom, amount: amount.clone().into() }],
+	let fee = config.fee_schedule.network_fee(msg.network_id);
+	msg.assets = deduct_fee(msg.assets, &fee)?;
+	let mut fees = core::mem::take(&mut msg.tip);
+	add_funds(&mut fees, fee.0);
+	fees.0.retain(|(_, Displayed(amount))| *amount != 0);
+	let mut response = Response::default();
+	for (asset, Displayed(amount)) in msg.assets.0.iter().chain(fees.0.iter()) {
+		response = response.add_message(transfer_asset(
+			deps.as_ref(),
+			&config.registry_address,
+			*asset,
+			*amount,
+			&config.gateway_address,
+		)?);
+	}
+	let fees = if fees.0.is_empty() {
+		None
+	} else {
+		response = response.add_event(
+			Event::new(XCVM_ROUTER_EVENT_PREFIX)
+				.add_attribute("action", "fees_escrowed")
+				.add_attribute(
+					"fees",
+					fees.0
+						.iter()
+						.map(|(asset, Displayed(amount))| {
+							format!("{}:{}", u128::from(*asset), amount)
+						})
+						.collect::<Vec<_>>()
+						.join(","),
+				),
+		);
+		Some(RelayerFees { fees })
+	};
+	Ok(response.add_message(wasm_execute(
+		config.gateway_address,
+		&cw_xcvm_common::gateway::ExecuteMsg::Bridge { interpreter: info.sender, msg, fees },
+		Default::default(),
+	)?))
+}
+
+/// Transfer an asset held by the router to the `recipient`.
+fn transfer_asset(
+	deps: Deps,
+	registry_address: &Addr,
+	asset: AssetId,
+	amount: u128,
+	recipient: &Addr,
+) -> Result<CosmosMsg, ContractError> {
+	let reference = external_query_lookup_asset(deps.querier, registry_address.to_string(), asset)?;
+	Ok(match reference {
+		AssetReference::Native { denom } =>
+			BankMsg::Send { to_address: recipient.into(), amount: vec![Coin::new(amount, denom)] }
+				.into(),
+		AssetReference::Virtual { cw20_address } =>
+			Cw20Contract(cw20_address).call(Cw20ExecuteMsg::Transfer {
+				recipient: recipient.into(),
+				amount: amount.into(),
+			})?,
+	})
+}
+
+/// Deduct the `fee` from the `assets`, failing if they do not cover it.
+fn deduct_fee(
+	mut assets: Funds<Displayed<u128>>,
+	fee: &Funds<Displayed<u128>>,
+) -> Result<Funds<Displayed<u128>>, ContractError> {
+	for (asset, Displayed(fee_amount)) in fee.0.iter().filter(|(_, amount)| amount.0 != 0) {
+		let Displayed(amount) = assets
+			.0
+			.iter_mut()
+			.find(|(id, _)| id == asset)
+			.map(|(_, amount)| amount)
+			.ok_or(ContractError::InsufficientFundsForFees)?;
+		*amount = amount.checked_sub(*fee_amount).ok_or(ContractError::InsufficientFundsForFees)?;
+	}
+	Ok(assets)
+}
+
+/// Accumulate `other` into `funds`.
+fn add_funds(funds: &mut Funds<Displayed<u128>>, other: Vec<(AssetId, Displayed<u128>)>) {
+	for (asset, Displayed(amount)) in other {
+		match funds.0.iter_mut().find(|(id, _)| *id == asset) {
+			Some((_, Displayed(total))) => *total = total.saturating_add(amount),
+			None => funds.0.push((asset, Displayed(amount))),
+		}
+	}
+}
+
+/// Accumulate the fees and the absolute part of the tips of the spawns executed by the
+/// `instructions` on this network. Both branches of the conditionals and the catch blocks are
+/// accounted, the estimation being an upper bound.
+fn estimate_fees(
+	fee_schedule: &FeeSchedule,
+	instructions: &VecDeque<DefaultXCVMInstruction>,
+	fees: &mut Funds<Displayed<u128>>,
+) {
+	for instruction in instructions {
+		match instruction {
+			Instruction::Spawn { network, tip, .. } => {
+				add_funds(fees, fee_schedule.network_fee(*network).0);
+				if let Some(tip) = tip {
+					add_funds(
+						fees,
+						tip.0.iter().map(|(asset, amount)| (*asset, amount.intercept)).collect(),
+					);
 				}
-				.into()),
-				AssetReference::Virtual { cw20_address } =>
-					Cw20Contract(cw20_address).call(Cw20ExecuteMsg::Transfer {
-						recipient: config.gateway_address.clone().into(),
-						amount: amount.clone().into(),
-					}),
-			}
-		})
-		.collect::<Result<Vec<CosmosMsg>, _>>()?;
-	Ok(Response::default().add_messages(transfers).add_message(wasm_execute(
+			},
+			Instruction::If { then, otherwise, .. } => {
+				estimate_fees(fee_schedule, then, fees);
+				estimate_fees(fee_schedule, otherwise, fees);
+			},
+			Instruction::Try { body, catch } => {
+				estimate_fees(fee_schedule, body, fees);
+				if let Some(catch) = catch {
+					estimate_fees(fee_schedule, catch, fees);
+				}
+			},
+			_ => {},
+		}
+	}
+}
+
+/// Handle a request to forward a query to the bridge gateway.
+/// The call must originate from an interpreter.
+fn handle_bridge_forward_query(
+	deps: DepsMut,
+	info: MessageInfo,
+	msg: BridgeQueryMsg,
+) -> Result<Response, ContractError> {
+	ensure_interpreter(&deps, &info.sender, msg.interpreter_origin.clone())?;
+	let config = CONFIG.load(deps.storage)?;
+	Ok(Response::default().add_message(wasm_execute(
 		config.gateway_address,
-		&cw_xcvm_common::gateway::ExecuteMsg::Bridge { interpreter: info.sender, msg },
+		&cw_xcvm_common::gateway::ExecuteMsg::BridgeQuery { interpreter: info.sender, msg },
 		Default::default(),
 	)?))
 }
@@ -288,6 +428,7 @@ fn handle_execute_program(
 					registry_address: config.registry_address.into(),
 					router_address: env.contract.address.clone().into_string(),
 					interpreter_origin: interpreter_origin.clone(),
+					network_id: config.network_id,
 				})?,
 				funds: vec![],
 				label: format!(
@@ -321,6 +462,60 @@ fn handle_execute_program(
 	}
 }
 
+/// Statically validate a program submitted by a user, so that a malformed program is rejected
+/// before being bridged. The networks registered on the gateway are known in addition to the
+/// built-in ones, and the assets spawned from this network must be registered in the
+/// asset-registry to be bridged.
+fn validate_program(
+	deps: Deps,
+	program: &DefaultXCVMProgram,
+	assets: &Funds<Displayed<u128>>,
+) -> Result<(), ContractError> {
+	let config = CONFIG.load(deps.storage)?;
+	let registry = AssetRegistryContract(config.registry_address.clone())
+		.registry(deps.querier, MemoryRegistry::default())
+		.with_gateway(config.gateway_address.clone());
+	if let Some(diagnostic) = Validator::new(&registry)
+		.validate(program, Some(assets))
+		.into_iter()
+		.find(Diagnostic::is_error)
+	{
+		return Err(ContractError::InvalidProgram(diagnostic))
+	}
+	let mut assets = Vec::new();
+	spawned_assets(&program.instructions, &mut assets);
+	for asset_id in assets {
+		external_query_lookup_asset(deps.querier, config.registry_address.to_string(), asset_id)
+			.map_err(|_| ContractError::UnregisteredAsset(asset_id.into()))?;
+	}
+	Ok(())
+}
+
+/// Collect the assets spawned by the instructions, including the ones of nested blocks.
+fn spawned_assets(instructions: &VecDeque<DefaultXCVMInstruction>, assets: &mut Vec<AssetId>) {
+	for instruction in instructions {
+		match instruction {
+			Instruction::Spawn { assets: spawned, .. } =>
+				for (asset_id, _) in &spawned.0 {
+					if !assets.contains(asset_id) {
+						assets.push(*asset_id);
+					}
+				},
+			Instruction::If { then, otherwise, .. } => {
+				spawned_assets(then, assets);
+				spawned_assets(otherwise, assets);
+			},
+			Instruction::Try { body, catch } => {
+				spawned_assets(body, assets);
+				if let Some(catch) = catch {
+					spawned_assets(catch, assets);
+				}
+			},
+			_ => {},
+		}
+	}
+}
+
 /// Transfer funds attached to a [`XCVMProgram`] before dispatching the program to the interpreter.
 fn send_funds_to_interpreter(
 	deps: Deps,
@@ -364,8 +559,43 @@ pub fn reply(deps: DepsMut, _env: Env, msg: Reply) -> StdResult<Response> {
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn query(_deps: Deps, _env: Env, _msg: QueryMsg) -> StdResult<Binary> {
-	Err(StdError::generic_err("not implemented"))
+pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+	match msg {
+		QueryMsg::Config {} => to_binary(&ConfigResponse { config: CONFIG.load(deps.storage)? }),
+		QueryMsg::Interpreter { interpreter_origin } => to_binary(&InterpreterResponse {
+			interpreter: INTERPRETERS.load(deps.storage, interpreter_origin)?,
+		}),
+		QueryMsg::Interpreters { start_after, limit } =>
+			to_binary(&query_interpreters(deps, start_after, limit)?),
+		QueryMsg::EstimateFees { program } => {
+			let Config { fee_schedule, .. } = CONFIG.load(deps.storage)?;
+			let mut fees = Funds::empty();
+			estimate_fees(&fee_schedule, &program.instructions, &mut fees);
+			fees.0.retain(|(_, Displayed(amount))| *amount != 0);
+			to_binary(&EstimateFeesResponse { fees })
+		},
+	}
+}
+
+/// Paginated listing of the interpreters, at most [`MAX_QUERY_LIMIT`] per page.
+fn query_interpreters(
+	deps: Deps,
+	start_after: Option<InterpreterOrigin>,
+	limit: Option<u32>,
+) -> StdResult<InterpretersResponse> {
+	let limit = limit.unwrap_or(DEFAULT_QUERY_LIMIT).min(MAX_QUERY_LIMIT) as usize;
+	let interpreters = INTERPRETERS
+		.range(deps.storage, start_after.map(Bound::exclusive), None, Order::Ascending)
+		.take(limit)
+		.map(|entry| {
+			entry.map(|((network_id, user_id, salt), interpreter)| {
+				let user_origin =
+					UserOrigin { network_id: NetworkId::from(network_id), user_id: user_id.into() };
+				(InterpreterOrigin { user_origin, salt }, interpreter)
+			})
+		})
+		.collect::<StdResult<Vec<_>>>()?;
+	Ok(InterpretersResponse { interpreters })
 }
 
 fn handle_instantiate_reply(deps: DepsMut, msg: Reply) -> StdResult<Response> {
@@ -426,3 +656,130 @@ fn handle_instantiate_reply(deps: DepsMut, msg: Reply) -> StdResult<Response> {
 
 	Ok(Response::new())
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
+	use xcvm_core::{Amount, Asset, Condition, Program, ETH, MAX_PARTS, PICA, USDT};
+
+	fn fee_schedule() -> FeeSchedule {
+		FeeSchedule {
+			network_fees: vec![
+				(2.into(), Funds::from([(PICA::ID, 10_u128)])),
+				(3.into(), Funds::from([(ETH::ID, 1_u128)])),
+			],
+		}
+	}
+
+	fn spawn(network: u32, tip: Option<u128>) -> DefaultXCVMInstruction {
+		Instruction::Spawn {
+			network: network.into(),
+			bridge_security: BridgeSecurity::Deterministic,
+			salt: Vec::new(),
+			assets: Funds::empty(),
+			program: Program { tag: Vec::new(), instructions: VecDeque::new() },
+			deadline: None,
+			tip: tip.map(|tip| Funds(vec![(PICA::ID, Amount::new(tip, MAX_PARTS / 2))])),
+		}
+	}
+
+	#[test]
+	fn fees_are_deducted_from_the_assets() {
+		let fee = Funds::from([(PICA::ID, 10_u128)]);
+		assert_eq!(
+			deduct_fee(Funds::from([(PICA::ID, 100_u128), (ETH::ID, 5_u128)]), &fee).unwrap(),
+			Funds::from([(PICA::ID, 90_u128), (ETH::ID, 5_u128)])
+		);
+		assert!(matches!(
+			deduct_fee(Funds::from([(PICA::ID, 9_u128)]), &fee),
+			Err(ContractError::InsufficientFundsForFees)
+		));
+		assert!(matches!(
+			deduct_fee(Funds::from([(ETH::ID, 100_u128)]), &fee),
+			Err(ContractError::InsufficientFundsForFees)
+		));
+		// Zero fees don't require the asset.
+		assert_eq!(
+			deduct_fee(Funds::from([(ETH::ID, 100_u128)]), &Funds::from([(PICA::ID, 0_u128)]))
+				.unwrap(),
+			Funds::from([(ETH::ID, 100_u128)])
+		);
+	}
+
+	#[test]
+	fn fees_are_estimated_for_every_branch() {
+		let instructions = VecDeque::from([
+			spawn(2, Some(5)),
+			Instruction::If {
+				condition: Condition::CallSucceeded,
+				then: VecDeque::from([spawn(3, None)]),
+				otherwise: VecDeque::from([spawn(2, None)]),
+			},
+			Instruction::Try {
+				body: VecDeque::from([spawn(4, None)]),
+				catch: Some(VecDeque::from([spawn(2, None)])),
+			},
+		]);
+		let mut fees = Funds::empty();
+		estimate_fees(&fee_schedule(), &instructions, &mut fees);
+		// Only the absolute part of the tips is known ahead of the execution.
+		assert_eq!(fees, Funds::from([(PICA::ID, 35_u128), (ETH::ID, 1_u128)]));
+	}
+
+	#[test]
+	fn nested_spawns_are_validated() {
+		let spawn_asset = |asset_id: AssetId| match spawn(2, None) {
+			Instruction::Spawn {
+				network, bridge_security, salt, program, deadline, tip, ..
+			} => Instruction::Spawn {
+				network,
+				bridge_security,
+				salt,
+				assets: Funds(vec![(asset_id, Amount::absolute(1))]),
+				program,
+				deadline,
+				tip,
+			},
+			_ => unreachable!(),
+		};
+		let instructions = VecDeque::from([
+			spawn_asset(PICA::ID),
+			Instruction::If {
+				condition: Condition::CallSucceeded,
+				then: VecDeque::from([spawn_asset(ETH::ID)]),
+				otherwise: VecDeque::new(),
+			},
+			Instruction::Try {
+				body: VecDeque::from([spawn_asset(PICA::ID)]),
+				catch: Some(VecDeque::from([spawn_asset(USDT::ID)])),
+			},
+		]);
+		let mut assets = Vec::new();
+		spawned_assets(&instructions, &mut assets);
+		assert_eq!(assets, [PICA::ID, ETH::ID, USDT::ID]);
+	}
+
+	#[test]
+	fn only_the_gateway_sets_the_fee_schedule() {
+		let mut deps = mock_dependencies();
+		instantiate(
+			deps.as_mut(),
+			mock_env(),
+			mock_info("gateway", &[]),
+			InstantiateMsg {
+				registry_address: "registry".into(),
+				interpreter_code_id: 1,
+				network_id: 1.into(),
+			},
+		)
+		.unwrap();
+		let msg = ExecuteMsg::SetFeeSchedule { fee_schedule: fee_schedule() };
+		assert!(matches!(
+			execute(deps.as_mut(), mock_env(), mock_info("alice", &[]), msg.clone()),
+			Err(ContractError::NotAuthorized)
+		));
+		execute(deps.as_mut(), mock_env(), mock_info("gateway", &[]), msg).unwrap();
+		assert_eq!(CONFIG.load(&deps.storage).unwrap().fee_schedule, fee_schedule());
+	}
+}
@@ -1,6 +1,6 @@
 use cosmwasm_std::StdError;
 use thiserror::Error;
-use xcvm_core::BridgeSecurity;
+use xcvm_core::{BridgeSecurity, Diagnostic};
 
 #[derive(Error, Debug)]
 pub enum ContractError {
@@ -15,4 +15,13 @@ pub enum ContractError {
 
 	#[error("The user did not provide enough fund to cover the execution.")]
 	InsufficientFunds,
+
+	#[error("The program is invalid: {0}")]
+	InvalidProgram(Diagnostic),
+
+	#[error("The spawned assets do not cover the fee of the destination network.")]
+	InsufficientFundsForFees,
+
+	#[error("The asset {0} is not registered.")]
+	UnregisteredAsset(u128),
 }
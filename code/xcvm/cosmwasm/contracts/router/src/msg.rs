@@ -1,6 +1,8 @@
+use crate::state::{Config, Interpreter};
+use cw_xcvm_utils::DefaultXCVMProgram;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-use xcvm_core::NetworkId;
+use xcvm_core::{Displayed, Funds, InterpreterOrigin, NetworkId};
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct InstantiateMsg {
@@ -16,4 +18,37 @@ pub struct InstantiateMsg {
 pub struct MigrateMsg {}
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
-pub enum QueryMsg {}
+#[serde(rename_all = "snake_case")]
+pub enum QueryMsg {
+	/// Configuration of the router.
+	Config {},
+	/// Lookup the interpreter of an origin, including the ones that are not instantiated yet but
+	/// already have a security requirement.
+	Interpreter { interpreter_origin: InterpreterOrigin },
+	/// List the interpreters, ordered by origin.
+	Interpreters { start_after: Option<InterpreterOrigin>, limit: Option<u32> },
+	/// Estimate the fees and tips charged on the spawns of a program executed on this network.
+	/// Only the absolute part of the tips is accounted, the programs spawned on other networks
+	/// being charged by their own routers.
+	EstimateFees { program: DefaultXCVMProgram },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ConfigResponse {
+	pub config: Config,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct InterpreterResponse {
+	pub interpreter: Interpreter,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct InterpretersResponse {
+	pub interpreters: Vec<(InterpreterOrigin, Interpreter)>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct EstimateFeesResponse {
+	pub fees: Funds<Displayed<u128>>,
+}
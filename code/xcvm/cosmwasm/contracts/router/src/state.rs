@@ -1,5 +1,6 @@
 use cosmwasm_std::Addr;
 use cw_storage_plus::{Item, Map};
+use cw_xcvm_common::router::FeeSchedule;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use xcvm_core::{BridgeSecurity, InterpreterOrigin, NetworkId};
@@ -10,6 +11,9 @@ pub struct Config {
 	pub registry_address: Addr,
 	pub interpreter_code_id: u64,
 	pub network_id: NetworkId,
+	/// Fees charged on the spawned programs, set by the admin of the gateway.
+	#[serde(default)]
+	pub fee_schedule: FeeSchedule,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
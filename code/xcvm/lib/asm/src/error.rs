@@ -0,0 +1,15 @@
+use thiserror::Error;
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+#[error("{line}:{column}: {message}")]
+pub struct ParseError {
+	pub line: usize,
+	pub column: usize,
+	pub message: String,
+}
+
+impl ParseError {
+	pub fn new(line: usize, column: usize, message: &str) -> Self {
+		ParseError { line, column, message: message.into() }
+	}
+}
@@ -0,0 +1,142 @@
+use crate::error::ParseError;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Token {
+	/// Keyword or symbolic name, e.g. `transfer`, `Picasso`, `PICA`.
+	Ident(String),
+	/// Unsigned decimal number, possibly with a fractional part, e.g. `100`, `12.5`.
+	Number(String),
+	/// Raw bytes, written either as a `"string"` or as `0x` prefixed hexadecimal.
+	Bytes(Vec<u8>),
+	LBrace,
+	RBrace,
+	Colon,
+	Comma,
+	Percent,
+	Plus,
+	EqEq,
+	GtEq,
+}
+
+/// A token and its position (line, column), both starting at 1.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Spanned {
+	pub token: Token,
+	pub line: usize,
+	pub column: usize,
+}
+
+struct Cursor {
+	chars: Vec<char>,
+	position: usize,
+	line: usize,
+	column: usize,
+}
+
+impl Cursor {
+	fn peek(&self) -> Option<char> {
+		self.chars.get(self.position).copied()
+	}
+
+	fn peek_second(&self) -> Option<char> {
+		self.chars.get(self.position + 1).copied()
+	}
+
+	fn bump(&mut self) -> Option<char> {
+		let c = self.peek()?;
+		self.position += 1;
+		if c == '\n' {
+			self.line += 1;
+			self.column = 1;
+		} else {
+			self.column += 1;
+		}
+		Some(c)
+	}
+
+	fn take_while(&mut self, predicate: impl Fn(char) -> bool) -> String {
+		let mut taken = String::new();
+		while let Some(c) = self.peek().filter(|c| predicate(*c)) {
+			taken.push(c);
+			self.bump();
+		}
+		taken
+	}
+}
+
+/// Split the source into tokens. `//` starts a comment that runs until the end of the line.
+pub fn tokenize(source: &str) -> Result<Vec<Spanned>, ParseError> {
+	let mut tokens = Vec::new();
+	let mut cursor = Cursor { chars: source.chars().collect(), position: 0, line: 1, column: 1 };
+
+	while let Some(c) = cursor.peek() {
+		let (line, column) = (cursor.line, cursor.column);
+		let error = |message: &str| ParseError::new(line, column, message);
+		let token = match c {
+			_ if c.is_whitespace() => {
+				cursor.bump();
+				continue
+			},
+			'/' if cursor.peek_second() == Some('/') => {
+				cursor.take_while(|c| c != '\n');
+				continue
+			},
+			'{' | '}' | ':' | ',' | '%' | '+' => {
+				cursor.bump();
+				match c {
+					'{' => Token::LBrace,
+					'}' => Token::RBrace,
+					':' => Token::Colon,
+					',' => Token::Comma,
+					'%' => Token::Percent,
+					_ => Token::Plus,
+				}
+			},
+			'=' | '>' if cursor.peek_second() == Some('=') => {
+				cursor.bump();
+				cursor.bump();
+				if c == '=' {
+					Token::EqEq
+				} else {
+					Token::GtEq
+				}
+			},
+			'"' => {
+				cursor.bump();
+				let mut value = String::new();
+				loop {
+					match cursor.bump() {
+						None => return Err(error("unterminated string")),
+						Some('"') => break,
+						Some('\\') => match cursor.bump() {
+							Some('"') => value.push('"'),
+							Some('\\') => value.push('\\'),
+							Some('n') => value.push('\n'),
+							Some('t') => value.push('\t'),
+							_ => return Err(error("invalid escape sequence")),
+						},
+						Some(c) => value.push(c),
+					}
+				}
+				Token::Bytes(value.into_bytes())
+			},
+			'0' if cursor.peek_second() == Some('x') => {
+				cursor.bump();
+				cursor.bump();
+				let digits = cursor.take_while(|c| c.is_ascii_hexdigit());
+				Token::Bytes(hex::decode(digits).map_err(|_| error("invalid hexadecimal bytes"))?)
+			},
+			_ if c.is_ascii_digit() => Token::Number(
+				cursor
+					.take_while(|c| c.is_ascii_digit() || c == '.' || c == '_')
+					.replace('_', ""),
+			),
+			_ if c.is_ascii_alphabetic() || c == '_' =>
+				Token::Ident(cursor.take_while(|c| c.is_ascii_alphanumeric() || c == '_')),
+			_ => return Err(error(&format!("unexpected character `{}`", c))),
+		};
+		tokens.push(Spanned { token, line, column });
+	}
+
+	Ok(tokens)
+}
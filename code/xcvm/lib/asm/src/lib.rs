@@ -0,0 +1,238 @@
+//! Human-readable text form of XCVM programs.
+//!
+//! ```text
+//! <program>     ::= "program" <bytes> "{" <instruction>* "}"
+//! <instruction> ::= "transfer" "to" ("relayer" | <bytes>) <assets>
+//!                 | "call" <bytes> ["with" "{" [<index> ":" <binding> {"," ...}] "}"]
+//!                 | "spawn" <network> <security> "salt" <bytes> ["deadline" <integer>]
+//!                   ["tip" <assets>] <assets> <program>
+//!                 | "query" <network> "salt" <bytes>
+//!                 | "assert" <condition>
+//!                 | "if" <condition> "{" <instruction>* "}" ["else" "{" <instruction>* "}"]
+//!                 | "try" "{" <instruction>* "}" ["catch" "{" <instruction>* "}"]
+//! <assets>      ::= "{" [<asset> ":" <amount> {"," ...}] "}"
+//! <amount>      ::= <integer> | <percent> "%" | <integer> "+" <percent> "%"
+//! <binding>     ::= "ip" | "relayer" | "this" | "result" | <asset>
+//! <condition>   ::= "call_succeeded" | "result" "==" <bytes>
+//!                 | "balance" <asset> ">=" <integer> | "not" <condition>
+//! <network>     ::= <name> | <integer>
+//! <asset>       ::= <name> | <integer>
+//! <security>    ::= "insecure" | "optimistic" | "probabilistic" | "deterministic"
+//! <bytes>       ::= "\"" <string> "\"" | "0x" <hex>
+//! ```
+//!
+//! `//` starts a comment running until the end of the line. A percentage is a ratio of the
+//! balance, e.g. `100%` is the whole balance, with at most 16 decimals.
+//!
+//! Networks and assets are named through a [`xcvm_core::Registry`]. [`parse`] and [`print`] use
+//! the default [`xcvm_core::MemoryRegistry`], i.e. `Picasso`, `Juno`, `Ethereum` and `PICA`, `ETH`,
+//! `USDT`, `USDC`, while [`parse_with`] and [`print_with`] accept any registry.
+
+mod error;
+mod lexer;
+mod parser;
+mod printer;
+mod symbols;
+
+pub use crate::{
+	error::*,
+	parser::{parse, parse_with},
+	printer::{print, print_with},
+};
+use std::collections::VecDeque;
+use xcvm_core::{Funds, Instruction, NetworkId, Program, MAX_PARTS};
+use xcvm_proto::Encodable;
+
+pub type AsmInstruction = Instruction<NetworkId, Vec<u8>, Vec<u8>, Funds>;
+pub type AsmProgram = Program<VecDeque<AsmInstruction>>;
+
+/// Parts of [`MAX_PARTS`] representing 1%.
+const PERCENT_PARTS: u128 = MAX_PARTS / 100;
+
+/// Encodings a program can be converted from/to.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Format {
+	/// Text form, see the module documentation.
+	Asm,
+	/// JSON (serde) encoding, as submitted to the CosmWasm contracts.
+	Json,
+	/// Protobuf encoding of `xcvm-proto`, as sent over the bridges.
+	Proto,
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum ConversionError {
+	#[error("{0}")]
+	Parse(#[from] ParseError),
+	#[error("Invalid JSON program: {0}")]
+	Json(#[from] serde_json::Error),
+	#[error("Invalid protobuf program: {0}")]
+	Proto(xcvm_proto::DecodingFailure),
+	#[error("The input is not valid utf8")]
+	Utf8,
+}
+
+/// Decode a program from the given format.
+pub fn decode(format: Format, input: &[u8]) -> Result<AsmProgram, ConversionError> {
+	match format {
+		Format::Asm => Ok(parse(std::str::from_utf8(input).map_err(|_| ConversionError::Utf8)?)?),
+		Format::Json => Ok(serde_json::from_slice(input)?),
+		Format::Proto => xcvm_proto::decode(input).map_err(ConversionError::Proto),
+	}
+}
+
+/// Encode a program to the given format.
+pub fn encode(format: Format, program: AsmProgram) -> Result<Vec<u8>, ConversionError> {
+	match format {
+		Format::Asm => Ok(print(&program).into_bytes()),
+		Format::Json => Ok(serde_json::to_vec_pretty(&program)?),
+		Format::Proto => Ok(program.encode()),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use xcvm_core::{
+		Amount, Asset, AssetId, AssetInfo, BindingValue, BridgeSecurity, Condition, Destination,
+		Displayed, Juno, MemoryRegistry, Network, NetworkInfo, Picasso, Register, PICA, USDC,
+	};
+
+	const SOURCE: &str = r#"program "swap" {
+	call "{\"swap\":{\"to\":\"\"}}" with { 17: this, 20: PICA }
+	// Only bridge the funds if the swap succeeded
+	if not balance USDC >= 1000 {
+		transfer to relayer { PICA: 10 }
+	} else {
+		spawn Juno deterministic salt 0x0102 deadline 1700000000 tip { PICA: 2 } { USDC: 12.5%, PICA: 1 + 50% } program "remote" {
+			transfer to 0xdeadbeef { USDC: 100% }
+			query Picasso salt ""
+		}
+	}
+	assert result == 0x00
+	try {
+		transfer to relayer { USDC: 1 }
+	} catch {}
+}
+"#;
+
+	fn program() -> AsmProgram {
+		Program {
+			tag: b"swap".to_vec(),
+			instructions: VecDeque::from([
+				Instruction::Call {
+					bindings: vec![
+						(17, BindingValue::Register(Register::This)),
+						(20, BindingValue::Asset(PICA::ID)),
+					],
+					encoded: br#"{"swap":{"to":""}}"#.to_vec(),
+				},
+				Instruction::If {
+					condition: Condition::Not(Box::new(Condition::BalanceAtLeast {
+						asset: USDC::ID,
+						amount: Displayed(1000),
+					})),
+					then: VecDeque::from([Instruction::Transfer {
+						to: Destination::Relayer,
+						assets: Funds::from([(PICA::ID, 10_u128)]),
+					}]),
+					otherwise: VecDeque::from([Instruction::Spawn {
+						network: Juno::ID,
+						bridge_security: BridgeSecurity::Deterministic,
+						salt: vec![1, 2],
+						assets: Funds(vec![
+							(USDC::ID, Amount::ratio(MAX_PARTS / 8)),
+							(PICA::ID, Amount::new(1, MAX_PARTS / 2)),
+						]),
+						program: Program {
+							tag: b"remote".to_vec(),
+							instructions: VecDeque::from([
+								Instruction::Transfer {
+									to: Destination::Account(vec![0xde, 0xad, 0xbe, 0xef]),
+									assets: Funds(vec![(USDC::ID, Amount::ratio(MAX_PARTS))]),
+								},
+								Instruction::Query { network: Picasso::ID, salt: Vec::new() },
+							]),
+						},
+						deadline: Some(1700000000),
+						tip: Some(Funds::from([(PICA::ID, 2_u128)])),
+					}]),
+				},
+				Instruction::Assert { condition: Condition::ResultEquals(vec![0]) },
+				Instruction::Try {
+					body: VecDeque::from([Instruction::Transfer {
+						to: Destination::Relayer,
+						assets: Funds::from([(USDC::ID, 1_u128)]),
+					}]),
+					catch: Some(VecDeque::new()),
+				},
+			]),
+		}
+	}
+
+	#[test]
+	fn parse_works() {
+		assert_eq!(parse(SOURCE).unwrap(), program());
+	}
+
+	#[test]
+	fn print_is_the_inverse_of_parse() {
+		let printed = print(&program());
+		assert_eq!(
+			printed,
+			SOURCE.replace("\t// Only bridge the funds if the swap succeeded\n", "")
+		);
+		assert_eq!(parse(&printed).unwrap(), program());
+	}
+
+	#[test]
+	fn slopes_above_the_whole_balance_print_as_100_percent() {
+		let program = |slope| AsmProgram {
+			tag: Vec::new(),
+			instructions: VecDeque::from([Instruction::Transfer {
+				to: Destination::Relayer,
+				assets: Funds::from([(PICA::ID, Amount::new(1, slope))]),
+			}]),
+		};
+		let printed = print(&program(MAX_PARTS * 2));
+		assert_eq!(printed, "program \"\" {\n\ttransfer to relayer { PICA: 1 + 100% }\n}\n");
+		assert_eq!(parse(&printed).unwrap(), program(MAX_PARTS));
+	}
+
+	#[test]
+	fn conversions_roundtrip() {
+		for format in [Format::Asm, Format::Json, Format::Proto] {
+			let encoded = encode(format, program()).unwrap();
+			assert_eq!(decode(format, &encoded).unwrap(), program());
+		}
+	}
+
+	#[test]
+	fn parse_errors_are_located() {
+		assert_eq!(
+			parse("program \"\" {\n\ttransfer to relayer { PICA: 101% }\n}").unwrap_err(),
+			ParseError::new(2, 30, "percentage must be between 0% and 100%")
+		);
+		assert_eq!(
+			parse("program \"\" {\n\tspawn Kusama").unwrap_err(),
+			ParseError::new(2, 8, "unknown network")
+		);
+	}
+
+	#[test]
+	fn runtime_registered_symbols() {
+		let mut registry = MemoryRegistry::default();
+		registry
+			.register_network(NetworkInfo { id: NetworkId(4), name: "Osmosis".into() })
+			.unwrap();
+		registry
+			.register_asset(AssetInfo { id: AssetId::from(5), name: "OSMO".into(), decimals: 6 })
+			.unwrap();
+		let source = "program \"\" {\n\tspawn Osmosis deterministic salt \"\" { OSMO: 1 } program \"\" {}\n}\n";
+		let program = parse_with(source, &registry).unwrap();
+		assert_eq!(print_with(&program, &registry), source);
+		// Without the registry, the identifiers are used instead
+		assert!(print(&program)
+			.starts_with("program \"\" {\n\tspawn 4 deterministic salt \"\" { 5: 1 }"));
+	}
+}
@@ -0,0 +1,84 @@
+use clap::{Parser, ValueEnum};
+use std::io::{Read, Write};
+use xcvm_asm::{decode, encode, Format};
+
+#[derive(ValueEnum, Copy, Clone, Debug)]
+pub enum Encoding {
+	/// Text form of the program.
+	Asm,
+	/// JSON (serde) encoding.
+	Json,
+	/// Binary protobuf encoding.
+	Proto,
+	/// Hexadecimal protobuf encoding.
+	ProtoHex,
+}
+
+impl From<Encoding> for Format {
+	fn from(encoding: Encoding) -> Self {
+		match encoding {
+			Encoding::Asm => Format::Asm,
+			Encoding::Json => Format::Json,
+			Encoding::Proto | Encoding::ProtoHex => Format::Proto,
+		}
+	}
+}
+
+#[derive(Parser, Debug)]
+#[clap(version = "1.0", author = "Composable")]
+/// Convert XCVM programs between the text form, JSON and protobuf.
+pub struct Opts {
+	/// Encoding of the input program.
+	#[clap(short, long, value_enum)]
+	pub from: Encoding,
+
+	/// Encoding of the output program.
+	#[clap(short, long, value_enum)]
+	pub to: Encoding,
+
+	/// Path of the input program, read from the standard input if missing.
+	#[clap(short, long)]
+	pub input: Option<String>,
+
+	/// Path of the output program, written to the standard output if missing.
+	#[clap(short, long)]
+	pub output: Option<String>,
+}
+
+fn main() {
+	let opts = Opts::parse();
+
+	let mut input = match &opts.input {
+		Some(path) => std::fs::read(path).expect("Failed to read the input program"),
+		None => {
+			let mut input = Vec::new();
+			std::io::stdin()
+				.read_to_end(&mut input)
+				.expect("Failed to read the standard input");
+			input
+		},
+	};
+	if let Encoding::ProtoHex = opts.from {
+		input = hex::decode(String::from_utf8_lossy(&input).trim())
+			.expect("Input is not valid hexadecimal");
+	}
+
+	let program = match decode(opts.from.into(), &input) {
+		Ok(program) => program,
+		Err(e) => {
+			eprintln!("{}", e);
+			std::process::exit(1)
+		},
+	};
+	let mut output = encode(opts.to.into(), program).expect("Failed to encode the program");
+	if let Encoding::ProtoHex = opts.to {
+		output = format!("{}\n", hex::encode(output)).into_bytes();
+	}
+
+	match &opts.output {
+		Some(path) => std::fs::write(path, output).expect("Failed to write the output program"),
+		None => std::io::stdout()
+			.write_all(&output)
+			.expect("Failed to write the standard output"),
+	}
+}
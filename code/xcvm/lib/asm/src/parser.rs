@@ -0,0 +1,309 @@
+use crate::{
+	error::ParseError,
+	lexer::{tokenize, Spanned, Token},
+	symbols, AsmInstruction, AsmProgram, PERCENT_PARTS,
+};
+use std::collections::VecDeque;
+use xcvm_core::{
+	Amount, AssetId, BindingValue, Condition, Destination, Displayed, Funds, Instruction,
+	MemoryRegistry, NetworkId, Program, Registry, MAX_PARTS,
+};
+
+/// Parse the text form of a program, naming the networks and assets known at compile time.
+pub fn parse(source: &str) -> Result<AsmProgram, ParseError> {
+	parse_with(source, &MemoryRegistry::default())
+}
+
+/// Parse the text form of a program, resolving the network and asset names through `registry`.
+pub fn parse_with(source: &str, registry: &dyn Registry) -> Result<AsmProgram, ParseError> {
+	let mut parser = Parser { registry, tokens: tokenize(source)?, position: 0 };
+	let program = parser.program()?;
+	match parser.tokens.get(parser.position) {
+		Some(_) => Err(parser.error("expected the end of the program")),
+		None => Ok(program),
+	}
+}
+
+struct Parser<'a> {
+	registry: &'a dyn Registry,
+	tokens: Vec<Spanned>,
+	position: usize,
+}
+
+impl<'a> Parser<'a> {
+	fn error(&self, message: &str) -> ParseError {
+		self.error_at(self.position, message)
+	}
+
+	fn error_at(&self, position: usize, message: &str) -> ParseError {
+		match self.tokens.get(position).or_else(|| self.tokens.last()) {
+			Some(Spanned { line, column, .. }) => ParseError::new(*line, *column, message),
+			None => ParseError::new(1, 1, message),
+		}
+	}
+
+	fn peek(&self) -> Option<&Token> {
+		self.tokens.get(self.position).map(|spanned| &spanned.token)
+	}
+
+	fn next(&mut self, expected: &str) -> Result<Token, ParseError> {
+		let token = self.peek().cloned().ok_or_else(|| {
+			self.error(&format!("expected {}, got the end of the input", expected))
+		})?;
+		self.position += 1;
+		Ok(token)
+	}
+
+	fn eat(&mut self, token: &Token) -> bool {
+		let found = self.peek() == Some(token);
+		if found {
+			self.position += 1;
+		}
+		found
+	}
+
+	fn eat_keyword(&mut self, keyword: &str) -> bool {
+		self.eat(&Token::Ident(keyword.into()))
+	}
+
+	fn expect(&mut self, token: Token, expected: &str) -> Result<(), ParseError> {
+		if self.eat(&token) {
+			Ok(())
+		} else {
+			Err(self.error(&format!("expected {}", expected)))
+		}
+	}
+
+	fn keyword(&mut self, keyword: &str) -> Result<(), ParseError> {
+		self.expect(Token::Ident(keyword.into()), &format!("`{}`", keyword))
+	}
+
+	fn bytes(&mut self) -> Result<Vec<u8>, ParseError> {
+		match self.next("bytes")? {
+			Token::Bytes(bytes) => Ok(bytes),
+			_ => Err(self.back_error("expected a string or 0x prefixed bytes")),
+		}
+	}
+
+	fn back_error(&mut self, message: &str) -> ParseError {
+		self.position -= 1;
+		self.error(message)
+	}
+
+	fn integer<T: core::str::FromStr>(&mut self) -> Result<T, ParseError> {
+		match self.next("a number")? {
+			Token::Number(digits) =>
+				digits.parse().map_err(|_| self.back_error("expected an integer")),
+			_ => Err(self.back_error("expected a number")),
+		}
+	}
+
+	fn network(&mut self) -> Result<NetworkId, ParseError> {
+		match self.next("a network")? {
+			Token::Ident(name) =>
+				self.registry.network(&name).map_err(|_| self.back_error("unknown network")),
+			Token::Number(digits) => digits
+				.parse::<u32>()
+				.map(NetworkId)
+				.map_err(|_| self.back_error("invalid network id")),
+			_ => Err(self.back_error("expected a network")),
+		}
+	}
+
+	fn asset(&mut self) -> Result<AssetId, ParseError> {
+		match self.next("an asset")? {
+			Token::Ident(name) =>
+				self.registry.asset(&name).map_err(|_| self.back_error("unknown asset")),
+			Token::Number(digits) => digits
+				.parse::<u128>()
+				.map(AssetId::from)
+				.map_err(|_| self.back_error("invalid asset id")),
+			_ => Err(self.back_error("expected an asset")),
+		}
+	}
+
+	/// `<integer>`, `<percent>%` or `<integer> + <percent>%`.
+	fn amount(&mut self) -> Result<Amount, ParseError> {
+		let digits = match self.next("an amount")? {
+			Token::Number(digits) => digits,
+			_ => return Err(self.back_error("expected an amount")),
+		};
+		if self.eat(&Token::Percent) {
+			return Ok(Amount::ratio(self.percent(&digits, self.position - 2)?))
+		}
+		let intercept = digits
+			.parse::<u128>()
+			.map_err(|_| self.back_error("expected an integer amount"))?;
+		if !self.eat(&Token::Plus) {
+			return Ok(Amount::absolute(intercept))
+		}
+		let digits = match self.next("a percentage")? {
+			Token::Number(digits) => digits,
+			_ => return Err(self.back_error("expected a percentage")),
+		};
+		self.expect(Token::Percent, "`%`")?;
+		Ok(Amount::new(intercept, self.percent(&digits, self.position - 2)?))
+	}
+
+	/// Convert the decimal percentage of the token at `position` to parts of [`MAX_PARTS`].
+	fn percent(&self, digits: &str, position: usize) -> Result<u128, ParseError> {
+		let (integer, fraction) = digits.split_once('.').unwrap_or((digits, ""));
+		let decimals = PERCENT_PARTS.to_string().len() - 1;
+		if fraction.len() > decimals {
+			return Err(self.error_at(position, "too many decimals in percentage"))
+		}
+		integer
+			.parse::<u128>()
+			.ok()
+			.and_then(|integer| integer.checked_mul(PERCENT_PARTS))
+			.zip(format!("{:0<width$}", fraction, width = decimals).parse::<u128>().ok())
+			.map(|(integer, fraction)| integer + fraction)
+			.filter(|parts| *parts <= MAX_PARTS)
+			.ok_or_else(|| self.error_at(position, "percentage must be between 0% and 100%"))
+	}
+
+	/// `{ <asset>: <amount>, ... }`
+	fn assets(&mut self) -> Result<Funds, ParseError> {
+		self.expect(Token::LBrace, "`{`")?;
+		let mut assets = Vec::new();
+		while !self.eat(&Token::RBrace) {
+			let asset = self.asset()?;
+			self.expect(Token::Colon, "`:`")?;
+			assets.push((asset, self.amount()?));
+			if !self.eat(&Token::Comma) {
+				self.expect(Token::RBrace, "`,` or `}`")?;
+				break
+			}
+		}
+		Ok(Funds(assets))
+	}
+
+	/// `{ <index>: <binding>, ... }`
+	fn bindings(&mut self) -> Result<Vec<(u32, BindingValue)>, ParseError> {
+		self.expect(Token::LBrace, "`{`")?;
+		let mut bindings = Vec::new();
+		while !self.eat(&Token::RBrace) {
+			let index = self.integer()?;
+			self.expect(Token::Colon, "`:`")?;
+			let binding = match self.peek() {
+				Some(Token::Ident(name)) => match symbols::register(name) {
+					Some(register) => {
+						self.position += 1;
+						BindingValue::Register(register)
+					},
+					None => BindingValue::Asset(self.asset()?),
+				},
+				_ => BindingValue::Asset(self.asset()?),
+			};
+			bindings.push((index, binding));
+			if !self.eat(&Token::Comma) {
+				self.expect(Token::RBrace, "`,` or `}`")?;
+				break
+			}
+		}
+		Ok(bindings)
+	}
+
+	fn condition(&mut self) -> Result<Condition, ParseError> {
+		match self.next("a condition")? {
+			Token::Ident(keyword) => match keyword.as_str() {
+				"call_succeeded" => Ok(Condition::CallSucceeded),
+				"result" => {
+					self.expect(Token::EqEq, "`==`")?;
+					Ok(Condition::ResultEquals(self.bytes()?))
+				},
+				"balance" => {
+					let asset = self.asset()?;
+					self.expect(Token::GtEq, "`>=`")?;
+					Ok(Condition::BalanceAtLeast { asset, amount: Displayed(self.integer()?) })
+				},
+				"not" => Ok(Condition::Not(Box::new(self.condition()?))),
+				_ => Err(self.back_error("unknown condition")),
+			},
+			_ => Err(self.back_error("expected a condition")),
+		}
+	}
+
+	/// `{ <instruction>... }`
+	fn block(&mut self) -> Result<VecDeque<AsmInstruction>, ParseError> {
+		self.expect(Token::LBrace, "`{`")?;
+		let mut instructions = VecDeque::new();
+		while !self.eat(&Token::RBrace) {
+			instructions.push_back(self.instruction()?);
+		}
+		Ok(instructions)
+	}
+
+	/// `program <tag> { <instruction>... }`
+	fn program(&mut self) -> Result<AsmProgram, ParseError> {
+		self.keyword("program")?;
+		let tag = self.bytes()?;
+		Ok(Program { tag, instructions: self.block()? })
+	}
+
+	fn instruction(&mut self) -> Result<AsmInstruction, ParseError> {
+		let keyword = match self.next("an instruction")? {
+			Token::Ident(keyword) => keyword,
+			_ => return Err(self.back_error("expected an instruction")),
+		};
+		match keyword.as_str() {
+			"transfer" => {
+				self.keyword("to")?;
+				let to = if self.eat_keyword("relayer") {
+					Destination::Relayer
+				} else {
+					Destination::Account(self.bytes()?)
+				};
+				Ok(Instruction::Transfer { to, assets: self.assets()? })
+			},
+			"call" => {
+				let encoded = self.bytes()?;
+				let bindings = if self.eat_keyword("with") { self.bindings()? } else { Vec::new() };
+				Ok(Instruction::Call { bindings, encoded })
+			},
+			"spawn" => {
+				let network = self.network()?;
+				let bridge_security = match self.next("a bridge security")? {
+					Token::Ident(name) => symbols::security(&name)
+						.ok_or_else(|| self.back_error("unknown bridge security"))?,
+					_ => return Err(self.back_error("expected a bridge security")),
+				};
+				self.keyword("salt")?;
+				let salt = self.bytes()?;
+				let deadline =
+					if self.eat_keyword("deadline") { Some(self.integer()?) } else { None };
+				let tip = if self.eat_keyword("tip") { Some(self.assets()?) } else { None };
+				let assets = self.assets()?;
+				let program = self.program()?;
+				Ok(Instruction::Spawn {
+					network,
+					bridge_security,
+					salt,
+					assets,
+					program,
+					deadline,
+					tip,
+				})
+			},
+			"query" => {
+				let network = self.network()?;
+				self.keyword("salt")?;
+				Ok(Instruction::Query { network, salt: self.bytes()? })
+			},
+			"assert" => Ok(Instruction::Assert { condition: self.condition()? }),
+			"if" => {
+				let condition = self.condition()?;
+				let then = self.block()?;
+				let otherwise =
+					if self.eat_keyword("else") { self.block()? } else { VecDeque::new() };
+				Ok(Instruction::If { condition, then, otherwise })
+			},
+			"try" => {
+				let body = self.block()?;
+				let catch = if self.eat_keyword("catch") { Some(self.block()?) } else { None };
+				Ok(Instruction::Try { body, catch })
+			},
+			_ => Err(self.back_error("unknown instruction")),
+		}
+	}
+}
@@ -0,0 +1,210 @@
+use crate::{symbols, AsmInstruction, AsmProgram, PERCENT_PARTS};
+use std::fmt::{Result, Write};
+use xcvm_core::{
+	Amount, AssetId, BindingValue, Condition, Destination, Funds, MemoryRegistry, NetworkId,
+	Registry, MAX_PARTS,
+};
+
+/// Format a program to its text form, naming the networks and assets known at compile time.
+/// Parsing the output gives back the same program.
+pub fn print(program: &AsmProgram) -> String {
+	print_with(program, &MemoryRegistry::default())
+}
+
+/// Format a program to its text form, naming the networks and assets registered in `registry`.
+/// Unknown networks and assets are written as their identifier.
+pub fn print_with(program: &AsmProgram, registry: &dyn Registry) -> String {
+	let mut output = String::new();
+	write_program(&mut output, registry, program, 0)
+		.expect("writing to a string never fails; qed;");
+	output.push('\n');
+	output
+}
+
+fn indent(output: &mut String, depth: usize) {
+	output.push_str(&"\t".repeat(depth));
+}
+
+fn write_program(
+	output: &mut String,
+	registry: &dyn Registry,
+	program: &AsmProgram,
+	depth: usize,
+) -> Result {
+	write!(output, "program {} ", bytes(&program.tag))?;
+	write_block(output, registry, program.instructions.iter(), depth)
+}
+
+fn write_block<'a>(
+	output: &mut String,
+	registry: &dyn Registry,
+	instructions: impl ExactSizeIterator<Item = &'a AsmInstruction>,
+	depth: usize,
+) -> Result {
+	if instructions.len() == 0 {
+		return output.write_str("{}")
+	}
+	output.push_str("{\n");
+	for instruction in instructions {
+		indent(output, depth + 1);
+		write_instruction(output, registry, instruction, depth + 1)?;
+		output.push('\n');
+	}
+	indent(output, depth);
+	output.write_char('}')
+}
+
+fn write_instruction(
+	output: &mut String,
+	registry: &dyn Registry,
+	instruction: &AsmInstruction,
+	depth: usize,
+) -> Result {
+	match instruction {
+		AsmInstruction::Transfer { to, assets } => {
+			let to = match to {
+				Destination::Account(account) => bytes(account),
+				Destination::Relayer => "relayer".into(),
+			};
+			write!(output, "transfer to {} {}", to, funds(registry, assets))
+		},
+		AsmInstruction::Call { bindings, encoded } => {
+			write!(output, "call {}", bytes(encoded))?;
+			if bindings.is_empty() {
+				Ok(())
+			} else {
+				let bindings = bindings
+					.iter()
+					.map(|(index, binding)| {
+						let binding = match binding {
+							BindingValue::Register(register) =>
+								symbols::register_name(*register).into(),
+							BindingValue::Asset(id) => asset(registry, *id),
+						};
+						format!("{}: {}", index, binding)
+					})
+					.collect::<Vec<_>>();
+				write!(output, " with {{ {} }}", bindings.join(", "))
+			}
+		},
+		AsmInstruction::Spawn {
+			network: id,
+			bridge_security,
+			salt,
+			assets,
+			program,
+			deadline,
+			tip,
+		} => {
+			write!(
+				output,
+				"spawn {} {} salt {} ",
+				network(registry, *id),
+				symbols::security_name(*bridge_security),
+				bytes(salt)
+			)?;
+			if let Some(deadline) = deadline {
+				write!(output, "deadline {} ", deadline)?;
+			}
+			if let Some(tip) = tip {
+				write!(output, "tip {} ", funds(registry, tip))?;
+			}
+			write!(output, "{} ", funds(registry, assets))?;
+			write_program(output, registry, program, depth)
+		},
+		AsmInstruction::Query { network: id, salt } =>
+			write!(output, "query {} salt {}", network(registry, *id), bytes(salt)),
+		AsmInstruction::Assert { condition: c } =>
+			write!(output, "assert {}", condition(registry, c)),
+		AsmInstruction::If { condition: c, then, otherwise } => {
+			write!(output, "if {} ", condition(registry, c))?;
+			write_block(output, registry, then.iter(), depth)?;
+			if !otherwise.is_empty() {
+				output.push_str(" else ");
+				write_block(output, registry, otherwise.iter(), depth)?;
+			}
+			Ok(())
+		},
+		AsmInstruction::Try { body, catch } => {
+			output.push_str("try ");
+			write_block(output, registry, body.iter(), depth)?;
+			if let Some(catch) = catch {
+				output.push_str(" catch ");
+				write_block(output, registry, catch.iter(), depth)?;
+			}
+			Ok(())
+		},
+	}
+}
+
+fn condition(registry: &dyn Registry, condition: &Condition) -> String {
+	match condition {
+		Condition::ResultEquals(expected) => format!("result == {}", bytes(expected)),
+		Condition::BalanceAtLeast { asset: id, amount } =>
+			format!("balance {} >= {}", asset(registry, *id), amount.0),
+		Condition::CallSucceeded => "call_succeeded".into(),
+		Condition::Not(inner) => format!("not {}", self::condition(registry, inner)),
+	}
+}
+
+/// Strings are kept readable, anything else is written as hexadecimal.
+fn bytes(bytes: &[u8]) -> String {
+	match std::str::from_utf8(bytes) {
+		Ok(s) if s.chars().all(|c| !c.is_control() || c == '\n' || c == '\t') => format!(
+			"\"{}\"",
+			s.replace('\\', "\\\\")
+				.replace('"', "\\\"")
+				.replace('\n', "\\n")
+				.replace('\t', "\\t")
+		),
+		_ => format!("0x{}", hex::encode(bytes)),
+	}
+}
+
+fn network(registry: &dyn Registry, network: NetworkId) -> String {
+	registry
+		.network_by_id(network)
+		.map(|network| network.name)
+		.unwrap_or_else(|| network.to_string())
+}
+
+fn asset(registry: &dyn Registry, asset: AssetId) -> String {
+	registry
+		.asset_by_id(asset)
+		.map(|asset| asset.name)
+		.unwrap_or_else(|| asset.0 .0.to_string())
+}
+
+fn funds(registry: &dyn Registry, funds: &Funds) -> String {
+	if funds.0.is_empty() {
+		return "{}".into()
+	}
+	let funds = funds
+		.0
+		.iter()
+		.map(|(id, value)| format!("{}: {}", asset(registry, *id), amount(value)))
+		.collect::<Vec<_>>();
+	format!("{{ {} }}", funds.join(", "))
+}
+
+fn amount(amount: &Amount) -> String {
+	// Applying an amount never takes more than the balance, a slope above `MAX_PARTS` is the same
+	// as the whole balance and the parser only accepts percentages up to 100%.
+	match (amount.intercept.0, amount.slope.0.min(MAX_PARTS)) {
+		(intercept, 0) => intercept.to_string(),
+		(0, slope) => percent(slope),
+		(intercept, slope) => format!("{} + {}", intercept, percent(slope)),
+	}
+}
+
+fn percent(parts: u128) -> String {
+	let fraction = parts % PERCENT_PARTS;
+	let integer = parts / PERCENT_PARTS;
+	if fraction == 0 {
+		format!("{}%", integer)
+	} else {
+		let decimals = PERCENT_PARTS.to_string().len() - 1;
+		let fraction = format!("{:0>width$}", fraction, width = decimals);
+		format!("{}.{}%", integer, fraction.trim_end_matches('0'))
+	}
+}
@@ -0,0 +1,41 @@
+//! Symbolic names of the enumerations of the XCVM. Networks and assets are named by a
+//! [`xcvm_core::Registry`].
+use xcvm_core::{BridgeSecurity, Register};
+
+const SECURITIES: [(&str, BridgeSecurity); 4] = [
+	("insecure", BridgeSecurity::Insecure),
+	("optimistic", BridgeSecurity::Optimistic),
+	("probabilistic", BridgeSecurity::Probabilistic),
+	("deterministic", BridgeSecurity::Deterministic),
+];
+
+const REGISTERS: [(&str, Register); 4] = [
+	("ip", Register::Ip),
+	("relayer", Register::Relayer),
+	("this", Register::This),
+	("result", Register::Result),
+];
+
+fn by_name<T: Copy>(table: &[(&str, T)], name: &str) -> Option<T> {
+	table.iter().find(|(n, _)| *n == name).map(|(_, value)| *value)
+}
+
+fn by_value<T: PartialEq>(table: &[(&'static str, T)], value: &T) -> Option<&'static str> {
+	table.iter().find(|(_, v)| v == value).map(|(name, _)| *name)
+}
+
+pub fn security(name: &str) -> Option<BridgeSecurity> {
+	by_name(&SECURITIES, name)
+}
+
+pub fn security_name(security: BridgeSecurity) -> &'static str {
+	by_value(&SECURITIES, &security).expect("every security is named; qed;")
+}
+
+pub fn register(name: &str) -> Option<Register> {
+	by_name(&REGISTERS, name)
+}
+
+pub fn register_name(register: Register) -> &'static str {
+	by_value(&REGISTERS, &register).expect("every register is named; qed;")
+}
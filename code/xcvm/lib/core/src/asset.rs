@@ -256,6 +256,13 @@ impl<T> Funds<T> {
 	}
 }
 
+impl<T> Default for Funds<T> {
+	#[inline]
+	fn default() -> Self {
+		Funds::empty()
+	}
+}
+
 impl<T, U, V> From<Vec<(U, V)>> for Funds<T>
 where
 	U: Into<AssetId>,
@@ -64,7 +64,7 @@
 //! variants of both of them.
 
 use super::{BindingValue, Bindings};
-use crate::{InterpreterOrigin, NetworkId, OrderedBindings, UserId, UserOrigin};
+use crate::{BridgeId, InterpreterOrigin, NetworkId, OrderedBindings, UserId, UserOrigin};
 use alloc::{fmt::Debug, string::String, vec, vec::Vec};
 use cosmwasm_std::{BankMsg, Coin, CosmosMsg, StdResult};
 use cw_storage_plus::{CwIntKey, Key, KeyDeserialize, Prefixer, PrimaryKey};
@@ -147,6 +147,29 @@ impl KeyDeserialize for UserId {
 	}
 }
 
+impl<'a> PrimaryKey<'a> for BridgeId {
+	type Prefix = ();
+	type SubPrefix = ();
+	type Suffix = Self;
+	type SuperSuffix = Self;
+	fn key(&self) -> Vec<Key> {
+		vec![Key::Ref(self.as_ref())]
+	}
+}
+
+impl<'a> Prefixer<'a> for BridgeId {
+	fn prefix(&self) -> Vec<Key> {
+		vec![Key::Ref(self.as_ref())]
+	}
+}
+
+impl KeyDeserialize for BridgeId {
+	type Output = <Vec<u8> as KeyDeserialize>::Output;
+	fn from_vec(value: Vec<u8>) -> StdResult<Self::Output> {
+		<Vec<u8> as KeyDeserialize>::from_vec(value)
+	}
+}
+
 impl<'a> PrimaryKey<'a> for NetworkId {
 	type Prefix = ();
 	type SubPrefix = ();
@@ -689,4 +712,18 @@ mod tests {
 			.unwrap()
 		);
 	}
+
+	#[test]
+	fn bridge_id_keys_roundtrip() {
+		let mut storage = cosmwasm_std::testing::MockStorage::new();
+		let bridges: cw_storage_plus::Map<BridgeId, u8> = cw_storage_plus::Map::new("bridges");
+		let bridge_id = BridgeId::from(b"bridge".to_vec());
+		bridges.save(&mut storage, bridge_id.clone(), &1).unwrap();
+		let keys = bridges
+			.prefix(())
+			.keys(&storage, None, None, cosmwasm_std::Order::Ascending)
+			.collect::<StdResult<Vec<_>>>()
+			.unwrap();
+		assert_eq!(keys, vec![Vec::from(bridge_id)]);
+	}
 }
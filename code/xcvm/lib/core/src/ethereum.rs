@@ -0,0 +1,324 @@
+//! XCVM SDK for the EVM interpreter
+//!
+//! # Introduction
+//!
+//! The payload of a `Call` executed by the EVM interpreter is the ABI encoded target address
+//! followed by the calldata of the call, i.e. the function selector and the ABI encoded
+//! arguments:
+//! ```text
+//! abi.encode(target) ++ bytes4(keccak256(signature)) ++ abi.encode(arguments...)
+//! ```
+//!
+//! Unlike CosmWasm, a late-binding on the EVM replaces the single placeholder byte at its index
+//! with the 32 bytes ABI encoding of the binding value. [`EthereumCall`] computes those indices,
+//! leaving room for the bound words when encoding the offsets of the dynamic arguments. As for
+//! CosmWasm, a binding must be located before the last byte of the payload: a call ending with a
+//! bound word is followed by a padding byte, ignored by the ABI decoder of the called contract.
+//!
+//! # Examples
+//!
+//! Transfer 1000 units of `PICA` from the interpreter to the relayer:
+//! ```
+//! # use xcvm_core::{ethereum::{EthereumCall, Token}, Asset, BindingValue, Register, PICA};
+//! let call = EthereumCall::new(
+//!     Token::Binding(BindingValue::Asset(PICA::ID)),
+//!     "transfer(address,uint256)",
+//!     vec![Token::Binding(BindingValue::Register(Register::Relayer)), Token::Uint(1000)],
+//! );
+//! let (encoded, bindings) = call.encode().unwrap();
+//! assert_eq!(encoded.len(), 1 + 4 + 1 + 32);
+//! assert_eq!(bindings[1], (5, BindingValue::Register(Register::Relayer)));
+//! ```
+
+use crate::{BindingValue, Bindings, Ethereum, Network, Protocol};
+use alloc::{string::String, vec, vec::Vec};
+use sha3::{Digest, Keccak256};
+
+/// Size of an ABI word.
+const WORD: usize = 32;
+
+/// Error types for the ABI encoding
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum AbiError {
+	/// A `bytesN` value is longer than 32 bytes
+	FixedBytesTooLong,
+	/// The encoded call is too large for the binding indices
+	PayloadTooLarge,
+}
+
+/// Typed Solidity value.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum Token {
+	/// `address`
+	Address([u8; 20]),
+	/// `uintN`
+	Uint(u128),
+	/// `intN`
+	Int(i128),
+	/// `bool`
+	Bool(bool),
+	/// `bytesN`, at most 32 bytes
+	FixedBytes(Vec<u8>),
+	/// `bytes`
+	Bytes(Vec<u8>),
+	/// `string`
+	String(String),
+	/// `T[]`
+	Array(Vec<Token>),
+	/// `(T1, T2, ...)` and `T[N]`
+	Tuple(Vec<Token>),
+	/// Word sized value (`address`, `uint256`...) resolved by the interpreter at execution.
+	Binding(BindingValue),
+}
+
+impl Token {
+	fn is_dynamic(&self) -> bool {
+		match self {
+			Token::Bytes(_) | Token::String(_) | Token::Array(_) => true,
+			Token::Tuple(tokens) => tokens.iter().any(Token::is_dynamic),
+			_ => false,
+		}
+	}
+
+	/// Size of the token in the head of the enclosing tuple.
+	fn head_size(&self) -> usize {
+		match self {
+			Token::Tuple(tokens) if !self.is_dynamic() => tokens.iter().map(Token::head_size).sum(),
+			_ => WORD,
+		}
+	}
+}
+
+/// Part of the encoding, a bound word occupying [`WORD`] bytes once resolved.
+enum Chunk {
+	Bytes(Vec<u8>),
+	Binding(BindingValue),
+}
+
+impl Chunk {
+	fn len(&self) -> usize {
+		match self {
+			Chunk::Bytes(bytes) => bytes.len(),
+			Chunk::Binding(_) => WORD,
+		}
+	}
+}
+
+fn word(value: [u8; 16], padding: u8) -> Chunk {
+	let mut word = vec![padding; WORD - value.len()];
+	word.extend_from_slice(&value);
+	Chunk::Bytes(word)
+}
+
+fn uint(value: usize) -> Chunk {
+	word((value as u128).to_be_bytes(), 0)
+}
+
+fn padded(mut bytes: Vec<u8>) -> Vec<u8> {
+	let padding = (WORD - bytes.len() % WORD) % WORD;
+	bytes.resize(bytes.len() + padding, 0);
+	bytes
+}
+
+fn encode_tuple(tokens: &[Token], output: &mut Vec<Chunk>) -> Result<(), AbiError> {
+	let heads_size = tokens.iter().map(Token::head_size).sum::<usize>();
+	let mut heads = Vec::new();
+	let mut tails = Vec::new();
+	for token in tokens {
+		if token.is_dynamic() {
+			let tails_size = tails.iter().map(Chunk::len).sum::<usize>();
+			heads.push(uint(heads_size + tails_size));
+			encode_token(token, &mut tails)?;
+		} else {
+			encode_token(token, &mut heads)?;
+		}
+	}
+	output.extend(heads);
+	output.extend(tails);
+	Ok(())
+}
+
+fn encode_token(token: &Token, output: &mut Vec<Chunk>) -> Result<(), AbiError> {
+	match token {
+		Token::Address(address) => {
+			let mut word = vec![0; WORD - address.len()];
+			word.extend_from_slice(address);
+			output.push(Chunk::Bytes(word));
+		},
+		Token::Uint(value) => output.push(word(value.to_be_bytes(), 0)),
+		Token::Int(value) =>
+			output.push(word(value.to_be_bytes(), if *value < 0 { 0xFF } else { 0 })),
+		Token::Bool(value) => output.push(uint(*value as usize)),
+		Token::FixedBytes(bytes) => {
+			if bytes.len() > WORD {
+				return Err(AbiError::FixedBytesTooLong)
+			}
+			output.push(Chunk::Bytes(padded(bytes.clone())));
+		},
+		Token::Bytes(bytes) => {
+			output.push(uint(bytes.len()));
+			output.push(Chunk::Bytes(padded(bytes.clone())));
+		},
+		Token::String(string) => {
+			output.push(uint(string.len()));
+			output.push(Chunk::Bytes(padded(string.clone().into_bytes())));
+		},
+		Token::Array(tokens) => {
+			output.push(uint(tokens.len()));
+			encode_tuple(tokens, output)?;
+		},
+		Token::Tuple(tokens) => encode_tuple(tokens, output)?,
+		Token::Binding(binding) => output.push(Chunk::Binding(*binding)),
+	}
+	Ok(())
+}
+
+/// Compute the 4 bytes selector of a function from its canonical signature, e.g.
+/// `transfer(address,uint256)`.
+pub fn selector(signature: &str) -> [u8; 4] {
+	let hash = Keccak256::digest(signature.as_bytes());
+	[hash[0], hash[1], hash[2], hash[3]]
+}
+
+/// Call of a function of a contract deployed on an EVM network.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct EthereumCall {
+	/// The called contract, either a [`Token::Address`] or a [`Token::Binding`].
+	pub target: Token,
+	/// Canonical signature of the function, e.g. `transfer(address,uint256)`.
+	pub signature: String,
+	pub arguments: Vec<Token>,
+}
+
+impl EthereumCall {
+	pub fn new(target: Token, signature: impl Into<String>, arguments: Vec<Token>) -> Self {
+		EthereumCall { target, signature: signature.into(), arguments }
+	}
+
+	/// Encode the call to the payload expected by the EVM interpreter, along with its
+	/// late-bindings.
+	pub fn encode(&self) -> Result<(Vec<u8>, Bindings), AbiError> {
+		let mut chunks = Vec::new();
+		encode_token(&self.target, &mut chunks)?;
+		chunks.push(Chunk::Bytes(selector(&self.signature).to_vec()));
+		encode_tuple(&self.arguments, &mut chunks)?;
+
+		let mut encoded = Vec::new();
+		let mut bindings = Bindings::new();
+		for chunk in chunks {
+			match chunk {
+				Chunk::Bytes(bytes) => encoded.extend(bytes),
+				Chunk::Binding(binding) => {
+					let index =
+						u32::try_from(encoded.len()).map_err(|_| AbiError::PayloadTooLarge)?;
+					bindings.push((index, binding));
+					// Placeholder replaced by the interpreter.
+					encoded.push(0);
+				},
+			}
+		}
+		if matches!(bindings.last(), Some((index, _)) if *index as usize + 1 == encoded.len()) {
+			encoded.push(0);
+		}
+		Ok((encoded, bindings))
+	}
+}
+
+impl Protocol<Ethereum> for EthereumCall {
+	type Error = AbiError;
+	fn serialize(&self) -> Result<<Ethereum as Network>::EncodedCall, Self::Error> {
+		self.encode().map(|(encoded, _)| encoded)
+	}
+
+	fn serialize_with_bindings(
+		&self,
+	) -> Result<(<Ethereum as Network>::EncodedCall, Bindings), Self::Error> {
+		self.encode()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{Asset, Register, USDC};
+
+	fn hex(encoded: &[u8]) -> String {
+		encoded.iter().map(|byte| alloc::format!("{:02x}", byte)).collect()
+	}
+
+	#[test]
+	fn selectors() {
+		assert_eq!(selector("transfer(address,uint256)"), [0xa9, 0x05, 0x9c, 0xbb]);
+		assert_eq!(selector("approve(address,uint256)"), [0x09, 0x5e, 0xa7, 0xb3]);
+	}
+
+	#[test]
+	fn encode_dynamic_arguments() {
+		// Example of the Solidity documentation, `f(uint256,uint32[],bytes10,bytes)` with
+		// `(0x123, [0x456, 0x789], "1234567890", "Hello, world!")`.
+		let call = EthereumCall::new(
+			Token::Address([0x11; 20]),
+			"f(uint256,uint32[],bytes10,bytes)",
+			vec![
+				Token::Uint(0x123),
+				Token::Array(vec![Token::Uint(0x456), Token::Uint(0x789)]),
+				Token::FixedBytes(b"1234567890".to_vec()),
+				Token::Bytes(b"Hello, world!".to_vec()),
+			],
+		);
+		let (encoded, bindings) = call.encode().unwrap();
+		assert!(bindings.is_empty());
+		assert_eq!(
+			hex(&encoded[WORD..]),
+			[
+				"8be65246",
+				"0000000000000000000000000000000000000000000000000000000000000123",
+				"0000000000000000000000000000000000000000000000000000000000000080",
+				"3132333435363738393000000000000000000000000000000000000000000000",
+				"00000000000000000000000000000000000000000000000000000000000000e0",
+				"0000000000000000000000000000000000000000000000000000000000000002",
+				"0000000000000000000000000000000000000000000000000000000000000456",
+				"0000000000000000000000000000000000000000000000000000000000000789",
+				"000000000000000000000000000000000000000000000000000000000000000d",
+				"48656c6c6f2c20776f726c642100000000000000000000000000000000000000",
+			]
+			.concat()
+		);
+	}
+
+	#[test]
+	fn bound_words_are_placeholders() {
+		let call = EthereumCall::new(
+			Token::Address([0x22; 20]),
+			"g(bytes,address,address)",
+			vec![
+				Token::Bytes(vec![0xAB]),
+				Token::Binding(BindingValue::Register(Register::This)),
+				Token::Binding(BindingValue::Asset(USDC::ID)),
+			],
+		);
+		let (encoded, bindings) = call.encode().unwrap();
+		assert_eq!(
+			bindings,
+			vec![
+				(36 + 32, BindingValue::Register(Register::This)),
+				(36 + 32 + 1, BindingValue::Asset(USDC::ID)),
+			]
+		);
+		// The offset of the dynamic argument accounts for the resolved words.
+		assert_eq!(encoded[36 + 31], 0x60);
+		assert_eq!(encoded.len(), 36 + 32 + 2 + 2 * WORD);
+	}
+
+	#[test]
+	fn trailing_bound_word_is_padded() {
+		let call = EthereumCall::new(
+			Token::Binding(BindingValue::Asset(USDC::ID)),
+			"approve(address)",
+			vec![Token::Binding(BindingValue::Register(Register::Relayer))],
+		);
+		let (encoded, bindings) = call.encode().unwrap();
+		assert_eq!(bindings[1], (5, BindingValue::Register(Register::Relayer)));
+		assert_eq!(encoded.len(), 1 + 4 + 1 + 1);
+	}
+}
@@ -1,6 +1,7 @@
-use crate::{AssetId, BridgeSecurity, Program};
+use crate::{AssetId, BridgeSecurity, Displayed, Program};
 use alloc::{
 	borrow::Cow,
+	boxed::Box,
 	collections::{BTreeMap, VecDeque},
 	vec::Vec,
 };
@@ -45,6 +46,23 @@ pub enum Destination<Account> {
 	Relayer,
 }
 
+/// Condition evaluated by the control-flow instructions against the current interpreter state.
+#[cfg_attr(feature = "std", derive(schemars::JsonSchema))]
+#[derive(Clone, PartialEq, Eq, Debug, Encode, Decode, TypeInfo, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Condition {
+	/// The [`Register::Result`] holds exactly the given value, using the same network specific
+	/// encoding as the late-bindings.
+	ResultEquals(Vec<u8>),
+	/// The interpreter ([`Register::This`]) holds at least `amount` of `asset`.
+	#[serde(rename_all = "snake_case")]
+	BalanceAtLeast { asset: AssetId, amount: Displayed<u128> },
+	/// The last executed `Call` succeeded.
+	CallSucceeded,
+	/// Negation of the inner condition.
+	Not(Box<Condition>),
+}
+
 /// Base XCVM instructions.
 /// This set will remain as small as possible, expressiveness must come on `top` of the base
 /// instructions.
@@ -70,6 +88,10 @@ pub enum Instruction<Network, Payload, Account, Assets> {
 	///
 	/// The program will be spawned with the desired [`Assets`].
 	/// The salt is used to track the program when events are dispatched in the network.
+	/// The optional deadline is a unix timestamp, in seconds, after which the program must no
+	/// longer be delivered to the target `network`, tightening the default timeout of the bridge.
+	/// The optional tip is paid, along with the fee charged by the router for the target
+	/// `network`, to the relayer that delivered the current program.
 	#[serde(rename_all = "snake_case")]
 	Spawn {
 		network: Network,
@@ -77,10 +99,26 @@ pub enum Instruction<Network, Payload, Account, Assets> {
 		salt: Vec<u8>,
 		assets: Assets,
 		program: Program<VecDeque<Self>>,
+		#[serde(default)]
+		deadline: Option<u64>,
+		#[serde(default)]
+		tip: Option<Assets>,
 	},
 	/// Query the state of a contract
 	#[serde(rename_all = "snake_case")]
 	Query { network: Network, salt: Vec<u8> },
+	/// Abort the program if the [`Condition`] does not hold.
+	#[serde(rename_all = "snake_case")]
+	Assert { condition: Condition },
+	/// Execute the `then` instructions if the [`Condition`] holds, the `otherwise` instructions
+	/// otherwise. The selected branch is executed in place, before the remaining instructions.
+	#[serde(rename_all = "snake_case")]
+	If { condition: Condition, then: VecDeque<Self>, otherwise: VecDeque<Self> },
+	/// Execute the `body` instructions, reverting them if any fails. The failure is stored in the
+	/// [`Register::Result`] and the `catch` instructions are executed in place instead, the
+	/// remaining funds being returned to the user origin if there is no `catch`.
+	#[serde(rename_all = "snake_case")]
+	Try { body: VecDeque<Self>, catch: Option<VecDeque<Self>> },
 }
 
 /// Error types for late binding operation
@@ -7,14 +7,18 @@ mod asset;
 mod bridge;
 #[cfg(feature = "cosmwasm")]
 pub mod cosmwasm;
+pub mod ethereum;
 mod instruction;
 mod network;
 mod packet;
 mod program;
 mod protocol;
+mod registry;
+mod validation;
 
 pub use crate::{
 	asset::*, bridge::*, instruction::*, network::*, packet::*, program::*, protocol::*,
+	registry::*, validation::*,
 };
 use alloc::{collections::VecDeque, vec::Vec};
 use core::marker::PhantomData;
@@ -27,11 +31,11 @@ pub struct ProgramBuilder<CurrentNetwork, Account, Assets> {
 	pub _marker: PhantomData<CurrentNetwork>,
 }
 
-impl<CurrentNetwork, Account, Assets> ProgramBuilder<CurrentNetwork, Account, Assets>
-where
-	CurrentNetwork: Network,
-	CurrentNetwork::EncodedCall: Into<Vec<u8>>,
-{
+/// Marker of a network that is not known at compile time, but resolved through a [`Registry`].
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct RuntimeNetwork;
+
+impl<CurrentNetwork, Account, Assets> ProgramBuilder<CurrentNetwork, Account, Assets> {
 	#[inline]
 	pub fn new(tag: Vec<u8>) -> Self {
 		ProgramBuilder { tag, instructions: VecDeque::new(), _marker: PhantomData }
@@ -43,6 +47,117 @@ where
 		self
 	}
 
+	/// Spawn a program on a network registered at runtime, resolved by name through the `registry`.
+	/// As the network is only known at runtime, calls of the spawned program are raw bytes.
+	#[allow(clippy::too_many_arguments)]
+	#[inline]
+	pub fn spawn_to<R, E, F>(
+		mut self,
+		registry: &R,
+		network: &str,
+		tag: Vec<u8>,
+		salt: Vec<u8>,
+		bridge_security: BridgeSecurity,
+		assets: Assets,
+		f: F,
+	) -> Result<Self, E>
+	where
+		R: Registry,
+		E: From<RegistryError>,
+		F: FnOnce(
+			ProgramBuilder<RuntimeNetwork, Account, Assets>,
+		) -> Result<ProgramBuilder<RuntimeNetwork, Account, Assets>, E>,
+	{
+		let network = registry.network(network)?;
+		self.instructions.push_back(Instruction::Spawn {
+			bridge_security,
+			salt,
+			assets,
+			network,
+			program: f(ProgramBuilder::<RuntimeNetwork, Account, Assets>::new(tag))?.build(),
+			deadline: None,
+			tip: None,
+		});
+		Ok(self)
+	}
+
+	#[inline]
+	pub fn assert(mut self, condition: Condition) -> Self {
+		self.instructions.push_back(Instruction::Assert { condition });
+		self
+	}
+
+	#[inline]
+	pub fn if_else<E, F, G>(
+		mut self,
+		condition: Condition,
+		then: F,
+		otherwise: G,
+	) -> Result<Self, E>
+	where
+		F: FnOnce(Self) -> Result<Self, E>,
+		G: FnOnce(Self) -> Result<Self, E>,
+	{
+		let then = then(Self::new(Vec::new()))?.instructions;
+		let otherwise = otherwise(Self::new(Vec::new()))?.instructions;
+		self.instructions.push_back(Instruction::If { condition, then, otherwise });
+		Ok(self)
+	}
+
+	/// Add an [`Instruction::Try`], returning the remaining funds to the user origin on failure
+	/// if `catch` is `None`.
+	#[inline]
+	pub fn try_catch<E, F, G>(mut self, body: F, catch: Option<G>) -> Result<Self, E>
+	where
+		F: FnOnce(Self) -> Result<Self, E>,
+		G: FnOnce(Self) -> Result<Self, E>,
+	{
+		let body = body(Self::new(Vec::new()))?.instructions;
+		let catch = catch.map(|catch| catch(Self::new(Vec::new()))).transpose()?;
+		self.instructions
+			.push_back(Instruction::Try { body, catch: catch.map(|catch| catch.instructions) });
+		Ok(self)
+	}
+
+	#[inline]
+	pub fn build(self) -> Program<VecDeque<Instruction<NetworkId, Vec<u8>, Account, Assets>>> {
+		Program { tag: self.tag, instructions: self.instructions }
+	}
+}
+
+impl<CurrentNetwork, Account> ProgramBuilder<CurrentNetwork, Account, Funds> {
+	/// Build the program, failing with the diagnostics of the `validator` if any is an error.
+	#[inline]
+	pub fn build_validated(
+		self,
+		validator: &Validator,
+		funds: Option<&Funds<Displayed<u128>>>,
+	) -> Result<Program<VecDeque<Instruction<NetworkId, Vec<u8>, Account, Funds>>>, Vec<Diagnostic>>
+	{
+		let program = self.build();
+		let diagnostics = validator.validate(&program, funds);
+		if diagnostics.iter().any(Diagnostic::is_error) {
+			Err(diagnostics)
+		} else {
+			Ok(program)
+		}
+	}
+}
+
+impl<Account, Assets> ProgramBuilder<RuntimeNetwork, Account, Assets> {
+	/// Call with an already encoded payload, as the encoding of a [`RuntimeNetwork`] is unknown.
+	#[inline]
+	pub fn call_encoded(mut self, encoded: Vec<u8>) -> Self {
+		self.instructions.push_back(Instruction::Call { bindings: Vec::new(), encoded });
+		self
+	}
+}
+
+impl<CurrentNetwork, Account, Assets> ProgramBuilder<CurrentNetwork, Account, Assets>
+where
+	CurrentNetwork: Network,
+	CurrentNetwork::EncodedCall: Into<Vec<u8>>,
+{
 	#[inline]
 	pub fn spawn<SpawningNetwork, FinalNetwork, E, F>(
 		self,
@@ -70,6 +185,8 @@ where
 			assets,
 			network: SpawningNetwork::ID,
 			program: f(ProgramBuilder::<SpawningNetwork, Account, Assets>::new(tag))?.build(),
+			deadline: None,
+			tip: None,
 		});
 		Ok(builder)
 	}
@@ -82,16 +199,15 @@ where
 	}
 
 	#[inline]
-	pub fn call<T>(self, protocol: T) -> Result<Self, T::Error>
+	pub fn call<T>(mut self, protocol: T) -> Result<Self, T::Error>
 	where
 		T: Protocol<CurrentNetwork>,
 	{
-		protocol.serialize().map(|encoded_call| self.call_raw(encoded_call))
-	}
-
-	#[inline]
-	pub fn build(self) -> Program<VecDeque<Instruction<NetworkId, Vec<u8>, Account, Assets>>> {
-		Program { tag: self.tag, instructions: self.instructions }
+		protocol.serialize_with_bindings().map(|(encoded_call, bindings)| {
+			self.instructions
+				.push_back(Instruction::Call { bindings, encoded: encoded_call.into() });
+			self
+		})
 	}
 }
 
@@ -148,6 +264,73 @@ mod tests {
 		}
 	}
 
+	#[test]
+	fn can_build_conditional() {
+		let condition = Condition::BalanceAtLeast { asset: USDC::ID, amount: Displayed(1_000_000) };
+		let program = ProgramBuilder::<Picasso, (), Funds>::new(Vec::new())
+			.call_raw(vec![0xCA, 0xFE])
+			.assert(Condition::CallSucceeded)
+			.if_else::<(), _, _>(
+				condition.clone(),
+				|then| Ok(then.transfer(Destination::Relayer, Funds::from([(USDC::ID, 1_u128)]))),
+				Ok,
+			)
+			.expect("valid program")
+			.build();
+
+		assert_eq!(
+			program.instructions,
+			VecDeque::from([
+				Instruction::Call { bindings: vec![], encoded: vec![0xCA, 0xFE] },
+				Instruction::Assert { condition: Condition::CallSucceeded },
+				Instruction::If {
+					condition,
+					then: VecDeque::from([Instruction::Transfer {
+						to: Destination::Relayer,
+						assets: Funds::from([(USDC::ID, 1_u128)])
+					}]),
+					otherwise: VecDeque::new(),
+				}
+			])
+		);
+	}
+
+	#[test]
+	fn can_spawn_to_runtime_network() {
+		let mut registry = MemoryRegistry::default();
+		registry
+			.register_network(NetworkInfo { id: NetworkId(4), name: "Osmosis".into() })
+			.expect("new network");
+		let program = ProgramBuilder::<Picasso, (), Funds>::new(Vec::new())
+			.spawn_to::<_, RegistryError, _>(
+				&registry,
+				"Osmosis",
+				Vec::new(),
+				Vec::new(),
+				BridgeSecurity::Deterministic,
+				Funds::empty(),
+				|child| Ok(child.call_encoded(vec![0xCA, 0xFE])),
+			)
+			.expect("registered network")
+			.build();
+		assert!(matches!(
+			program.instructions[0],
+			Instruction::Spawn { network: NetworkId(4), .. }
+		));
+		assert!(matches!(
+			ProgramBuilder::<Picasso, (), Funds>::new(Vec::new()).spawn_to::<_, RegistryError, _>(
+				&registry,
+				"Kusama",
+				Vec::new(),
+				Vec::new(),
+				BridgeSecurity::Deterministic,
+				Funds::empty(),
+				Ok,
+			),
+			Err(RegistryError::UnknownNetwork(_))
+		));
+	}
+
 	#[test]
 	fn can_build() {
 		let program = || -> Result<_, ProgramBuildError> {
@@ -200,7 +383,9 @@ mod tests {
 									assets: Funds::from(vec![(PICA::ID, u128::MAX)])
 								}
 							])
-						}
+						},
+						deadline: None,
+						tip: None,
 					}
 				])
 			},
@@ -1,5 +1,5 @@
 use crate::{Displayed, Funds, UserOrigin};
-use alloc::{vec, vec::Vec};
+use alloc::{string::String, vec, vec::Vec};
 use codec::{Decode, Encode};
 use scale_info::TypeInfo;
 use serde::{Deserialize, Serialize};
@@ -47,4 +47,41 @@ pub struct Packet<Program> {
 	pub program: Program,
 	/// The assets that were attached to the program.
 	pub assets: Funds<Displayed<u128>>,
+	/// Block height of the sending network when the packet was sent.
+	#[serde(default)]
+	pub height: u64,
+}
+
+/// Packet sent by an interpreter to query the state of a remote interpreter.
+#[cfg_attr(feature = "std", derive(schemars::JsonSchema))]
+#[derive(Clone, PartialEq, Eq, Debug, Encode, Decode, TypeInfo, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub struct QueryPacket {
+	/// The interpreter that was the origin of this query, it receives the [`QueryResult`].
+	pub interpreter: Vec<u8>,
+	/// The user owning both the querying and the queried interpreters.
+	pub user_origin: UserOrigin,
+	/// The salt of the queried interpreter.
+	pub salt: Vec<u8>,
+	/// Identifier of the query, chosen by the querying interpreter and delivered back to it with
+	/// the [`QueryResult`].
+	#[serde(default)]
+	pub query_id: u64,
+}
+
+/// Snapshot of the registers and balances of an interpreter, answered to a [`QueryPacket`].
+#[cfg_attr(feature = "std", derive(schemars::JsonSchema))]
+#[derive(Clone, PartialEq, Eq, Debug, Encode, Decode, TypeInfo, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub struct QueryResult {
+	/// Value of the IP register.
+	pub ip: u32,
+	/// Network specific encoding of the result register, empty if it was never set.
+	pub result: Vec<u8>,
+	/// Value of the relayer register, empty if it was never set.
+	pub relayer: Vec<u8>,
+	/// Address of the queried interpreter.
+	pub this: Vec<u8>,
+	/// Balances held by the queried interpreter, indexed by their local denomination.
+	pub balances: Vec<(String, Displayed<u128>)>,
 }
@@ -1,6 +1,12 @@
-use crate::network::Network;
+use crate::{network::Network, Bindings};
 
 pub trait Protocol<N: Network> {
 	type Error;
 	fn serialize(&self) -> Result<N::EncodedCall, Self::Error>;
+
+	/// Serialize the call along with its late-bindings, see [`crate::apply_bindings`]. Protocols
+	/// without late-binding only have to implement [`Protocol::serialize`].
+	fn serialize_with_bindings(&self) -> Result<(N::EncodedCall, Bindings), Self::Error> {
+		self.serialize().map(|encoded_call| (encoded_call, Bindings::new()))
+	}
 }
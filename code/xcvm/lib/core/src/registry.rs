@@ -0,0 +1,217 @@
+use crate::{
+	Amount, Asset, AssetId, Ethereum, Funds, Juno, Network, NetworkId, Picasso, ETH, PICA, USDC,
+	USDT,
+};
+use alloc::{
+	collections::BTreeMap,
+	string::{String, ToString},
+	vec::Vec,
+};
+use codec::{Decode, Encode};
+use scale_info::TypeInfo;
+use serde::{Deserialize, Serialize};
+
+/// Metadata of a network known by the XCVM.
+#[cfg_attr(feature = "std", derive(schemars::JsonSchema))]
+#[derive(Clone, PartialEq, Eq, Debug, Encode, Decode, TypeInfo, Serialize, Deserialize)]
+pub struct NetworkInfo {
+	pub id: NetworkId,
+	/// Symbolic name of the network, e.g. `Picasso`.
+	pub name: String,
+}
+
+/// Metadata of an asset known by the XCVM.
+#[cfg_attr(feature = "std", derive(schemars::JsonSchema))]
+#[derive(Clone, PartialEq, Eq, Debug, Encode, Decode, TypeInfo, Serialize, Deserialize)]
+pub struct AssetInfo {
+	pub id: AssetId,
+	/// Symbolic name of the asset, e.g. `PICA`.
+	pub name: String,
+	/// Number of decimals of the canonical unit of the asset.
+	pub decimals: u8,
+}
+
+/// Error types for the registry operations
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum RegistryError {
+	/// No network is registered under this name
+	UnknownNetwork(String),
+	/// No asset is registered under this name
+	UnknownAsset(String),
+	/// The identifier or the name is already registered
+	AlreadyRegistered,
+}
+
+/// Registry of the networks and assets known by the XCVM.
+///
+/// Unlike [`Networks`](crate::Networks) and [`Assets`](crate::Assets) that are fixed at compile
+/// time, a registry can be extended at runtime, e.g. by loading the content of the asset-registry
+/// and gateway contracts.
+pub trait Registry {
+	fn network_by_id(&self, id: NetworkId) -> Option<NetworkInfo>;
+	fn network_by_name(&self, name: &str) -> Option<NetworkInfo>;
+	fn asset_by_id(&self, id: AssetId) -> Option<AssetInfo>;
+	fn asset_by_name(&self, name: &str) -> Option<AssetInfo>;
+
+	/// Resolve a network by its name.
+	fn network(&self, name: &str) -> Result<NetworkId, RegistryError> {
+		self.network_by_name(name)
+			.map(|network| network.id)
+			.ok_or_else(|| RegistryError::UnknownNetwork(name.into()))
+	}
+
+	/// Resolve an asset by its name.
+	fn asset(&self, name: &str) -> Result<AssetId, RegistryError> {
+		self.asset_by_name(name)
+			.map(|asset| asset.id)
+			.ok_or_else(|| RegistryError::UnknownAsset(name.into()))
+	}
+
+	/// Resolve named assets to [`Funds`].
+	fn funds<'a>(
+		&self,
+		assets: impl IntoIterator<Item = (&'a str, Amount)>,
+	) -> Result<Funds, RegistryError>
+	where
+		Self: Sized,
+	{
+		assets
+			.into_iter()
+			.map(|(name, amount)| Ok((self.asset(name)?, amount)))
+			.collect::<Result<Vec<_>, _>>()
+			.map(Funds)
+	}
+}
+
+/// In-memory [`Registry`].
+///
+/// The default registry contains the networks and assets known at compile time.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct MemoryRegistry {
+	networks: BTreeMap<NetworkId, NetworkInfo>,
+	assets: BTreeMap<AssetId, AssetInfo>,
+}
+
+impl MemoryRegistry {
+	/// A registry without any network nor asset.
+	#[inline]
+	pub fn empty() -> Self {
+		MemoryRegistry { networks: BTreeMap::new(), assets: BTreeMap::new() }
+	}
+
+	pub fn register_network(&mut self, network: NetworkInfo) -> Result<(), RegistryError> {
+		if self.networks.contains_key(&network.id) || self.network_by_name(&network.name).is_some()
+		{
+			return Err(RegistryError::AlreadyRegistered)
+		}
+		self.networks.insert(network.id, network);
+		Ok(())
+	}
+
+	pub fn register_asset(&mut self, asset: AssetInfo) -> Result<(), RegistryError> {
+		if self.assets.contains_key(&asset.id) || self.asset_by_name(&asset.name).is_some() {
+			return Err(RegistryError::AlreadyRegistered)
+		}
+		self.assets.insert(asset.id, asset);
+		Ok(())
+	}
+
+	pub fn unregister_network(&mut self, id: NetworkId) -> Option<NetworkInfo> {
+		self.networks.remove(&id)
+	}
+
+	pub fn unregister_asset(&mut self, id: AssetId) -> Option<AssetInfo> {
+		self.assets.remove(&id)
+	}
+
+	pub fn networks(&self) -> impl Iterator<Item = &NetworkInfo> {
+		self.networks.values()
+	}
+
+	pub fn assets(&self) -> impl Iterator<Item = &AssetInfo> {
+		self.assets.values()
+	}
+}
+
+impl Default for MemoryRegistry {
+	fn default() -> Self {
+		let networks = [(Picasso::ID, "Picasso"), (Juno::ID, "Juno"), (Ethereum::ID, "Ethereum")];
+		let assets = [
+			(PICA::ID, "PICA", 12),
+			(ETH::ID, "ETH", 18),
+			(USDT::ID, "USDT", 6),
+			(USDC::ID, "USDC", 6),
+		];
+		MemoryRegistry {
+			networks: networks
+				.into_iter()
+				.map(|(id, name)| (id, NetworkInfo { id, name: name.to_string() }))
+				.collect(),
+			assets: assets
+				.into_iter()
+				.map(|(id, name, decimals)| {
+					(id, AssetInfo { id, name: name.to_string(), decimals })
+				})
+				.collect(),
+		}
+	}
+}
+
+impl Registry for MemoryRegistry {
+	fn network_by_id(&self, id: NetworkId) -> Option<NetworkInfo> {
+		self.networks.get(&id).cloned()
+	}
+
+	fn network_by_name(&self, name: &str) -> Option<NetworkInfo> {
+		self.networks.values().find(|network| network.name == name).cloned()
+	}
+
+	fn asset_by_id(&self, id: AssetId) -> Option<AssetInfo> {
+		self.assets.get(&id).cloned()
+	}
+
+	fn asset_by_name(&self, name: &str) -> Option<AssetInfo> {
+		self.assets.values().find(|asset| asset.name == name).cloned()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn default_registry_matches_static_lists() {
+		let registry = MemoryRegistry::default();
+		assert_eq!(registry.network("Juno"), Ok(Juno::ID));
+		assert_eq!(registry.asset("USDC"), Ok(USDC::ID));
+		assert_eq!(registry.asset_by_id(PICA::ID).map(|asset| asset.decimals), Some(12));
+	}
+
+	#[test]
+	fn can_register_at_runtime() {
+		let mut registry = MemoryRegistry::default();
+		assert_eq!(
+			registry.network("Osmosis"),
+			Err(RegistryError::UnknownNetwork("Osmosis".into()))
+		);
+		registry
+			.register_network(NetworkInfo { id: NetworkId(4), name: "Osmosis".into() })
+			.unwrap();
+		registry
+			.register_asset(AssetInfo { id: AssetId::from(5), name: "OSMO".into(), decimals: 6 })
+			.unwrap();
+		assert_eq!(registry.network("Osmosis"), Ok(NetworkId(4)));
+		assert_eq!(
+			registry.funds([("OSMO", Amount::absolute(1)), ("PICA", Amount::absolute(2))]),
+			Ok(Funds::from([(AssetId::from(5), 1_u128), (PICA::ID, 2_u128)]))
+		);
+		assert_eq!(
+			registry.register_asset(AssetInfo {
+				id: AssetId::from(6),
+				name: "OSMO".into(),
+				decimals: 6
+			}),
+			Err(RegistryError::AlreadyRegistered)
+		);
+	}
+}
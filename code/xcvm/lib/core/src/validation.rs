@@ -0,0 +1,527 @@
+use crate::{AssetId, Displayed, Funds, Instruction, NetworkId, Program, Registry, MAX_PARTS};
+use alloc::{
+	collections::{BTreeMap, VecDeque},
+	vec::Vec,
+};
+use core::fmt::{self, Display};
+
+/// Default maximum number of nested [`Instruction::Spawn`].
+pub const DEFAULT_MAX_SPAWN_DEPTH: u32 = 8;
+
+/// Step from a program to one of its instructions.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum PathStep {
+	/// Index of the instruction in the current block.
+	Instruction(usize),
+	/// `then` branch of an [`Instruction::If`].
+	Then,
+	/// `otherwise` branch of an [`Instruction::If`].
+	Otherwise,
+	/// `body` of an [`Instruction::Try`].
+	Body,
+	/// `catch` block of an [`Instruction::Try`].
+	Catch,
+	/// Program spawned by an [`Instruction::Spawn`].
+	Spawned,
+}
+
+/// Location of an instruction, starting from the root program, e.g. `[1].then[0].spawned[2]`.
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub struct InstructionPath(pub Vec<PathStep>);
+
+impl InstructionPath {
+	fn join(&self, step: PathStep) -> Self {
+		let mut path = self.clone();
+		path.0.push(step);
+		path
+	}
+}
+
+impl Display for InstructionPath {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		for step in &self.0 {
+			match step {
+				PathStep::Instruction(index) => write!(f, "[{}]", index)?,
+				PathStep::Then => f.write_str(".then")?,
+				PathStep::Otherwise => f.write_str(".otherwise")?,
+				PathStep::Body => f.write_str(".body")?,
+				PathStep::Catch => f.write_str(".catch")?,
+				PathStep::Spawned => f.write_str(".spawned")?,
+			}
+		}
+		Ok(())
+	}
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Severity {
+	/// The program is guaranteed to fail or to be rejected.
+	Error,
+	/// The program might fail, depending on the state of the interpreter at runtime.
+	Warning,
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum DiagnosticKind {
+	/// Binding indices must be strictly increasing, see [`crate::apply_bindings`].
+	UnsortedBindings { index: u32 },
+	/// A binding must be located before the last byte of the payload.
+	BindingOutOfBounds { index: u32, payload_len: usize },
+	/// The slope of an [`crate::Amount`] is greater than [`MAX_PARTS`].
+	RatioExceedsMaxParts { asset: AssetId },
+	/// Spawned programs are nested deeper than the configured limit.
+	SpawnTooDeep { depth: u32, max: u32 },
+	/// The target network is not known by the registry.
+	UnknownNetwork { network: NetworkId },
+	/// The absolute amounts transferred or spawned exceed the funds attached to the program.
+	ExceedsAttachedFunds { asset: AssetId, required: u128, available: u128 },
+}
+
+impl DiagnosticKind {
+	pub fn severity(&self) -> Severity {
+		match self {
+			// Calls might bring new funds, e.g. a swap, so this is not necessarily a failure.
+			DiagnosticKind::ExceedsAttachedFunds { .. } => Severity::Warning,
+			_ => Severity::Error,
+		}
+	}
+}
+
+impl Display for DiagnosticKind {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			DiagnosticKind::UnsortedBindings { index } =>
+				write!(f, "binding index {} is not greater than the previous one", index),
+			DiagnosticKind::BindingOutOfBounds { index, payload_len } =>
+				write!(f, "binding index {} is out of a payload of {} bytes", index, payload_len),
+			DiagnosticKind::RatioExceedsMaxParts { asset } =>
+				write!(f, "ratio of asset {} exceeds {} parts", asset.0 .0, MAX_PARTS),
+			DiagnosticKind::SpawnTooDeep { depth, max } =>
+				write!(f, "spawn depth {} exceeds the limit of {}", depth, max),
+			DiagnosticKind::UnknownNetwork { network } => write!(f, "unknown network {}", network),
+			DiagnosticKind::ExceedsAttachedFunds { asset, required, available } => write!(
+				f,
+				"{} of asset {} required while only {} is attached",
+				required, asset.0 .0, available
+			),
+		}
+	}
+}
+
+/// Issue found by the [`Validator`], located at `path`.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Diagnostic {
+	pub path: InstructionPath,
+	pub kind: DiagnosticKind,
+}
+
+impl Diagnostic {
+	#[inline]
+	pub fn is_error(&self) -> bool {
+		self.kind.severity() == Severity::Error
+	}
+}
+
+impl Display for Diagnostic {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "{}: {}", self.path, self.kind)
+	}
+}
+
+/// Absolute amount of each asset a program can spend. Assets attached as a ratio are unbounded.
+type Available = BTreeMap<AssetId, u128>;
+
+/// Static checks of a program, run before submitting it so that a malformed program fails before
+/// being bridged.
+pub struct Validator<'a> {
+	registry: &'a dyn Registry,
+	max_spawn_depth: u32,
+}
+
+impl<'a> Validator<'a> {
+	/// A validator resolving the target networks through `registry`.
+	#[inline]
+	pub fn new(registry: &'a dyn Registry) -> Self {
+		Validator { registry, max_spawn_depth: DEFAULT_MAX_SPAWN_DEPTH }
+	}
+
+	#[inline]
+	pub fn with_max_spawn_depth(mut self, max_spawn_depth: u32) -> Self {
+		self.max_spawn_depth = max_spawn_depth;
+		self
+	}
+
+	/// Check the program, returning every diagnostic found.
+	///
+	/// * `funds`: assets attached to the program, if known. Transfers are only checked against the
+	///   attached funds when they are provided.
+	pub fn validate<Account, Payload>(
+		&self,
+		program: &Program<VecDeque<Instruction<NetworkId, Payload, Account, Funds>>>,
+		funds: Option<&Funds<Displayed<u128>>>,
+	) -> Vec<Diagnostic>
+	where
+		Payload: AsRef<[u8]>,
+	{
+		let mut diagnostics = Vec::new();
+		let mut available = funds.map(|funds| {
+			let mut available = Available::new();
+			for (asset, Displayed(amount)) in &funds.0 {
+				let entry = available.entry(*asset).or_default();
+				*entry = entry.saturating_add(*amount);
+			}
+			available
+		});
+		self.validate_block(
+			&program.instructions,
+			&InstructionPath::default(),
+			0,
+			&mut available,
+			&mut diagnostics,
+		);
+		diagnostics
+	}
+
+	fn validate_block<Account, Payload>(
+		&self,
+		instructions: &VecDeque<Instruction<NetworkId, Payload, Account, Funds>>,
+		path: &InstructionPath,
+		depth: u32,
+		available: &mut Option<Available>,
+		diagnostics: &mut Vec<Diagnostic>,
+	) where
+		Payload: AsRef<[u8]>,
+	{
+		for (index, instruction) in instructions.iter().enumerate() {
+			let path = path.join(PathStep::Instruction(index));
+			let mut report = |kind| diagnostics.push(Diagnostic { path: path.clone(), kind });
+			match instruction {
+				Instruction::Transfer { assets, .. } => {
+					check_ratios(assets, &mut report);
+					spend(assets, available, &mut report);
+				},
+				Instruction::Call { bindings, encoded } => {
+					let payload_len = encoded.as_ref().len();
+					let mut previous = None;
+					for (binding_index, _) in bindings {
+						if matches!(previous, Some(previous) if *binding_index <= previous) {
+							report(DiagnosticKind::UnsortedBindings { index: *binding_index });
+						}
+						// Mirror the bound check of `apply_bindings`.
+						if *binding_index as usize + 1 >= payload_len {
+							report(DiagnosticKind::BindingOutOfBounds {
+								index: *binding_index,
+								payload_len,
+							});
+						}
+						previous = Some(*binding_index);
+					}
+				},
+				Instruction::Spawn { network, assets, program, tip, .. } => {
+					check_ratios(assets, &mut report);
+					check_network(self.registry, *network, &mut report);
+					spend(assets, available, &mut report);
+					if let Some(tip) = tip {
+						check_ratios(tip, &mut report);
+						spend(tip, available, &mut report);
+					}
+					if depth + 1 > self.max_spawn_depth {
+						report(DiagnosticKind::SpawnTooDeep {
+							depth: depth + 1,
+							max: self.max_spawn_depth,
+						});
+					} else {
+						let mut spawned_available = Some(
+							assets
+								.0
+								.iter()
+								.map(|(asset, amount)| {
+									let amount = if amount.is_absolute() {
+										amount.intercept.0
+									} else {
+										u128::MAX
+									};
+									(*asset, amount)
+								})
+								.collect(),
+						);
+						self.validate_block(
+							&program.instructions,
+							&path.join(PathStep::Spawned),
+							depth + 1,
+							&mut spawned_available,
+							diagnostics,
+						);
+					}
+				},
+				Instruction::Query { network, .. } =>
+					check_network(self.registry, *network, &mut report),
+				Instruction::Assert { .. } => {},
+				Instruction::If { then, otherwise, .. } => {
+					let mut then_available = available.clone();
+					let mut otherwise_available = available.clone();
+					self.validate_block(
+						then,
+						&path.join(PathStep::Then),
+						depth,
+						&mut then_available,
+						diagnostics,
+					);
+					self.validate_block(
+						otherwise,
+						&path.join(PathStep::Otherwise),
+						depth,
+						&mut otherwise_available,
+						diagnostics,
+					);
+					// Only one branch is executed, keep the worst case.
+					keep_worst_case(available, then_available, otherwise_available);
+				},
+				Instruction::Try { body, catch } => {
+					let mut body_available = available.clone();
+					self.validate_block(
+						body,
+						&path.join(PathStep::Body),
+						depth,
+						&mut body_available,
+						diagnostics,
+					);
+					// The body is reverted on failure, either it or the catch block is executed.
+					let catch_available = match catch {
+						Some(catch) => {
+							let mut catch_available = available.clone();
+							self.validate_block(
+								catch,
+								&path.join(PathStep::Catch),
+								depth,
+								&mut catch_available,
+								diagnostics,
+							);
+							catch_available
+						},
+						// The remaining funds are returned to the user origin.
+						None => available.as_ref().map(|_| Available::new()),
+					};
+					keep_worst_case(available, body_available, catch_available);
+				},
+			}
+		}
+	}
+}
+
+fn keep_worst_case(
+	available: &mut Option<Available>,
+	left: Option<Available>,
+	right: Option<Available>,
+) {
+	if let (Some(available), Some(left), Some(right)) = (available.as_mut(), left, right) {
+		for (asset, amount) in available.iter_mut() {
+			let left = left.get(asset).copied().unwrap_or_default();
+			let right = right.get(asset).copied().unwrap_or_default();
+			*amount = left.min(right);
+		}
+	}
+}
+
+fn check_ratios(assets: &Funds, report: &mut impl FnMut(DiagnosticKind)) {
+	for (asset, amount) in &assets.0 {
+		if amount.slope.0 > MAX_PARTS {
+			report(DiagnosticKind::RatioExceedsMaxParts { asset: *asset });
+		}
+	}
+}
+
+fn check_network(
+	registry: &dyn Registry,
+	network: NetworkId,
+	report: &mut impl FnMut(DiagnosticKind),
+) {
+	if registry.network_by_id(network).is_none() {
+		report(DiagnosticKind::UnknownNetwork { network });
+	}
+}
+
+fn spend(
+	assets: &Funds,
+	available: &mut Option<Available>,
+	report: &mut impl FnMut(DiagnosticKind),
+) {
+	let available = match available {
+		Some(available) => available,
+		None => return,
+	};
+	for (asset, amount) in &assets.0 {
+		let required = amount.intercept.0;
+		if required == 0 {
+			continue
+		}
+		let remaining = available.entry(*asset).or_default();
+		match remaining.checked_sub(required) {
+			Some(left) => *remaining = left,
+			None => {
+				report(DiagnosticKind::ExceedsAttachedFunds {
+					asset: *asset,
+					required,
+					available: *remaining,
+				});
+				*remaining = 0;
+			},
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{
+		Amount, Asset, BindingValue, BridgeSecurity, Condition, Destination, MemoryRegistry,
+		Register, PICA, USDC,
+	};
+	use alloc::vec;
+
+	type TestInstruction = Instruction<NetworkId, Vec<u8>, (), Funds>;
+
+	fn program(instructions: Vec<TestInstruction>) -> Program<VecDeque<TestInstruction>> {
+		Program { tag: Vec::new(), instructions: instructions.into() }
+	}
+
+	fn spawn(network: NetworkId, instructions: Vec<TestInstruction>) -> TestInstruction {
+		Instruction::Spawn {
+			network,
+			bridge_security: BridgeSecurity::Deterministic,
+			salt: Vec::new(),
+			assets: Funds::empty(),
+			program: program(instructions),
+			deadline: None,
+			tip: None,
+		}
+	}
+
+	#[test]
+	fn valid_program() {
+		let registry = MemoryRegistry::default();
+		let program = program(vec![
+			Instruction::Call {
+				bindings: vec![(1, BindingValue::Register(Register::This))],
+				encoded: b"{\"\"}".to_vec(),
+			},
+			Instruction::Transfer {
+				to: Destination::Relayer,
+				assets: Funds(vec![(PICA::ID, Amount::new(10, MAX_PARTS))]),
+			},
+		]);
+		let funds = Funds::from([(PICA::ID, 10_u128)]);
+		assert_eq!(Validator::new(&registry).validate(&program, Some(&funds)), vec![]);
+	}
+
+	#[test]
+	fn diagnostics_are_located() {
+		let registry = MemoryRegistry::default();
+		let program = program(vec![Instruction::If {
+			condition: Condition::CallSucceeded,
+			then: VecDeque::new(),
+			otherwise: VecDeque::from([spawn(
+				NetworkId(1),
+				vec![
+					Instruction::Call {
+						bindings: vec![
+							(2, BindingValue::Register(Register::Ip)),
+							(1, BindingValue::Register(Register::Ip)),
+						],
+						encoded: vec![0; 3],
+					},
+					Instruction::Transfer {
+						to: Destination::Relayer,
+						assets: Funds(vec![(USDC::ID, Amount::new(1, MAX_PARTS + 1))]),
+					},
+					spawn(NetworkId(42), vec![spawn(NetworkId(1), vec![])]),
+				],
+			)]),
+		}]);
+		let located =
+			|path: &[PathStep], kind| Diagnostic { path: InstructionPath(path.to_vec()), kind };
+		let spawned = [
+			PathStep::Instruction(0),
+			PathStep::Otherwise,
+			PathStep::Instruction(0),
+			PathStep::Spawned,
+		];
+		let at = |index: usize| [&spawned[..], &[PathStep::Instruction(index)]].concat();
+		let diagnostics =
+			Validator::new(&registry).with_max_spawn_depth(2).validate(&program, None);
+		assert_eq!(
+			diagnostics,
+			vec![
+				located(&at(0), DiagnosticKind::BindingOutOfBounds { index: 2, payload_len: 3 }),
+				located(&at(0), DiagnosticKind::UnsortedBindings { index: 1 }),
+				located(&at(1), DiagnosticKind::RatioExceedsMaxParts { asset: USDC::ID }),
+				located(
+					&at(1),
+					DiagnosticKind::ExceedsAttachedFunds {
+						asset: USDC::ID,
+						required: 1,
+						available: 0
+					}
+				),
+				located(&at(2), DiagnosticKind::UnknownNetwork { network: NetworkId(42) }),
+				located(
+					&[&at(2)[..], &[PathStep::Spawned, PathStep::Instruction(0)]].concat(),
+					DiagnosticKind::SpawnTooDeep { depth: 3, max: 2 }
+				),
+			]
+		);
+		assert_eq!(
+			alloc::format!("{}", diagnostics[0]),
+			"[0].otherwise[0].spawned[0]: binding index 2 is out of a payload of 3 bytes"
+		);
+	}
+
+	#[test]
+	fn failed_try_returns_the_funds() {
+		let registry = MemoryRegistry::default();
+		let transfer = |amount: u128| Instruction::Transfer {
+			to: Destination::Relayer,
+			assets: Funds::from([(PICA::ID, amount)]),
+		};
+		let funds = Funds::from([(PICA::ID, 10_u128)]);
+		let program = program(vec![
+			Instruction::Try {
+				body: VecDeque::from([transfer(4)]),
+				catch: Some(VecDeque::from([transfer(2)])),
+			},
+			transfer(6),
+			Instruction::Try { body: VecDeque::new(), catch: None },
+			transfer(1),
+		]);
+		assert_eq!(
+			Validator::new(&registry).validate(&program, Some(&funds)),
+			vec![Diagnostic {
+				path: InstructionPath(vec![PathStep::Instruction(3)]),
+				kind: DiagnosticKind::ExceedsAttachedFunds {
+					asset: PICA::ID,
+					required: 1,
+					available: 0
+				},
+			}]
+		);
+	}
+
+	#[test]
+	fn binding_bounds_match_apply_bindings() {
+		let registry = MemoryRegistry::default();
+		for index in 0..4 {
+			let bindings = vec![(index, BindingValue::Register(Register::This))];
+			let encoded = b"{\"\"}".to_vec();
+			let program = program(vec![Instruction::Call {
+				bindings: bindings.clone(),
+				encoded: encoded.clone(),
+			}]);
+			let rejected = !Validator::new(&registry).validate(&program, None).is_empty();
+			let mut formatted = vec![0; encoded.len() + 4];
+			let applied = crate::apply_bindings(encoded, bindings, &mut formatted, |_| {
+				Ok::<_, ()>(alloc::borrow::Cow::Borrowed(&b"this"[..]))
+			});
+			assert_eq!(rejected, applied.is_err(), "binding at {}", index);
+		}
+	}
+}
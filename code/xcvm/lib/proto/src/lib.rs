@@ -4,7 +4,7 @@ extern crate alloc;
 
 use core::fmt::Display;
 
-use alloc::{collections::VecDeque, format, vec::Vec};
+use alloc::{boxed::Box, collections::VecDeque, format, string::String, vec::Vec};
 use fixed::{types::extra::U16, FixedU128};
 use prost::{DecodeError, Message};
 use xcvm_core::{Amount, Destination, Displayed, Funds, NetworkId, MAX_PARTS};
@@ -64,6 +64,22 @@ where
 		.and_then(|x| TryInto::try_into(x).map_err(|_| DecodingFailure::Isomorphism))
 }
 
+pub fn decode_query_packet(
+	buffer: &[u8],
+) -> core::result::Result<xcvm_core::QueryPacket, DecodingFailure> {
+	QueryPacket::decode(buffer)
+		.map_err(DecodingFailure::Protobuf)
+		.and_then(|x| TryInto::try_into(x).map_err(|_| DecodingFailure::Isomorphism))
+}
+
+pub fn decode_query_result(
+	buffer: &[u8],
+) -> core::result::Result<xcvm_core::QueryResult, DecodingFailure> {
+	QueryResult::decode(buffer)
+		.map_err(DecodingFailure::Protobuf)
+		.and_then(|x| TryInto::try_into(x).map_err(|_| DecodingFailure::Isomorphism))
+}
+
 impl<TNetwork, TAbiEncoded, TAccount, TAssets> Encodable
 	for XCVMPacket<TNetwork, TAbiEncoded, TAccount, TAssets>
 where
@@ -77,6 +93,82 @@ where
 	}
 }
 
+impl Encodable for xcvm_core::QueryPacket {
+	fn encode(self) -> Vec<u8> {
+		QueryPacket::encode_to_vec(&self.into())
+	}
+}
+
+impl Encodable for xcvm_core::QueryResult {
+	fn encode(self) -> Vec<u8> {
+		QueryResult::encode_to_vec(&self.into())
+	}
+}
+
+impl From<xcvm_core::QueryPacket> for QueryPacket {
+	fn from(value: xcvm_core::QueryPacket) -> Self {
+		QueryPacket {
+			interpreter: Some(Account { account: value.interpreter }),
+			user_origin: Some(value.user_origin.into()),
+			salt: Some(value.salt.into()),
+			query_id: value.query_id,
+		}
+	}
+}
+
+impl TryFrom<QueryPacket> for xcvm_core::QueryPacket {
+	type Error = ();
+	fn try_from(value: QueryPacket) -> core::result::Result<Self, Self::Error> {
+		Ok(xcvm_core::QueryPacket {
+			interpreter: value.interpreter.ok_or(())?.account,
+			user_origin: value.user_origin.ok_or(())?.try_into()?,
+			salt: value.salt.ok_or(())?.salt,
+			query_id: value.query_id,
+		})
+	}
+}
+
+impl From<xcvm_core::QueryResult> for QueryResult {
+	fn from(value: xcvm_core::QueryResult) -> Self {
+		QueryResult {
+			ip: value.ip,
+			result: value.result,
+			relayer: Some(Account { account: value.relayer }),
+			self_: Some(Account { account: value.this }),
+			balances: value
+				.balances
+				.into_iter()
+				.map(|(denom, Displayed(amount))| QueryBalance {
+					denom,
+					amount: Some(amount.into()),
+				})
+				.collect(),
+		}
+	}
+}
+
+impl TryFrom<QueryResult> for xcvm_core::QueryResult {
+	type Error = ();
+	fn try_from(value: QueryResult) -> core::result::Result<Self, Self::Error> {
+		Ok(xcvm_core::QueryResult {
+			ip: value.ip,
+			result: value.result,
+			relayer: value.relayer.ok_or(())?.account,
+			this: value.self_.ok_or(())?.account,
+			balances: value
+				.balances
+				.into_iter()
+				.map(|QueryBalance { denom, amount }| {
+					Ok::<(String, Displayed<u128>), ()>((
+						denom,
+						Displayed(amount.ok_or(())?.into()),
+					))
+				})
+				.collect::<core::result::Result<Vec<_>, _>>()?,
+		})
+	}
+}
+
 impl From<Vec<u8>> for Salt {
 	fn from(value: Vec<u8>) -> Self {
 		Salt { salt: value }
@@ -116,6 +208,7 @@ where
 			salt: Some(value.salt.into()),
 			program: Some(value.program.into()),
 			assets: value.assets.0.into_iter().map(PacketAsset::from).collect::<Vec<_>>(),
+			height: value.height,
 		}
 	}
 }
@@ -188,6 +281,7 @@ where
 					.map(|asset| <(xcvm_core::AssetId, Displayed<u128>)>::try_from(asset))
 					.collect::<core::result::Result<Vec<_>, _>>()?,
 			),
+			height: packet.height,
 		})
 	}
 }
@@ -259,13 +353,105 @@ where
 			instruction::Instruction::Transfer(t) => t.try_into(),
 			instruction::Instruction::Spawn(s) => s.try_into(),
 			instruction::Instruction::Call(c) => c.try_into(),
-			// TODO(aeryz): Query needs to be implemented
-			// instruction::Instruction::Query(q) => q.try_into(),
-			_ => Err(()),
+			instruction::Instruction::Query(q) => q.try_into(),
+			instruction::Instruction::Assert(a) => a.try_into(),
+			instruction::Instruction::If(i) => i.try_into(),
+			instruction::Instruction::Try(t) => t.try_into(),
 		}
 	}
 }
 
+impl<TNetwork, TAbiEncoded, TAccount, TAssets> TryFrom<Assert>
+	for xcvm_core::Instruction<TNetwork, TAbiEncoded, TAccount, TAssets>
+where
+	TNetwork: From<u32>,
+	TAbiEncoded: TryFrom<Vec<u8>>,
+	TAccount: for<'a> TryFrom<&'a [u8]>,
+	TAssets: From<Vec<(xcvm_core::AssetId, xcvm_core::Amount)>>,
+{
+	type Error = ();
+
+	fn try_from(assert: Assert) -> core::result::Result<Self, Self::Error> {
+		Ok(xcvm_core::Instruction::Assert { condition: assert.condition.ok_or(())?.try_into()? })
+	}
+}
+
+impl<TNetwork, TAbiEncoded, TAccount, TAssets> TryFrom<If>
+	for xcvm_core::Instruction<TNetwork, TAbiEncoded, TAccount, TAssets>
+where
+	TNetwork: From<u32>,
+	TAbiEncoded: TryFrom<Vec<u8>>,
+	TAccount: for<'a> TryFrom<&'a [u8]>,
+	TAssets: From<Vec<(xcvm_core::AssetId, xcvm_core::Amount)>>,
+{
+	type Error = ();
+
+	fn try_from(r#if: If) -> core::result::Result<Self, Self::Error> {
+		Ok(xcvm_core::Instruction::If {
+			condition: r#if.condition.ok_or(())?.try_into()?,
+			then: r#if.then.ok_or(())?.try_into()?,
+			otherwise: r#if.otherwise.ok_or(())?.try_into()?,
+		})
+	}
+}
+
+impl<TNetwork, TAbiEncoded, TAccount, TAssets> TryFrom<Try>
+	for xcvm_core::Instruction<TNetwork, TAbiEncoded, TAccount, TAssets>
+where
+	TNetwork: From<u32>,
+	TAbiEncoded: TryFrom<Vec<u8>>,
+	TAccount: for<'a> TryFrom<&'a [u8]>,
+	TAssets: From<Vec<(xcvm_core::AssetId, xcvm_core::Amount)>>,
+{
+	type Error = ();
+
+	fn try_from(r#try: Try) -> core::result::Result<Self, Self::Error> {
+		Ok(xcvm_core::Instruction::Try {
+			body: r#try.body.ok_or(())?.try_into()?,
+			// An absent catch block returns the funds to the user origin.
+			catch: r#try.catch.map(TryInto::try_into).transpose()?,
+		})
+	}
+}
+
+impl TryFrom<Condition> for xcvm_core::Condition {
+	type Error = ();
+
+	fn try_from(condition: Condition) -> core::result::Result<Self, Self::Error> {
+		Ok(match condition.condition.ok_or(())? {
+			condition::Condition::ResultEquals(ResultEquals { result }) =>
+				xcvm_core::Condition::ResultEquals(result),
+			condition::Condition::BalanceAtLeast(BalanceAtLeast { asset_id, amount }) =>
+				xcvm_core::Condition::BalanceAtLeast {
+					asset: asset_id.ok_or(())?.try_into()?,
+					amount: Displayed(amount.ok_or(())?.into()),
+				},
+			condition::Condition::CallSucceeded(_) => xcvm_core::Condition::CallSucceeded,
+			condition::Condition::Not(not) => xcvm_core::Condition::Not(
+				xcvm_core::Condition::try_from(*not.condition.ok_or(())?)?.into(),
+			),
+		})
+	}
+}
+
+impl<TNetwork, TAbiEncoded, TAccount, TAssets> TryFrom<Query>
+	for xcvm_core::Instruction<TNetwork, TAbiEncoded, TAccount, TAssets>
+where
+	TNetwork: From<u32>,
+	TAbiEncoded: TryFrom<Vec<u8>>,
+	TAccount: for<'a> TryFrom<&'a [u8]>,
+	TAssets: From<Vec<(xcvm_core::AssetId, xcvm_core::Amount)>>,
+{
+	type Error = ();
+
+	fn try_from(query: Query) -> core::result::Result<Self, Self::Error> {
+		Ok(xcvm_core::Instruction::Query {
+			network: query.network.ok_or(())?.network_id.into(),
+			salt: query.salt.ok_or(())?.salt,
+		})
+	}
+}
+
 impl<TNetwork, TAbiEncoded, TAccount, TAssets> TryFrom<Call>
 	for xcvm_core::Instruction<TNetwork, TAbiEncoded, TAccount, TAssets>
 where
@@ -357,9 +543,19 @@ where
 				.map(|asset| asset.try_into())
 				.collect::<core::result::Result<Vec<_>, _>>()?
 				.into(),
-			program: XCVMProgram {
-				tag: Vec::new(),
-				instructions: spawn.program.ok_or(())?.instructions.ok_or(())?.try_into()?,
+			program: spawn.program.ok_or(())?.try_into()?,
+			deadline: spawn.deadline.map(|deadline| deadline.timestamp),
+			tip: if spawn.tip.is_empty() {
+				None
+			} else {
+				Some(
+					spawn
+						.tip
+						.into_iter()
+						.map(|asset| asset.try_into())
+						.collect::<core::result::Result<Vec<_>, _>>()?
+						.into(),
+				)
 			},
 		})
 	}
@@ -540,6 +736,26 @@ impl From<xcvm_core::NetworkId> for Network {
 	}
 }
 
+impl From<xcvm_core::Condition> for Condition {
+	fn from(condition: xcvm_core::Condition) -> Self {
+		let condition = match condition {
+			xcvm_core::Condition::ResultEquals(result) =>
+				condition::Condition::ResultEquals(ResultEquals { result }),
+			xcvm_core::Condition::BalanceAtLeast { asset, amount: Displayed(amount) } =>
+				condition::Condition::BalanceAtLeast(BalanceAtLeast {
+					asset_id: Some(asset.into()),
+					amount: Some(amount.into()),
+				}),
+			xcvm_core::Condition::CallSucceeded =>
+				condition::Condition::CallSucceeded(CallSucceeded {}),
+			xcvm_core::Condition::Not(condition) => condition::Condition::Not(Box::new(Not {
+				condition: Some(Box::new((*condition).into())),
+			})),
+		};
+		Condition { condition: Some(condition) }
+	}
+}
+
 impl<TAccount> From<xcvm_core::Destination<TAccount>> for transfer::AccountType
 where
 	TAccount: Into<Vec<u8>>,
@@ -581,19 +797,50 @@ where
 						bindings: bindings.into_iter().map(|binding| binding.into()).collect(),
 					}),
 				}),
-			xcvm_core::Instruction::Spawn { network, bridge_security, salt, assets, program } =>
-				instruction::Instruction::Spawn(Spawn {
-					network: Some(Network { network_id: network.into() }),
-					security: bridge_security as i32,
-					salt: Some(Salt { salt }),
-					program: Some(program.into()),
-					assets: assets.into().into_iter().map(|asset| asset.into()).collect(),
-				}),
+			xcvm_core::Instruction::Spawn {
+				network,
+				bridge_security,
+				salt,
+				assets,
+				program,
+				deadline,
+				tip,
+			} => instruction::Instruction::Spawn(Spawn {
+				network: Some(Network { network_id: network.into() }),
+				security: bridge_security as i32,
+				salt: Some(Salt { salt }),
+				program: Some(program.into()),
+				assets: assets.into().into_iter().map(|asset| asset.into()).collect(),
+				deadline: deadline.map(|timestamp| Deadline { timestamp }),
+				tip: tip
+					.map(|tip| tip.into().into_iter().map(|asset| asset.into()).collect())
+					.unwrap_or_default(),
+			}),
 			xcvm_core::Instruction::Query { network, salt } =>
 				instruction::Instruction::Query(Query {
 					network: Some(Network { network_id: network.into() }),
 					salt: Some(Salt { salt }),
 				}),
+			xcvm_core::Instruction::Assert { condition } =>
+				instruction::Instruction::Assert(Assert { condition: Some(condition.into()) }),
+			xcvm_core::Instruction::If { condition, then, otherwise } =>
+				instruction::Instruction::If(If {
+					condition: Some(condition.into()),
+					then: Some(Instructions {
+						instructions: then.into_iter().map(|instr| instr.into()).collect(),
+					}),
+					otherwise: Some(Instructions {
+						instructions: otherwise.into_iter().map(|instr| instr.into()).collect(),
+					}),
+				}),
+			xcvm_core::Instruction::Try { body, catch } => instruction::Instruction::Try(Try {
+				body: Some(Instructions {
+					instructions: body.into_iter().map(|instr| instr.into()).collect(),
+				}),
+				catch: catch.map(|catch| Instructions {
+					instructions: catch.into_iter().map(|instr| instr.into()).collect(),
+				}),
+			}),
 		}
 	}
 }
@@ -652,6 +899,70 @@ mod tests {
 		)
 	}
 
+	#[test]
+	fn query_packet_is_not_a_packet() {
+		let query = xcvm_core::QueryPacket {
+			interpreter: b"interpreter".to_vec(),
+			user_origin: xcvm_core::UserOrigin {
+				network_id: 1.into(),
+				user_id: b"user".to_vec().into(),
+			},
+			salt: b"salt".to_vec(),
+			query_id: 1,
+		};
+		let encoded = query.clone().encode();
+		assert_eq!(decode_query_packet(&encoded).unwrap(), query);
+		assert!(decode_packet::<NetworkId, Vec<u8>, Vec<u8>, Funds>(&encoded).is_err());
+
+		let packet = XCVMPacket::<NetworkId, Vec<u8>, Vec<u8>, Funds> {
+			interpreter: query.interpreter,
+			user_origin: query.user_origin,
+			salt: query.salt,
+			program: xcvm_core::Program { tag: Vec::new(), instructions: VecDeque::new() },
+			assets: Funds::empty(),
+			height: 1,
+		};
+		assert!(decode_query_packet(&packet.encode()).is_err());
+	}
+
+	#[test]
+	fn conditional_program_roundtrip() {
+		let program = XCVMProgram::<NetworkId, Vec<u8>, Vec<u8>, Funds> {
+			tag: b"conditional".to_vec(),
+			instructions: VecDeque::from([
+				xcvm_core::Instruction::Assert {
+					condition: xcvm_core::Condition::Not(
+						xcvm_core::Condition::ResultEquals(b"0".to_vec()).into(),
+					),
+				},
+				xcvm_core::Instruction::If {
+					condition: xcvm_core::Condition::BalanceAtLeast {
+						asset: xcvm_core::AssetId::from(1),
+						amount: Displayed(100),
+					},
+					then: VecDeque::from([xcvm_core::Instruction::Transfer {
+						to: Destination::Relayer,
+						assets: Funds::from([(xcvm_core::AssetId::from(1), 100_u128)]),
+					}]),
+					otherwise: VecDeque::from([xcvm_core::Instruction::Assert {
+						condition: xcvm_core::Condition::CallSucceeded,
+					}]),
+				},
+				xcvm_core::Instruction::Try {
+					body: VecDeque::from([xcvm_core::Instruction::Call {
+						bindings: Vec::new(),
+						encoded: alloc::vec![0xCA, 0xFE],
+					}]),
+					catch: None,
+				},
+				xcvm_core::Instruction::Try { body: VecDeque::new(), catch: Some(VecDeque::new()) },
+			]),
+		};
+		let decoded: XCVMProgram<NetworkId, Vec<u8>, Vec<u8>, Funds> =
+			decode(&program.clone().encode()).unwrap();
+		assert_eq!(decoded, program);
+	}
+
 	#[test]
 	fn u128_from_uint128_works() {
 		let real_value = 1231231231231231233123123123123123_u128;
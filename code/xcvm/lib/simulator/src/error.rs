@@ -0,0 +1,44 @@
+use thiserror::Error;
+use xcvm_core::{AssetId, BridgeSecurity, NetworkId};
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum SimulationError {
+	#[error("Network {0} is not part of the simulation")]
+	UnknownNetwork(NetworkId),
+
+	#[error("No bridge channel from network {0} to network {1}")]
+	UnknownChannel(NetworkId, NetworkId),
+
+	#[error("Expected bridge security to be at least {0:?}, got {1:?}")]
+	InsufficientBridgeSecurity(BridgeSecurity, BridgeSecurity),
+
+	#[error("Asset {0:?} is not registered on network {1}")]
+	UnknownAsset(AssetId, NetworkId),
+
+	#[error("Insufficient balance of asset {0:?}")]
+	InsufficientBalance(AssetId),
+
+	#[error("No contract is deployed at the called address")]
+	UnknownContract,
+
+	#[error("No interpreter is instantiated for the queried origin")]
+	UnknownInterpreter,
+
+	#[error("Invalid call payload")]
+	InvalidCallPayload,
+
+	#[error("Bindings are invalid")]
+	InvalidBindings,
+
+	#[error("The result register is empty")]
+	EmptyResultRegister,
+
+	#[error("Data cannot be serialized")]
+	DataSerializationError,
+
+	#[error("Call failed: {0}")]
+	CallFailed(String),
+
+	#[error("Assertion failed")]
+	AssertionFailed,
+}
@@ -0,0 +1,64 @@
+use crate::{error::SimulationError, Account};
+use std::collections::BTreeMap;
+use xcvm_core::AssetId;
+
+/// In-memory state of a simulated network: balances per account and asset, and the raw storage
+/// of the deployed contracts.
+#[derive(Clone, Default, Debug, PartialEq, Eq)]
+pub struct Ledger {
+	balances: BTreeMap<(Account, AssetId), u128>,
+	storage: BTreeMap<Account, BTreeMap<Vec<u8>, Vec<u8>>>,
+}
+
+impl Ledger {
+	/// Balance of `account` in `asset`, zero if the account never held the asset.
+	pub fn balance(&self, account: &[u8], asset: AssetId) -> u128 {
+		self.balances.get(&(account.to_vec(), asset)).copied().unwrap_or_default()
+	}
+
+	/// All the non-zero balances of `account`.
+	pub fn balances(&self, account: &[u8]) -> Vec<(AssetId, u128)> {
+		self.balances
+			.iter()
+			.filter(|((owner, _), amount)| owner == account && **amount > 0)
+			.map(|((_, asset), amount)| (*asset, *amount))
+			.collect()
+	}
+
+	/// Create `amount` of `asset` out of thin air and give it to `account`.
+	pub fn mint(&mut self, account: &[u8], asset: AssetId, amount: u128) {
+		let balance = self.balances.entry((account.to_vec(), asset)).or_default();
+		*balance = balance.saturating_add(amount);
+	}
+
+	/// Destroy `amount` of `asset` owned by `account`.
+	pub fn burn(
+		&mut self,
+		account: &[u8],
+		asset: AssetId,
+		amount: u128,
+	) -> Result<(), SimulationError> {
+		let balance = self.balances.entry((account.to_vec(), asset)).or_default();
+		*balance =
+			balance.checked_sub(amount).ok_or(SimulationError::InsufficientBalance(asset))?;
+		Ok(())
+	}
+
+	/// Move `amount` of `asset` from `from` to `to`.
+	pub fn transfer(
+		&mut self,
+		asset: AssetId,
+		from: &[u8],
+		to: &[u8],
+		amount: u128,
+	) -> Result<(), SimulationError> {
+		self.burn(from, asset, amount)?;
+		self.mint(to, asset, amount);
+		Ok(())
+	}
+
+	/// Raw key-value storage of `contract`.
+	pub fn storage(&mut self, contract: &[u8]) -> &mut BTreeMap<Vec<u8>, Vec<u8>> {
+		self.storage.entry(contract.to_vec()).or_default()
+	}
+}
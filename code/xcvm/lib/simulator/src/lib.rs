@@ -0,0 +1,937 @@
+//! Off-chain simulator of XCVM programs.
+//!
+//! Execute a program against an in-memory model of several networks, without deploying the
+//! gateway, router and interpreter contracts. Each network holds a [`Ledger`] of balances, the
+//! contracts that can be called and the references of the assets used by the late-bindings.
+//! Networks are connected by bridge channels of a given [`BridgeSecurity`].
+//!
+//! The semantics follow the CosmWasm interpreter:
+//! - a failing instruction reverts every effect of its program, including the spawned programs,
+//!   unless it is part of the body of a `Try`, only the body being reverted,
+//! - a failed `Try` body without a catch block returns every balance of the interpreter to its user
+//!   origin,
+//! - the instructions of the body and catch block of a `Try` are numbered from the position
+//!   following the `Try`, which is restored once they are executed,
+//! - spawned programs are executed once the spawning program is done, in spawn order,
+//! - the assets of a failing spawned program are returned to the spawning interpreter.
+//!
+//! It departs from the contracts where they wait for an IBC acknowledgement:
+//! - a `Query` is answered synchronously, its result being available to the next instructions,
+//!   while the interpreter only receives it once the query packet is acknowledged, after its
+//!   program is done,
+//! - the tip of a `Spawn` is paid to the relayer along with the spawn, while the gateway escrows it
+//!   until the spawned program is acknowledged, refunding it if the program fails.
+
+mod error;
+mod ledger;
+mod report;
+
+pub use crate::{error::*, ledger::*, report::*};
+use std::{
+	borrow::Cow,
+	collections::{BTreeMap, VecDeque},
+};
+use xcvm_core::{
+	apply_bindings,
+	cosmwasm::{FlatCosmosMsg, FlatWasmMsg},
+	Amount, AssetId, BindingValue, BridgeSecurity, Condition, Destination, Displayed, Funds,
+	Instruction, InterpreterOrigin, LateBindingError, NetworkId, Program, QueryResult, Register,
+	UserOrigin, MAX_PARTS,
+};
+
+/// Account on a simulated network, the raw bytes of the address.
+pub type Account = Vec<u8>;
+pub type SimulatedInstruction = Instruction<NetworkId, Vec<u8>, Account, Funds>;
+pub type SimulatedProgram = Program<VecDeque<SimulatedInstruction>>;
+
+/// Decode the bound payload of a `Call` into the called contract and the message it receives.
+pub type CallDecoder = fn(&[u8]) -> Result<(Account, Vec<u8>), SimulationError>;
+
+/// Contract deployed on a simulated network.
+/// Receives the message decoded by the network's [`CallDecoder`] and returns the data stored in
+/// the result register.
+pub type Contract = Box<dyn Fn(&mut CallContext, &[u8]) -> Result<Vec<u8>, String>>;
+
+/// Decode a JSON-encoded `FlatCosmosMsg`, as executed by the CosmWasm interpreter.
+/// Only `Wasm::Execute` messages are supported.
+pub fn cosmwasm_call_decoder(payload: &[u8]) -> Result<(Account, Vec<u8>), SimulationError> {
+	match serde_json::from_slice::<FlatCosmosMsg<serde_json::Value>>(payload)
+		.map_err(|_| SimulationError::InvalidCallPayload)?
+	{
+		FlatCosmosMsg::Wasm(FlatWasmMsg::Execute { contract_addr, msg, .. }) => Ok((
+			contract_addr.into_bytes(),
+			serde_json::to_vec(&msg).map_err(|_| SimulationError::DataSerializationError)?,
+		)),
+		_ => Err(SimulationError::InvalidCallPayload),
+	}
+}
+
+/// Execution context given to a [`Contract`].
+pub struct CallContext<'a> {
+	pub network: NetworkId,
+	/// The calling interpreter.
+	pub sender: &'a [u8],
+	/// The called contract.
+	pub contract: &'a [u8],
+	ledger: &'a mut Ledger,
+	effects: &'a mut Vec<Effect>,
+}
+
+impl<'a> CallContext<'a> {
+	pub fn balance(&self, account: &[u8], asset: AssetId) -> u128 {
+		self.ledger.balance(account, asset)
+	}
+
+	/// Move funds on behalf of the contract, the transfer is part of the report.
+	pub fn transfer(
+		&mut self,
+		asset: AssetId,
+		from: &[u8],
+		to: &[u8],
+		amount: u128,
+	) -> Result<(), String> {
+		self.ledger.transfer(asset, from, to, amount).map_err(|e| e.to_string())?;
+		self.effects
+			.push(Effect::Transfer { asset, from: from.to_vec(), to: to.to_vec(), amount });
+		Ok(())
+	}
+
+	/// Raw storage of the called contract.
+	pub fn storage(&mut self) -> &mut BTreeMap<Vec<u8>, Vec<u8>> {
+		self.ledger.storage(self.contract)
+	}
+}
+
+/// Registers of an interpreter, persisted across programs.
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Interpreter {
+	account: Account,
+	ip: u32,
+	result: Option<Result<Vec<u8>, String>>,
+}
+
+/// In-memory model of a network.
+pub struct SimulatedNetwork {
+	pub ledger: Ledger,
+	/// Account used for the `Relayer` register and destination.
+	pub relayer: Account,
+	contracts: BTreeMap<Account, Contract>,
+	assets: BTreeMap<AssetId, Vec<u8>>,
+	decode_call: CallDecoder,
+	interpreters: BTreeMap<InterpreterOrigin, Interpreter>,
+}
+
+impl SimulatedNetwork {
+	#[inline]
+	pub fn new(relayer: Account, decode_call: CallDecoder) -> Self {
+		SimulatedNetwork {
+			ledger: Ledger::default(),
+			relayer,
+			contracts: BTreeMap::new(),
+			assets: BTreeMap::new(),
+			decode_call,
+			interpreters: BTreeMap::new(),
+		}
+	}
+
+	/// Register the network specific reference of an asset, used by the `Asset` late-binding.
+	#[inline]
+	pub fn with_asset(mut self, asset: AssetId, reference: Vec<u8>) -> Self {
+		self.assets.insert(asset, reference);
+		self
+	}
+
+	#[inline]
+	pub fn with_contract(mut self, address: Account, contract: Contract) -> Self {
+		self.contracts.insert(address, contract);
+		self
+	}
+
+	#[inline]
+	pub fn with_balance(mut self, account: &[u8], asset: AssetId, amount: u128) -> Self {
+		self.ledger.mint(account, asset, amount);
+		self
+	}
+
+	/// Address of the interpreter of `origin`, if it has been instantiated.
+	pub fn interpreter(&self, origin: &InterpreterOrigin) -> Option<&[u8]> {
+		self.interpreters.get(origin).map(|interpreter| interpreter.account.as_slice())
+	}
+}
+
+/// Program waiting to be executed by an interpreter.
+struct Execution {
+	network: NetworkId,
+	origin: InterpreterOrigin,
+	program: SimulatedProgram,
+	/// Account providing the assets, or `None` for bridged assets that are minted.
+	depositor: Option<Account>,
+	assets: Vec<(AssetId, u128)>,
+	/// Where to return bridged assets if the program fails.
+	refund: Option<(NetworkId, Account)>,
+}
+
+/// Set of networks connected by bridges.
+#[derive(Default)]
+pub struct World {
+	networks: BTreeMap<NetworkId, SimulatedNetwork>,
+	channels: BTreeMap<(NetworkId, NetworkId), BridgeSecurity>,
+}
+
+impl World {
+	#[inline]
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	#[inline]
+	pub fn with_network(mut self, network_id: NetworkId, network: SimulatedNetwork) -> Self {
+		self.networks.insert(network_id, network);
+		self
+	}
+
+	/// Open a bidirectional bridge channel between two networks.
+	#[inline]
+	pub fn with_channel(mut self, a: NetworkId, b: NetworkId, security: BridgeSecurity) -> Self {
+		self.channels.insert((a, b), security);
+		self.channels.insert((b, a), security);
+		self
+	}
+
+	pub fn network(&self, network_id: NetworkId) -> Option<&SimulatedNetwork> {
+		self.networks.get(&network_id)
+	}
+
+	pub fn network_mut(&mut self, network_id: NetworkId) -> Option<&mut SimulatedNetwork> {
+		self.networks.get_mut(&network_id)
+	}
+
+	/// Deterministic address of the interpreter of `origin` on `network_id`.
+	pub fn interpreter_account(network_id: NetworkId, origin: &InterpreterOrigin) -> Account {
+		format!(
+			"interpreter-{}-{}-{}-{}",
+			network_id,
+			origin.user_origin.network_id,
+			hex::encode(&origin.user_origin.user_id),
+			hex::encode(&origin.salt)
+		)
+		.into_bytes()
+	}
+
+	/// Execute `program` on `network_id` on behalf of `user`, as if the user called the router
+	/// with `assets`. The assets are taken from the `user_id` account of the network.
+	pub fn simulate(
+		&mut self,
+		network_id: NetworkId,
+		user: UserOrigin,
+		salt: Vec<u8>,
+		assets: Vec<(AssetId, u128)>,
+		program: SimulatedProgram,
+	) -> Report {
+		let mut report = Report::default();
+		let mut queue = VecDeque::from([Execution {
+			network: network_id,
+			depositor: Some(user.user_id.0.clone()),
+			origin: InterpreterOrigin { user_origin: user, salt },
+			program,
+			assets,
+			refund: None,
+		}]);
+		while let Some(execution) = queue.pop_front() {
+			let (program_report, spawned) = self.execute(execution);
+			report.programs.push(program_report);
+			queue.extend(spawned);
+		}
+		report
+	}
+
+	fn execute(&mut self, execution: Execution) -> (ProgramReport, Vec<Execution>) {
+		let Execution { network, origin, program, depositor, assets, refund } = execution;
+		let interpreter = Self::interpreter_account(network, &origin);
+		let mut report = ProgramReport {
+			network,
+			origin: origin.clone(),
+			interpreter: interpreter.clone(),
+			tag: program.tag.clone(),
+			deposits: Vec::new(),
+			steps: Vec::new(),
+			outcome: Ok(()),
+		};
+		let state = match self.networks.get_mut(&network) {
+			Some(state) => state,
+			None => {
+				report.outcome = Err(SimulationError::UnknownNetwork(network));
+				return (report, Vec::new())
+			},
+		};
+		let snapshot = (state.ledger.clone(), state.interpreters.clone());
+		state.interpreters.entry(origin.clone()).or_insert_with(|| Interpreter {
+			account: interpreter.clone(),
+			ip: 0,
+			result: None,
+		});
+
+		let mut spawned = Vec::new();
+		let outcome = self
+			.deposit(network, &interpreter, depositor, &assets, &mut report.deposits)
+			.and_then(|_| {
+				self.run(network, &origin, program.instructions, &mut report.steps, &mut spawned)
+			});
+
+		if let Err(e) = outcome {
+			let state = self.networks.get_mut(&network).expect("network exists; qed;");
+			(state.ledger, state.interpreters) = snapshot;
+			if let Some((network, account)) = refund {
+				if let Some(state) = self.networks.get_mut(&network) {
+					for (asset, amount) in &assets {
+						state.ledger.mint(&account, *asset, *amount);
+					}
+				}
+			}
+			report.outcome = Err(e);
+			spawned.clear();
+		}
+
+		(report, spawned)
+	}
+
+	fn deposit(
+		&mut self,
+		network: NetworkId,
+		interpreter: &[u8],
+		depositor: Option<Account>,
+		assets: &[(AssetId, u128)],
+		effects: &mut Vec<Effect>,
+	) -> Result<(), SimulationError> {
+		let ledger = &mut self.state(network)?.ledger;
+		for (asset, amount) in assets {
+			match &depositor {
+				Some(depositor) => {
+					ledger.transfer(*asset, depositor, interpreter, *amount)?;
+					effects.push(Effect::Transfer {
+						asset: *asset,
+						from: depositor.clone(),
+						to: interpreter.to_vec(),
+						amount: *amount,
+					});
+				},
+				None => ledger.mint(interpreter, *asset, *amount),
+			}
+		}
+		Ok(())
+	}
+
+	fn run(
+		&mut self,
+		network: NetworkId,
+		origin: &InterpreterOrigin,
+		mut instructions: VecDeque<SimulatedInstruction>,
+		steps: &mut Vec<Step>,
+		spawned: &mut Vec<Execution>,
+	) -> Result<(), SimulationError> {
+		while let Some(instruction) = instructions.pop_front() {
+			let ip = self.interpreter(network, origin)?.ip;
+			steps.push(Step { ip, instruction: instruction.clone(), effects: Vec::new() });
+			let effects = &mut steps.last_mut().expect("pushed; qed;").effects;
+			match instruction {
+				Instruction::Transfer { to, assets } =>
+					self.transfer(network, origin, to, assets, effects)?,
+				Instruction::Call { bindings, encoded } =>
+					self.call(network, origin, bindings, encoded, effects)?,
+				Instruction::Spawn {
+					network: destination,
+					bridge_security,
+					salt,
+					assets,
+					program,
+					deadline: _,
+					tip,
+				} => {
+					if let Some(tip) = tip {
+						self.transfer(network, origin, Destination::Relayer, tip, effects)?;
+					}
+					spawned.push(self.spawn(
+						network,
+						origin,
+						destination,
+						bridge_security,
+						salt,
+						assets,
+						program,
+						effects,
+					)?)
+				},
+				Instruction::Query { network: destination, salt } =>
+					self.query(network, origin, destination, salt, effects)?,
+				Instruction::Assert { condition } => {
+					let holds = self.evaluate(network, origin, &condition)?;
+					effects.push(Effect::Condition { holds });
+					if !holds {
+						return Err(SimulationError::AssertionFailed)
+					}
+				},
+				Instruction::If { condition, then, otherwise } => {
+					let holds = self.evaluate(network, origin, &condition)?;
+					effects.push(Effect::Condition { holds });
+					let branch = if holds { then } else { otherwise };
+					for instruction in branch.into_iter().rev() {
+						instructions.push_front(instruction);
+					}
+				},
+				Instruction::Try { body, catch } => {
+					let step = steps.len() - 1;
+					self.interpreter_mut(network, origin)?.ip = ip + 1;
+					let state = self.state(network)?;
+					let snapshot = (state.ledger.clone(), state.interpreters.clone());
+					let spawned_before = spawned.len();
+					if let Err(e) = self.run(network, origin, body, steps, spawned) {
+						let state = self.state(network)?;
+						(state.ledger, state.interpreters) = snapshot;
+						spawned.truncate(spawned_before);
+						self.interpreter_mut(network, origin)?.result = Some(Err(e.to_string()));
+						let effects = &mut steps[step].effects;
+						effects.push(Effect::Reverted { error: e });
+						match catch {
+							Some(catch) => self.run(network, origin, catch, steps, spawned)?,
+							None => spawned.extend(self.return_funds(network, origin, effects)?),
+						}
+					}
+					self.interpreter_mut(network, origin)?.ip = ip;
+				},
+			}
+			self.interpreter_mut(network, origin)?.ip += 1;
+		}
+		Ok(())
+	}
+
+	fn transfer(
+		&mut self,
+		network: NetworkId,
+		origin: &InterpreterOrigin,
+		to: Destination<Account>,
+		assets: Funds,
+		effects: &mut Vec<Effect>,
+	) -> Result<(), SimulationError> {
+		let state = self.state(network)?;
+		let from = state.interpreter(origin).ok_or(SimulationError::UnknownInterpreter)?.to_vec();
+		let to = match to {
+			Destination::Account(account) => account,
+			Destination::Relayer => state.relayer.clone(),
+		};
+		for (asset, amount) in assets {
+			let amount = amount.apply(state.ledger.balance(&from, asset));
+			if amount == 0 {
+				continue
+			}
+			state.ledger.transfer(asset, &from, &to, amount)?;
+			effects.push(Effect::Transfer { asset, from: from.clone(), to: to.clone(), amount });
+		}
+		Ok(())
+	}
+
+	fn call(
+		&mut self,
+		network: NetworkId,
+		origin: &InterpreterOrigin,
+		bindings: Vec<(u32, BindingValue)>,
+		payload: Vec<u8>,
+		effects: &mut Vec<Effect>,
+	) -> Result<(), SimulationError> {
+		let payload = if bindings.is_empty() {
+			payload
+		} else {
+			let values = bindings
+				.iter()
+				.map(|(index, binding)| {
+					let value = self.binding_value(network, origin, *binding)?;
+					effects.push(Effect::Binding {
+						index: *index,
+						binding: *binding,
+						value: value.clone(),
+					});
+					Ok((*binding, value))
+				})
+				.collect::<Result<Vec<_>, SimulationError>>()?;
+			// Len here is the maximum possible length
+			let mut formatted_call =
+				vec![0; values.iter().map(|(_, value)| value.len()).sum::<usize>() + payload.len()];
+			apply_bindings(payload, bindings, &mut formatted_call, |binding| {
+				values
+					.iter()
+					.find(|(value_binding, _)| *value_binding == binding)
+					.map(|(_, value)| Cow::Borrowed(value.as_slice()))
+					.ok_or(SimulationError::InvalidBindings)
+			})
+			.map_err(|e| match e {
+				LateBindingError::InvalidBinding => SimulationError::InvalidBindings,
+				LateBindingError::App(e) => e,
+			})?;
+			formatted_call
+		};
+
+		let state = self.state(network)?;
+		let sender = state.interpreter(origin).ok_or(SimulationError::UnknownInterpreter)?.to_vec();
+		let (contract, msg) = (state.decode_call)(&payload)?;
+		let SimulatedNetwork { ledger, contracts, .. } = state;
+		let execute = contracts.get(&contract).ok_or(SimulationError::UnknownContract)?;
+		let mut call_effects = Vec::new();
+		let result = execute(
+			&mut CallContext {
+				network,
+				sender: &sender,
+				contract: &contract,
+				ledger,
+				effects: &mut call_effects,
+			},
+			&msg,
+		)
+		.map_err(SimulationError::CallFailed)?;
+		effects.extend(call_effects);
+		effects.push(Effect::Call { contract, payload, result: result.clone() });
+		self.interpreter_mut(network, origin)?.result = Some(Ok(result));
+		Ok(())
+	}
+
+	#[allow(clippy::too_many_arguments)]
+	fn spawn(
+		&mut self,
+		network: NetworkId,
+		origin: &InterpreterOrigin,
+		destination: NetworkId,
+		bridge_security: BridgeSecurity,
+		salt: Vec<u8>,
+		assets: Funds,
+		program: SimulatedProgram,
+		effects: &mut Vec<Effect>,
+	) -> Result<Execution, SimulationError> {
+		self.ensure_channel(network, destination, bridge_security)?;
+		let state = self.state(network)?;
+		let interpreter =
+			state.interpreter(origin).ok_or(SimulationError::UnknownInterpreter)?.to_vec();
+		let mut bridged = Vec::new();
+		for (asset, amount) in assets {
+			let amount = amount.apply(state.ledger.balance(&interpreter, asset));
+			if amount == 0 {
+				continue
+			}
+			state.ledger.burn(&interpreter, asset, amount)?;
+			bridged.push((asset, amount));
+		}
+		effects.push(Effect::Spawn {
+			network: destination,
+			bridge_security,
+			salt: salt.clone(),
+			assets: bridged.clone(),
+			program: program.clone(),
+		});
+		Ok(Execution {
+			network: destination,
+			origin: InterpreterOrigin { user_origin: origin.user_origin.clone(), salt },
+			program,
+			depositor: None,
+			assets: bridged,
+			refund: Some((network, interpreter)),
+		})
+	}
+
+	/// Return the balances of the interpreter to its user origin, bridging them back if the user
+	/// is on another network.
+	fn return_funds(
+		&mut self,
+		network: NetworkId,
+		origin: &InterpreterOrigin,
+		effects: &mut Vec<Effect>,
+	) -> Result<Option<Execution>, SimulationError> {
+		let state = self.state(network)?;
+		let interpreter =
+			state.interpreter(origin).ok_or(SimulationError::UnknownInterpreter)?.to_vec();
+		let assets = Funds(
+			state
+				.ledger
+				.balances(&interpreter)
+				.into_iter()
+				.map(|(asset, _)| (asset, Amount::ratio(MAX_PARTS)))
+				.collect(),
+		);
+		if assets.0.is_empty() {
+			return Ok(None)
+		}
+		let UserOrigin { network_id, user_id } = origin.user_origin.clone();
+		if network_id == network {
+			self.transfer(network, origin, Destination::Account(user_id.0), assets, effects)?;
+			return Ok(None)
+		}
+		let program = Program {
+			tag: Vec::new(),
+			instructions: VecDeque::from([Instruction::Transfer {
+				to: Destination::Account(user_id.0),
+				assets: assets.clone(),
+			}]),
+		};
+		self.spawn(
+			network,
+			origin,
+			network_id,
+			BridgeSecurity::Deterministic,
+			origin.salt.clone(),
+			assets,
+			program,
+			effects,
+		)
+		.map(Some)
+	}
+
+	fn query(
+		&mut self,
+		network: NetworkId,
+		origin: &InterpreterOrigin,
+		destination: NetworkId,
+		salt: Vec<u8>,
+		effects: &mut Vec<Effect>,
+	) -> Result<(), SimulationError> {
+		self.ensure_channel(network, destination, BridgeSecurity::Insecure)?;
+		let remote = self.state(destination)?;
+		let remote_origin = InterpreterOrigin { user_origin: origin.user_origin.clone(), salt };
+		let interpreter = remote
+			.interpreters
+			.get(&remote_origin)
+			.ok_or(SimulationError::UnknownInterpreter)?;
+		let query_result = QueryResult {
+			ip: interpreter.ip,
+			result: match &interpreter.result {
+				Some(result) => serde_json::to_vec(result)
+					.map_err(|_| SimulationError::DataSerializationError)?,
+				None => Vec::new(),
+			},
+			relayer: remote.relayer.clone(),
+			this: interpreter.account.clone(),
+			balances: remote
+				.ledger
+				.balances(&interpreter.account)
+				.into_iter()
+				.map(|(asset, amount)| (asset.0 .0.to_string(), Displayed(amount)))
+				.collect(),
+		};
+		let result = serde_json::to_vec(&query_result)
+			.map_err(|_| SimulationError::DataSerializationError)?;
+		effects.push(Effect::Query {
+			network: destination,
+			salt: remote_origin.salt,
+			result: result.clone(),
+		});
+		self.interpreter_mut(network, origin)?.result = Some(Ok(result));
+		Ok(())
+	}
+
+	fn evaluate(
+		&self,
+		network: NetworkId,
+		origin: &InterpreterOrigin,
+		condition: &Condition,
+	) -> Result<bool, SimulationError> {
+		let state = self.networks.get(&network).ok_or(SimulationError::UnknownNetwork(network))?;
+		let interpreter =
+			state.interpreters.get(origin).ok_or(SimulationError::UnknownInterpreter)?;
+		match condition {
+			Condition::ResultEquals(expected) => match &interpreter.result {
+				Some(result) => Ok(&serde_json::to_vec(result)
+					.map_err(|_| SimulationError::DataSerializationError)? ==
+					expected),
+				None => Ok(false),
+			},
+			Condition::BalanceAtLeast { asset, amount } =>
+				Ok(state.ledger.balance(&interpreter.account, *asset) >= amount.0),
+			Condition::CallSucceeded => Ok(matches!(interpreter.result, Some(Ok(_)))),
+			Condition::Not(condition) => Ok(!self.evaluate(network, origin, condition)?),
+		}
+	}
+
+	fn binding_value(
+		&self,
+		network: NetworkId,
+		origin: &InterpreterOrigin,
+		binding: BindingValue,
+	) -> Result<Vec<u8>, SimulationError> {
+		let state = self.networks.get(&network).ok_or(SimulationError::UnknownNetwork(network))?;
+		let interpreter =
+			state.interpreters.get(origin).ok_or(SimulationError::UnknownInterpreter)?;
+		Ok(match binding {
+			BindingValue::Register(Register::Ip) => format!("{}", interpreter.ip).into_bytes(),
+			BindingValue::Register(Register::Relayer) => state.relayer.clone(),
+			BindingValue::Register(Register::This) => interpreter.account.clone(),
+			BindingValue::Register(Register::Result) => serde_json::to_vec(
+				interpreter.result.as_ref().ok_or(SimulationError::EmptyResultRegister)?,
+			)
+			.map_err(|_| SimulationError::DataSerializationError)?,
+			BindingValue::Asset(asset) => state
+				.assets
+				.get(&asset)
+				.cloned()
+				.ok_or(SimulationError::UnknownAsset(asset, network))?,
+		})
+	}
+
+	fn ensure_channel(
+		&self,
+		from: NetworkId,
+		to: NetworkId,
+		bridge_security: BridgeSecurity,
+	) -> Result<(), SimulationError> {
+		let security = self
+			.channels
+			.get(&(from, to))
+			.ok_or(SimulationError::UnknownChannel(from, to))?;
+		if *security < bridge_security {
+			return Err(SimulationError::InsufficientBridgeSecurity(bridge_security, *security))
+		}
+		Ok(())
+	}
+
+	fn state(&mut self, network: NetworkId) -> Result<&mut SimulatedNetwork, SimulationError> {
+		self.networks.get_mut(&network).ok_or(SimulationError::UnknownNetwork(network))
+	}
+
+	fn interpreter(
+		&self,
+		network: NetworkId,
+		origin: &InterpreterOrigin,
+	) -> Result<&Interpreter, SimulationError> {
+		self.networks
+			.get(&network)
+			.ok_or(SimulationError::UnknownNetwork(network))?
+			.interpreters
+			.get(origin)
+			.ok_or(SimulationError::UnknownInterpreter)
+	}
+
+	fn interpreter_mut(
+		&mut self,
+		network: NetworkId,
+		origin: &InterpreterOrigin,
+	) -> Result<&mut Interpreter, SimulationError> {
+		self.state(network)?
+			.interpreters
+			.get_mut(origin)
+			.ok_or(SimulationError::UnknownInterpreter)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use xcvm_core::{Amount, Asset, Juno, Network, Picasso, ETH, PICA, USDT};
+
+	const ALICE: &[u8] = b"alice";
+	const BOB: &[u8] = b"bob";
+	const SWAP: &[u8] = b"swap";
+
+	fn alice() -> UserOrigin {
+		UserOrigin { network_id: Picasso::ID, user_id: ALICE.to_vec().into() }
+	}
+
+	/// Swap contract giving 1 USDT for 2 PICA of the caller.
+	fn swap() -> Contract {
+		Box::new(|ctx, msg| {
+			let msg: serde_json::Value = serde_json::from_slice(msg).map_err(|e| e.to_string())?;
+			let recipient = msg["recipient"].as_str().ok_or("missing recipient")?.as_bytes();
+			let amount = ctx.balance(ctx.sender, PICA::ID);
+			let sender = ctx.sender.to_vec();
+			let contract = ctx.contract.to_vec();
+			ctx.transfer(PICA::ID, &sender, &contract, amount)?;
+			ctx.transfer(USDT::ID, &contract, recipient, amount / 2)?;
+			Ok(format!("{}", amount / 2).into_bytes())
+		})
+	}
+
+	fn world() -> World {
+		World::new()
+			.with_network(
+				Picasso::ID,
+				SimulatedNetwork::new(b"relayer".to_vec(), cosmwasm_call_decoder)
+					.with_asset(PICA::ID, b"ppica".to_vec())
+					.with_contract(SWAP.to_vec(), swap())
+					.with_balance(ALICE, PICA::ID, 1000)
+					.with_balance(SWAP, USDT::ID, 1000),
+			)
+			.with_network(
+				Juno::ID,
+				SimulatedNetwork::new(b"relayer".to_vec(), cosmwasm_call_decoder),
+			)
+			.with_channel(Picasso::ID, Juno::ID, BridgeSecurity::Deterministic)
+	}
+
+	fn swap_call() -> SimulatedInstruction {
+		let encoded =
+			br#"{"wasm":{"execute":{"contract_addr":"swap","msg":{"recipient":""},"funds":[]}}}"#;
+		let index = encoded.windows(13).position(|w| w == br#""recipient":""#).unwrap() + 12;
+		Instruction::Call {
+			bindings: vec![(index as u32, BindingValue::Register(Register::This))],
+			encoded: encoded.to_vec(),
+		}
+	}
+
+	fn program(instructions: Vec<SimulatedInstruction>) -> SimulatedProgram {
+		Program { tag: b"simulation".to_vec(), instructions: instructions.into() }
+	}
+
+	#[test]
+	fn call_applies_bindings_and_moves_funds() {
+		let mut world = world();
+		let report = world.simulate(
+			Picasso::ID,
+			alice(),
+			Vec::new(),
+			vec![(PICA::ID, 100)],
+			program(vec![
+				swap_call(),
+				Instruction::Transfer {
+					to: Destination::Account(BOB.to_vec()),
+					assets: Funds::from([(USDT::ID, Amount::ratio(xcvm_core::MAX_PARTS))]),
+				},
+			]),
+		);
+		assert!(report.is_success());
+		let interpreter = World::interpreter_account(
+			Picasso::ID,
+			&InterpreterOrigin { user_origin: alice(), salt: Vec::new() },
+		);
+		let steps = &report.programs[0].steps;
+		assert_eq!(
+			steps[0].effects[0],
+			Effect::Binding {
+				index: 62,
+				binding: BindingValue::Register(Register::This),
+				value: interpreter.clone()
+			}
+		);
+		assert_eq!(report.transfers().count(), 4);
+		let ledger = &world.network(Picasso::ID).unwrap().ledger;
+		assert_eq!(ledger.balance(ALICE, PICA::ID), 900);
+		assert_eq!(ledger.balance(SWAP, PICA::ID), 100);
+		assert_eq!(ledger.balance(BOB, USDT::ID), 50);
+		assert_eq!(ledger.balance(&interpreter, USDT::ID), 0);
+	}
+
+	#[test]
+	fn failing_assertion_reverts_the_program() {
+		let mut world = world();
+		let report = world.simulate(
+			Picasso::ID,
+			alice(),
+			Vec::new(),
+			vec![(PICA::ID, 100)],
+			program(vec![
+				swap_call(),
+				Instruction::Assert {
+					condition: Condition::BalanceAtLeast { asset: USDT::ID, amount: Displayed(51) },
+				},
+				Instruction::Transfer {
+					to: Destination::Account(BOB.to_vec()),
+					assets: Funds::from([(USDT::ID, 50_u128)]),
+				},
+			]),
+		);
+		assert_eq!(
+			report.failures().collect::<Vec<_>>(),
+			[(Some(1), &SimulationError::AssertionFailed)]
+		);
+		let ledger = &world.network(Picasso::ID).unwrap().ledger;
+		assert_eq!(ledger.balance(ALICE, PICA::ID), 1000);
+		assert_eq!(ledger.balance(BOB, USDT::ID), 0);
+	}
+
+	#[test]
+	fn spawn_bridges_assets_and_executes_remotely() {
+		let mut world = world();
+		let spawn = |bridge_security| Instruction::Spawn {
+			network: Juno::ID,
+			bridge_security,
+			salt: b"juno".to_vec(),
+			assets: Funds::from([(PICA::ID, 60_u128)]),
+			program: program(vec![Instruction::Transfer {
+				to: Destination::Relayer,
+				assets: Funds::from([(PICA::ID, 10_u128)]),
+			}]),
+			deadline: None,
+			tip: None,
+		};
+		let report = world.simulate(
+			Picasso::ID,
+			alice(),
+			Vec::new(),
+			vec![(PICA::ID, 100)],
+			program(vec![spawn(BridgeSecurity::Deterministic)]),
+		);
+		assert!(report.is_success());
+		assert_eq!(report.programs.len(), 2);
+		let remote = InterpreterOrigin { user_origin: alice(), salt: b"juno".to_vec() };
+		let juno = world.network(Juno::ID).unwrap();
+		let interpreter = juno.interpreter(&remote).unwrap();
+		assert_eq!(juno.ledger.balance(interpreter, PICA::ID), 50);
+		assert_eq!(juno.ledger.balance(b"relayer", PICA::ID), 10);
+
+		let report = world.simulate(
+			Picasso::ID,
+			alice(),
+			Vec::new(),
+			Vec::new(),
+			program(vec![
+				spawn(BridgeSecurity::Deterministic),
+				Instruction::Spawn {
+					network: NetworkId(42),
+					bridge_security: BridgeSecurity::Insecure,
+					salt: Vec::new(),
+					assets: Funds::empty(),
+					program: program(Vec::new()),
+					deadline: None,
+					tip: None,
+				},
+			]),
+		);
+		assert_eq!(report.programs.len(), 1);
+		assert_eq!(
+			report.programs[0].outcome,
+			Err(SimulationError::UnknownChannel(Picasso::ID, NetworkId(42)))
+		);
+		assert_eq!(world.network(Picasso::ID).unwrap().ledger.balance(ALICE, ETH::ID), 0);
+	}
+
+	#[test]
+	fn failing_try_body_is_caught() {
+		let mut world = world();
+		let assert_usdt = |amount| Instruction::Assert {
+			condition: Condition::BalanceAtLeast { asset: USDT::ID, amount: Displayed(amount) },
+		};
+		let report = world.simulate(
+			Picasso::ID,
+			alice(),
+			Vec::new(),
+			vec![(PICA::ID, 100)],
+			program(vec![
+				Instruction::Try {
+					body: VecDeque::from([swap_call(), assert_usdt(51)]),
+					catch: Some(VecDeque::from([Instruction::Transfer {
+						to: Destination::Account(BOB.to_vec()),
+						assets: Funds::from([(PICA::ID, 10_u128)]),
+					}])),
+				},
+				Instruction::Assert { condition: Condition::Not(Condition::CallSucceeded.into()) },
+				// Without a catch block, the remaining funds are returned to the user.
+				Instruction::Try { body: VecDeque::from([assert_usdt(1)]), catch: None },
+			]),
+		);
+		assert!(report.is_success());
+		assert_eq!(
+			report.programs[0].steps[0].effects,
+			[Effect::Reverted { error: SimulationError::AssertionFailed }]
+		);
+		// The body and catch block are numbered from the instruction following their `Try`, the
+		// next instructions keeping their position.
+		let ips = report.programs[0].steps.iter().map(|step| step.ip).collect::<Vec<_>>();
+		assert_eq!(ips, [0, 1, 2, 1, 1, 2, 3]);
+		let ledger = &world.network(Picasso::ID).unwrap().ledger;
+		assert_eq!(ledger.balance(ALICE, PICA::ID), 990);
+		assert_eq!(ledger.balance(BOB, PICA::ID), 10);
+		assert_eq!(ledger.balance(SWAP, PICA::ID), 0);
+	}
+}
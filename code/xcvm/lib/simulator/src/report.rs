@@ -0,0 +1,88 @@
+use crate::{error::SimulationError, Account, SimulatedInstruction, SimulatedProgram};
+use xcvm_core::{AssetId, BindingValue, BridgeSecurity, InterpreterOrigin, NetworkId};
+
+/// Observable effect of an instruction.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Effect {
+	/// Funds moved between two accounts of the executing network.
+	Transfer { asset: AssetId, from: Account, to: Account, amount: u128 },
+	/// A late-binding has been resolved by `apply_bindings`.
+	Binding { index: u32, binding: BindingValue, value: Vec<u8> },
+	/// A contract has been called with the bound payload.
+	Call { contract: Account, payload: Vec<u8>, result: Vec<u8> },
+	/// A sub-program has been sent to another network, the assets are burnt on the executing
+	/// network and minted on the destination.
+	Spawn {
+		network: NetworkId,
+		bridge_security: BridgeSecurity,
+		salt: Vec<u8>,
+		assets: Vec<(AssetId, u128)>,
+		program: SimulatedProgram,
+	},
+	/// The registers and balances of a remote interpreter have been loaded in the result register.
+	Query { network: NetworkId, salt: Vec<u8>, result: Vec<u8> },
+	/// A condition has been evaluated by an `Assert` or an `If`.
+	Condition { holds: bool },
+	/// The body of a `Try` failed and its effects have been reverted.
+	Reverted { error: SimulationError },
+}
+
+/// Report of an executed instruction.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Step {
+	/// Value of the instruction pointer before the instruction.
+	pub ip: u32,
+	pub instruction: SimulatedInstruction,
+	pub effects: Vec<Effect>,
+}
+
+/// Report of a program executed by an interpreter.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ProgramReport {
+	pub network: NetworkId,
+	pub origin: InterpreterOrigin,
+	pub interpreter: Account,
+	pub tag: Vec<u8>,
+	/// Funds deposited in the interpreter before the execution.
+	pub deposits: Vec<Effect>,
+	/// Executed instructions, the last one is the failing instruction if the program failed.
+	pub steps: Vec<Step>,
+	/// In case of failure, every effect of the program has been reverted.
+	pub outcome: Result<(), SimulationError>,
+}
+
+/// Report of a simulation, the program and its sub-programs in execution order.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Report {
+	pub programs: Vec<ProgramReport>,
+}
+
+impl Report {
+	/// Whether every program succeeded.
+	pub fn is_success(&self) -> bool {
+		self.programs.iter().all(|program| program.outcome.is_ok())
+	}
+
+	/// Failing programs, with the failing instruction pointer if any.
+	pub fn failures(&self) -> impl Iterator<Item = (Option<u32>, &SimulationError)> {
+		self.programs.iter().filter_map(|program| match &program.outcome {
+			Ok(()) => None,
+			Err(e) => Some((program.steps.last().map(|step| step.ip), e)),
+		})
+	}
+
+	/// Every asset movement, including the deposits, as `(network, asset, from, to, amount)`.
+	pub fn transfers(&self) -> impl Iterator<Item = (NetworkId, AssetId, &[u8], &[u8], u128)> {
+		self.programs.iter().flat_map(|program| {
+			program
+				.deposits
+				.iter()
+				.chain(program.steps.iter().flat_map(|step| step.effects.iter()))
+				.filter_map(move |effect| match effect {
+					Effect::Transfer { asset, from, to, amount } =>
+						Some((program.network, *asset, from.as_slice(), to.as_slice(), *amount)),
+					_ => None,
+				})
+		})
+	}
+}